@@ -0,0 +1,194 @@
+//! Derive macros for [algen](https://docs.rs/algen). Enable the `derive`
+//! feature of algen rather than depending on this crate directly.
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, Data, DeriveInput, Expr, Fields, Index};
+
+/// Derive `algen::models::genome::Genome` for a struct or a fieldless
+/// enum.
+///
+/// Each field of a struct is a gene. Numeric fields need a range, given
+/// as `#[gene(range = 0.0..10.0)]`, and are drawn uniformly from it both
+/// when the genome is created and when the field mutates. Every other
+/// field must itself implement `Genome`, which covers `bool`, nested
+/// derived structs, and derived enums.
+///
+/// Crossover takes each gene from the first parent with probability
+/// `bias`, and mutation changes each gene with probability `rate`. Enums
+/// mutate by switching to a random variant.
+#[proc_macro_derive(Genome, attributes(gene))]
+pub fn derive_genome(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let result = match &input.data {
+        Data::Struct(data) => derive_struct(&input, &data.fields),
+        Data::Enum(data) => {
+            let variants = data
+                .variants
+                .iter()
+                .map(|variant| match variant.fields {
+                    Fields::Unit => Ok(&variant.ident),
+                    _ => Err(syn::Error::new_spanned(
+                        variant,
+                        "Genome can only be derived for enums without fields",
+                    )),
+                })
+                .collect::<syn::Result<Vec<_>>>();
+            variants.and_then(|variants| derive_enum(&input, &variants))
+        }
+        Data::Union(_) => Err(syn::Error::new_spanned(
+            &input.ident,
+            "Genome cannot be derived for unions",
+        )),
+    };
+
+    result
+        .unwrap_or_else(|error| error.to_compile_error())
+        .into()
+}
+
+fn derive_struct(input: &DeriveInput, fields: &Fields) -> syn::Result<TokenStream2> {
+    let mut random = Vec::new();
+    let mut crossover = Vec::new();
+    let mut mutate = Vec::new();
+
+    for (idx, field) in fields.iter().enumerate() {
+        let member = match &field.ident {
+            Some(ident) => quote!(#ident),
+            None => {
+                let idx = Index::from(idx);
+                quote!(#idx)
+            }
+        };
+        let ty = &field.ty;
+
+        match range(field)? {
+            Some(range) => {
+                random.push(quote! {
+                    #member: ::algen::__private::Rng::gen_range(rng, #range)
+                });
+                crossover.push(quote! {
+                    #member: if ::algen::__private::Rng::gen::<f32>(rng) < bias {
+                        ::core::clone::Clone::clone(&self.#member)
+                    } else {
+                        ::core::clone::Clone::clone(&other.#member)
+                    }
+                });
+                mutate.push(quote! {
+                    if ::algen::__private::Rng::gen::<f32>(rng) < rate {
+                        self.#member = ::algen::__private::Rng::gen_range(rng, #range);
+                    }
+                });
+            }
+            None => {
+                random.push(quote! {
+                    #member: <#ty as ::algen::models::genome::Genome>::random(rng)
+                });
+                crossover.push(quote! {
+                    #member: ::algen::models::genome::Genome::crossover(
+                        &self.#member, &other.#member, bias, rng
+                    )
+                });
+                mutate.push(quote! {
+                    ::algen::models::genome::Genome::mutate(&mut self.#member, rate, rng);
+                });
+            }
+        }
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let (random, crossover) = match fields {
+        Fields::Unit => (quote!(#name), quote!(#name)),
+        _ => (
+            quote!(#name { #(#random),* }),
+            quote!(#name { #(#crossover),* }),
+        ),
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::algen::models::genome::Genome for #name #ty_generics #where_clause {
+            fn random<R: ::algen::__private::Rng + ?Sized>(rng: &mut R) -> Self {
+                #random
+            }
+
+            fn crossover<R: ::algen::__private::Rng + ?Sized>(
+                &self,
+                other: &Self,
+                bias: f32,
+                rng: &mut R,
+            ) -> Self {
+                #crossover
+            }
+
+            fn mutate<R: ::algen::__private::Rng + ?Sized>(&mut self, rate: f32, rng: &mut R) {
+                #(#mutate)*
+            }
+        }
+    })
+}
+
+fn derive_enum(input: &DeriveInput, variants: &[&syn::Ident]) -> syn::Result<TokenStream2> {
+    if variants.is_empty() {
+        return Err(syn::Error::new_spanned(
+            &input.ident,
+            "Genome cannot be derived for enums without variants",
+        ));
+    }
+
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+    let count = variants.len();
+    let indices = 0..count;
+
+    Ok(quote! {
+        impl #impl_generics ::algen::models::genome::Genome for #name #ty_generics #where_clause {
+            fn random<R: ::algen::__private::Rng + ?Sized>(rng: &mut R) -> Self {
+                match ::algen::__private::Rng::gen_range(rng, 0..#count) {
+                    #(#indices => #name::#variants,)*
+                    _ => unreachable!(),
+                }
+            }
+
+            fn crossover<R: ::algen::__private::Rng + ?Sized>(
+                &self,
+                other: &Self,
+                bias: f32,
+                rng: &mut R,
+            ) -> Self {
+                if ::algen::__private::Rng::gen::<f32>(rng) < bias {
+                    ::core::clone::Clone::clone(self)
+                } else {
+                    ::core::clone::Clone::clone(other)
+                }
+            }
+
+            fn mutate<R: ::algen::__private::Rng + ?Sized>(&mut self, rate: f32, rng: &mut R) {
+                if ::algen::__private::Rng::gen::<f32>(rng) < rate {
+                    *self = <Self as ::algen::models::genome::Genome>::random(rng);
+                }
+            }
+        }
+    })
+}
+
+/// The value of a `#[gene(range = ...)]` attribute, if the field has one.
+fn range(field: &syn::Field) -> syn::Result<Option<Expr>> {
+    let mut range = None;
+    for attr in field
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("gene"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("range") {
+                range = Some(meta.value()?.parse::<Expr>()?);
+                Ok(())
+            } else {
+                Err(meta.error("expected `range = ...`"))
+            }
+        })?;
+    }
+
+    Ok(range)
+}
@@ -4,6 +4,7 @@ use algen::{
     models::{
         algorithm::Algorithm, analyzer::Analyzer, node::Node, test_parameters::TestParameters,
     },
+    operators::{mutate_each, uniform},
     run_algorithm,
 };
 use rand::prelude::*;
@@ -25,18 +26,14 @@ impl Algorithm<InputType, OutputType, Solution, FeatureFlags> for GeneticAlgorit
         _input: &InputType,
         _params: &TestParameters<FeatureFlags>,
     ) -> Node<Solution> {
-        let mut rng = rand::thread_rng();
+        let mut rng = algen::random::rng();
         let mut solution = Solution { shifts: [0; 13] };
 
         for idx in 0..13 {
             solution.shifts[idx] = rng.gen_range(-128..128);
         }
 
-        return Node {
-            id: 0,
-            score: f32::MIN,
-            solution: solution,
-        };
+        Node::new(solution)
     }
 
     fn combine_node(
@@ -45,28 +42,19 @@ impl Algorithm<InputType, OutputType, Solution, FeatureFlags> for GeneticAlgorit
         right: Node<Solution>,
         params: &TestParameters<FeatureFlags>,
     ) -> Node<Solution> {
-        let mut rng = rand::thread_rng();
-        let mut next_solution: InputType = [0; 13];
-
-        for i in 0..13 {
-            if rng.gen_bool(params.crossover_factor as f64) {
-                next_solution[i] = left.solution.shifts[i];
-            } else {
-                next_solution[i] = right.solution.shifts[i];
-            }
-
-            if rng.gen_bool(params.mutation_factor as f64) {
-                next_solution[i] = rng.gen_range(-128..128)
-            }
-        }
-
-        return Node {
-            id: 0,
-            score: f32::MIN,
-            solution: Solution {
-                shifts: next_solution,
-            },
-        };
+        let mut genes = uniform(
+            &left.solution.shifts,
+            &right.solution.shifts,
+            params.current_crossover_factor(),
+        );
+        mutate_each(&mut genes, params.current_mutation_factor(), |gene| {
+            *gene = algen::random::rng().gen_range(-128..128)
+        });
+        let next_solution: InputType = genes.try_into().unwrap();
+
+        Node::new(Solution {
+            shifts: next_solution,
+        })
     }
 
     fn output(
@@ -87,7 +75,7 @@ impl Algorithm<InputType, OutputType, Solution, FeatureFlags> for GeneticAlgorit
             }
         }
 
-        return String::from_str(std::str::from_utf8(&output.to_vec()).unwrap()).unwrap();
+        String::from_str(std::str::from_utf8(&output).unwrap()).unwrap()
     }
 }
 
@@ -104,7 +92,7 @@ impl Analyzer<InputType, OutputType, FeatureFlags> for GeneticAnalyzer {
             }
         }
 
-        return score;
+        score
     }
 }
 
@@ -118,6 +106,7 @@ fn main() {
         mutation_factor: 0.025,
         tournament_size: 10,
         feature_flag: Vec::new(),
+        ..Default::default()
     };
 
     let algo = GeneticAlgorithm {};
@@ -147,9 +136,5 @@ fn after_generation(_score: f32, _solution: &Solution, output: &OutputType) -> b
     let output_value = output;
     println!("{output_value}");
 
-    if output_value.eq("Hello, world!") {
-        return true;
-    } else {
-        return false;
-    }
+    output_value.eq("Hello, world!")
 }
@@ -0,0 +1,332 @@
+//! An Age-Layered Population Structure (ALPS) runner. Instead of one
+//! population, nodes live in layers based on how long their genetic
+//! material has been evolving. Each layer breeds with itself and the
+//! layer beneath it, old nodes are pushed upwards, and the bottom layer
+//! is regularly replaced with fresh random nodes. This keeps a steady
+//! supply of new genetic material flowing into the run so that it does
+//! not converge prematurely.
+use crate::parallel::*;
+use crate::{
+    breeding::breed,
+    math::constraints::PenaltyWeight,
+    math::scaling::apply_scaling,
+    math::selection::{compatible, Selector},
+    models::algen_result::AlgenResult,
+    models::algorithm::Algorithm,
+    models::alps::AlpsParameters,
+    models::analyzer::Analyzer,
+    models::node::Node,
+    models::statistics::GenerationStatistics,
+    models::test_parameters::TestParameters,
+    random::Seed,
+    scoring::{reward, score},
+    BREEDING, EVALUATION, NEWCOMERS,
+};
+
+#[cfg(feature = "tracing")]
+use tracing::{event, Level};
+
+/// Run a genetic algorithm using an Age-Layered Population Structure.
+/// This accepts the same types and parameters as `run_algorithm`, plus
+/// the `alps` parameters which describe the layers. The
+/// `params.population` is split evenly across the layers.
+pub fn run_alps<
+    InputData: Send + Sync,
+    OutputData: Clone + Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    alps: &AlpsParameters,
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
+) -> AlgenResult<OutputData, Solution> {
+    params.reject_loop_only("run_alps", algo.telemetry_sink());
+    let params = &params.for_run();
+    params.install(|| {
+        let seed = Seed::new(params.seed);
+        let _stream = seed.enter();
+        let weight = PenaltyWeight::new(&params.constraint_handling).weight();
+        let layer_count = alps.layers.max(1);
+        let layer_size = (params.population / layer_count).max(2);
+        let age_gap = alps.age_gap.max(1);
+
+        let mut best_score = f32::MIN;
+        let mut best_node: Option<Node<Solution>> = None;
+        let mut best_output: Option<OutputData> = None;
+
+        // Only the bottom layer starts populated, the rest fill up as nodes age
+        let allocate = || {
+            (0..layer_size)
+                .map(|_| algo.allocate_node(input_data, params))
+                .collect::<Vec<Node<Solution>>>()
+        };
+        let mut layers: Vec<Vec<Node<Solution>>> = vec![Vec::new(); layer_count];
+        layers[0] = allocate();
+
+        let evaluate = |node: &mut Node<Solution>| {
+            let outputs = algo.output(node, input_data, params);
+            score(algo, analyzer, node, &outputs, params, weight);
+            reward(algo, node);
+            outputs
+        };
+
+        let mut history = Vec::new();
+        for generation in 0..params.generations {
+            params.state.set_generation(generation);
+            let streams = seed.derive(generation as u64);
+
+            // Score every layer at once
+            let outputs = layers
+                .par_iter_mut()
+                .enumerate()
+                .flat_map(|(layer, nodes)| {
+                    let streams = streams.derive(EVALUATION).derive(layer as u64);
+                    nodes
+                        .par_iter_mut()
+                        .enumerate()
+                        .map(move |(idx, node)| (streams.derive(idx as u64), node))
+                })
+                .map(|(stream, node)| {
+                    let _stream = stream.enter();
+                    let output = evaluate(node);
+                    (node.score, output, node)
+                })
+                .collect::<Vec<(f32, OutputData, &mut Node<Solution>)>>();
+
+            for (score, output, node) in outputs {
+                if score > best_score {
+                    best_score = score;
+                    best_node = Some(node.clone());
+                    best_output = Some(output);
+                }
+            }
+            let scores: Vec<f32> = layers.iter().flatten().map(|node| node.score).collect();
+            history.push(GenerationStatistics::new(generation, &scores));
+
+            // Replace the bottom layer with fresh genetic material, giving the
+            // current bottom layer a chance to move up first
+            if generation > 0 && generation % age_gap == 0 {
+                let retired = std::mem::replace(&mut layers[0], allocate());
+                if layer_count > 1 {
+                    for node in retired {
+                        promote(node, &mut layers[1], layer_size);
+                    }
+                }
+                layers[0]
+                    .par_iter_mut()
+                    .enumerate()
+                    .for_each(|(idx, node)| {
+                        let _stream = streams.derive(NEWCOMERS).derive(idx as u64).enter();
+                        evaluate(node);
+                    });
+            }
+
+            // Move nodes which are too old for their layer up a layer
+            for layer in 0..layer_count - 1 {
+                let max_age = alps.max_age(layer);
+                let (young, old): (Vec<Node<Solution>>, Vec<Node<Solution>>) =
+                    std::mem::take(&mut layers[layer])
+                        .into_iter()
+                        .partition(|node| node.age <= max_age);
+                layers[layer] = young;
+                for node in old {
+                    promote(node, &mut layers[layer + 1], layer_size);
+                }
+            }
+
+            // Each layer breeds with itself and the layer beneath it
+            let next_layers = (0..layer_count)
+                .map(|layer| {
+                    let mut pool = layers[layer].clone();
+                    if layer > 0 {
+                        pool.extend(layers[layer - 1].iter().cloned());
+                    }
+                    let seed = streams.derive(BREEDING).derive(layer as u64);
+                    breed_layer(
+                        &layers[layer],
+                        pool,
+                        layer_size,
+                        params,
+                        algo,
+                        generation,
+                        seed,
+                    )
+                })
+                .collect::<Vec<Vec<Node<Solution>>>>();
+            layers = next_layers;
+
+            for node in layers.iter_mut().flatten() {
+                node.age += 1;
+            }
+
+            #[cfg(feature = "tracing")]
+            event!(
+                Level::INFO,
+                msg = "Generation finished processing",
+                generation = generation,
+                score = best_score
+            );
+
+            if let (Some(func), Some(output), Some(node)) =
+                (on_generation_complete, &best_output, &best_node)
+            {
+                if func(best_score, &node.solution, output) {
+                    break;
+                }
+            }
+        }
+
+        AlgenResult {
+            score: best_score,
+            output: best_output,
+            node: best_node,
+            pareto_front: Vec::new(),
+            archive: Vec::new(),
+            quality_diversity: Vec::new(),
+            hypervolume: Vec::new(),
+            history,
+            genealogy: Vec::new(),
+        }
+    })
+}
+
+/// Create the next generation of a single layer. The layer's elites are
+/// kept and the rest is filled with offspring of parents drawn from the
+/// breeding pool. Offspring inherit the age of their oldest parent, and
+/// each is bred from its own stream of `seed`.
+fn breed_layer<InputData, OutputData, Solution, FeatureFlags>(
+    layer: &[Node<Solution>],
+    mut pool: Vec<Node<Solution>>,
+    layer_size: usize,
+    params: &TestParameters<FeatureFlags>,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    generation: usize,
+    seed: Seed,
+) -> Vec<Node<Solution>>
+where
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Send + Sync,
+{
+    // Layers are only populated by nodes aging into them
+    if layer.is_empty() {
+        return Vec::new();
+    }
+
+    pool.sort_by(|left, right| right.score.total_cmp(&left.score));
+    let raw_scores: Vec<f32> = pool.iter().map(|node| node.score).collect();
+    apply_scaling(pool.as_mut_slice(), &params.scaling);
+
+    let mut elites = layer.to_vec();
+    elites.sort_by(|left, right| right.score.total_cmp(&left.score));
+    elites.truncate((params.elitism_factor * layer_size as f32) as usize);
+
+    let selector = Selector::new(pool.as_slice(), params, generation);
+    let offspring = (0..layer_size.saturating_sub(elites.len()))
+        .into_par_iter()
+        .filter_map(|idx| {
+            let _stream = seed.derive(idx as u64).enter();
+            selector
+                .select_pair(pool.as_slice(), params, |left, right| {
+                    compatible(params, algo.distance(), left, right)
+                })
+                .map(|(left, right)| {
+                    let age = pool[left].age.max(pool[right].age);
+                    let mut child = breed(algo, pool[left].clone(), pool[right].clone(), params);
+                    // Offspring are judged against their parents' raw scores
+                    child.parent_score = raw_scores[left].max(raw_scores[right]);
+                    child.age = age;
+                    child
+                })
+        })
+        .collect::<Vec<Node<Solution>>>();
+
+    elites.extend(offspring);
+    elites
+}
+
+/// Move a node into a layer. If the layer is full, the node replaces the
+/// worst node in the layer as long as it scores at least as well.
+fn promote<Solution>(node: Node<Solution>, layer: &mut Vec<Node<Solution>>, capacity: usize) {
+    if layer.len() < capacity {
+        layer.push(node);
+        return;
+    }
+
+    let worst = layer
+        .iter()
+        .enumerate()
+        .min_by(|(_, left), (_, right)| left.score.total_cmp(&right.score))
+        .map(|(idx, _)| idx);
+
+    if let Some(worst) = worst {
+        if node.score >= layer[worst].score {
+            layer[worst] = node;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn max_age_grows_with_each_layer() {
+        let alps = AlpsParameters {
+            layers: 4,
+            age_gap: 5,
+            ..Default::default()
+        };
+
+        assert_eq!(alps.max_age(0), 5);
+        assert_eq!(alps.max_age(1), 10);
+        assert_eq!(alps.max_age(2), 20);
+        assert_eq!(alps.max_age(3), usize::MAX);
+    }
+
+    #[test]
+    fn promotion_replaces_the_worst_node() {
+        let mut layer: Vec<Node<usize>> = (0..3)
+            .map(|x| Node {
+                score: x as f32,
+                ..Node::new(x)
+            })
+            .collect();
+
+        promote(
+            Node {
+                score: 1.5,
+                ..Node::new(9)
+            },
+            &mut layer,
+            3,
+        );
+
+        let solutions: Vec<usize> = layer.iter().map(|node| node.solution).collect();
+        assert_eq!(solutions, vec![9, 1, 2]);
+    }
+
+    #[test]
+    fn alps_works() {
+        let alps = AlpsParameters {
+            layers: 3,
+            age_gap: 5,
+            ..Default::default()
+        };
+        let result = run_alps(
+            &parameters(),
+            &alps,
+            &(),
+            &CountOnes {},
+            &CountOnesAnalyzer {},
+            None,
+        );
+        assert!(result.score >= 12.0);
+    }
+}
@@ -0,0 +1,24 @@
+//! Classic problems with known optima, for checking that operators and
+//! settings actually work and for measuring the runner. Each problem is
+//! an analyzer which also hands out the ready made genome it is solved
+//! with, so a benchmark runs with nothing more than:
+//!
+//! ```
+//! use algen::{benchmarks::one_max::OneMax, models::test_parameters::TestParameters};
+//!
+//! let problem = OneMax { length: 32 };
+//! let params = TestParameters::<()> {
+//!     generations: 20,
+//!     population: 100,
+//!     ..Default::default()
+//! };
+//! let result = algen::run_algorithm(&params, &(), &problem.algorithm(), &problem, None);
+//! assert!(result.score <= problem.optimum());
+//! ```
+//!
+//! Scores are always higher is better, so minimization problems are
+//! scored as the negated cost and their optimum is at or below zero.
+pub mod functions;
+pub mod knapsack;
+pub mod one_max;
+pub mod tsp;
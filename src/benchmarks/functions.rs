@@ -0,0 +1,106 @@
+//! Continuous test functions over real vectors, all with their minimum
+//! of zero at the origin and scored as the negated function value.
+use crate::{
+    genomes::real_vector::RealVector,
+    models::{analyzer::Analyzer, test_parameters::TestParameters},
+};
+use std::f64::consts::{E, PI};
+
+/// The sum of squares, inside `[-5.12, 5.12]` in every dimension. This is
+/// the easiest of the functions, with a single smooth bowl.
+#[derive(Clone, Copy, Debug)]
+pub struct Sphere {
+    pub dimensions: usize,
+}
+
+/// A sphere covered in a regular grid of local minima, inside
+/// `[-5.12, 5.12]` in every dimension. It punishes runs which converge
+/// too early.
+#[derive(Clone, Copy, Debug)]
+pub struct Rastrigin {
+    pub dimensions: usize,
+}
+
+/// A nearly flat outer region with a deep hole in the middle, inside
+/// `[-32.768, 32.768]` in every dimension.
+#[derive(Clone, Copy, Debug)]
+pub struct Ackley {
+    pub dimensions: usize,
+}
+
+macro_rules! test_function {
+    ($name:ident, $bound:expr, |$genes:ident| $value:expr) => {
+        impl $name {
+            pub fn algorithm(&self) -> RealVector {
+                RealVector::new(vec![(-$bound, $bound); self.dimensions])
+            }
+
+            pub fn optimum(&self) -> f32 {
+                0.0
+            }
+        }
+
+        impl<InputData, FeatureFlags> Analyzer<InputData, Vec<f64>, FeatureFlags> for $name {
+            fn evaluate(&self, attempt: &Vec<f64>, _params: &TestParameters<FeatureFlags>) -> f32 {
+                let $genes = attempt.as_slice();
+                -($value) as f32
+            }
+        }
+    };
+}
+
+test_function!(Sphere, 5.12, |genes| genes
+    .iter()
+    .map(|x| x * x)
+    .sum::<f64>());
+
+test_function!(Rastrigin, 5.12, |genes| {
+    10.0 * genes.len() as f64
+        + genes
+            .iter()
+            .map(|x| x * x - 10.0 * (2.0 * PI * x).cos())
+            .sum::<f64>()
+});
+
+test_function!(Ackley, 32.768, |genes| {
+    let n = genes.len().max(1) as f64;
+    let squares = genes.iter().map(|x| x * x).sum::<f64>() / n;
+    let cosines = genes.iter().map(|x| (2.0 * PI * x).cos()).sum::<f64>() / n;
+    -20.0 * (-0.2 * squares.sqrt()).exp() - cosines.exp() + 20.0 + E
+});
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_algorithm;
+
+    #[test]
+    fn functions_are_zero_at_the_origin() {
+        let params = TestParameters::<()>::default();
+        let origin = vec![0.0; 4];
+        let score = |analyzer: &dyn Analyzer<(), Vec<f64>, ()>, genes: &Vec<f64>| {
+            analyzer.evaluate(genes, &params)
+        };
+        for analyzer in [
+            &Sphere { dimensions: 4 } as &dyn Analyzer<(), Vec<f64>, ()>,
+            &Rastrigin { dimensions: 4 },
+            &Ackley { dimensions: 4 },
+        ] {
+            assert!(score(analyzer, &origin).abs() < 1e-5);
+            assert!(score(analyzer, &vec![1.0; 4]) < -1.0);
+        }
+    }
+
+    #[test]
+    fn sphere_is_solved() {
+        let problem = Sphere { dimensions: 4 };
+        let params = TestParameters::<()> {
+            generations: 100,
+            population: 100,
+            mutation_factor: 0.25,
+            ..Default::default()
+        };
+        let result = run_algorithm(&params, &(), &problem.algorithm(), &problem, None);
+        assert!(result.score > problem.optimum() - 0.1);
+    }
+}
@@ -0,0 +1,81 @@
+use crate::{
+    genomes::bit_string::BitString,
+    models::{analyzer::Analyzer, test_parameters::TestParameters},
+};
+
+/// Pick the items, each with a `(weight, value)`, which are worth the
+/// most without weighing more than `capacity`. A feasible selection
+/// scores its total value, and an overweight one scores how far it is
+/// overweight, negated, so it always loses to a feasible one. The excess
+/// is also reported as the violation for runs which handle constraints.
+#[derive(Clone, Debug)]
+pub struct Knapsack {
+    pub items: Vec<(f32, f32)>,
+    pub capacity: f32,
+    /// The best total value, when it is known.
+    pub optimum: Option<f32>,
+}
+
+impl Knapsack {
+    /// Ten items and a capacity of 165, whose best selection is worth
+    /// 309. This is the P01 instance from John Burkardt's test set.
+    pub fn p01() -> Self {
+        let weights = [23.0, 31.0, 29.0, 44.0, 53.0, 38.0, 63.0, 85.0, 89.0, 82.0];
+        let values = [92.0, 57.0, 49.0, 68.0, 60.0, 43.0, 67.0, 84.0, 87.0, 72.0];
+        Knapsack {
+            items: weights.into_iter().zip(values).collect(),
+            capacity: 165.0,
+            optimum: Some(309.0),
+        }
+    }
+
+    pub fn algorithm(&self) -> BitString {
+        BitString::new(self.items.len())
+    }
+
+    /// The total `(weight, value)` of the selected items.
+    pub fn totals(&self, selected: &[bool]) -> (f32, f32) {
+        self.items
+            .iter()
+            .zip(selected)
+            .filter(|(_, selected)| **selected)
+            .fold((0.0, 0.0), |(weight, value), (item, _)| {
+                (weight + item.0, value + item.1)
+            })
+    }
+}
+
+impl<InputData, FeatureFlags> Analyzer<InputData, Vec<bool>, FeatureFlags> for Knapsack {
+    fn evaluate(&self, attempt: &Vec<bool>, _params: &TestParameters<FeatureFlags>) -> f32 {
+        let (weight, value) = self.totals(attempt);
+        if weight <= self.capacity {
+            value
+        } else {
+            self.capacity - weight
+        }
+    }
+
+    fn violation(&self, attempt: &Vec<bool>, _params: &TestParameters<FeatureFlags>) -> f32 {
+        (self.totals(attempt).0 - self.capacity).max(0.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_algorithm;
+
+    #[test]
+    fn p01_is_solved() {
+        let problem = Knapsack::p01();
+        let params = TestParameters::<()> {
+            generations: 50,
+            population: 100,
+            mutation_factor: 0.1,
+            ..Default::default()
+        };
+        let result = run_algorithm(&params, &(), &problem.algorithm(), &problem, None);
+        assert_eq!(Some(result.score), problem.optimum);
+        assert_eq!(result.node.unwrap().violation, 0.0);
+    }
+}
@@ -0,0 +1,45 @@
+use crate::{
+    genomes::bit_string::BitString,
+    models::{analyzer::Analyzer, test_parameters::TestParameters},
+};
+
+/// Count the ones in a bit string. The optimum is a string of nothing but
+/// ones.
+#[derive(Clone, Copy, Debug)]
+pub struct OneMax {
+    pub length: usize,
+}
+
+impl OneMax {
+    pub fn algorithm(&self) -> BitString {
+        BitString::new(self.length)
+    }
+
+    pub fn optimum(&self) -> f32 {
+        self.length as f32
+    }
+}
+
+impl<InputData, FeatureFlags> Analyzer<InputData, Vec<bool>, FeatureFlags> for OneMax {
+    fn evaluate(&self, attempt: &Vec<bool>, _params: &TestParameters<FeatureFlags>) -> f32 {
+        attempt.iter().filter(|bit| **bit).count() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_algorithm;
+
+    #[test]
+    fn one_max_is_solved() {
+        let problem = OneMax { length: 32 };
+        let params = TestParameters::<()> {
+            generations: 60,
+            population: 100,
+            ..Default::default()
+        };
+        let result = run_algorithm(&params, &(), &problem.algorithm(), &problem, None);
+        assert_eq!(result.score, problem.optimum());
+    }
+}
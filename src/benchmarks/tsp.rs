@@ -0,0 +1,85 @@
+use crate::{
+    genomes::permutation::Permutation,
+    models::{analyzer::Analyzer, test_parameters::TestParameters},
+};
+use std::f64::consts::PI;
+
+/// Visit every city once and return to the start along the shortest
+/// tour. A tour is a permutation of the cities, scored as its length,
+/// negated.
+#[derive(Clone, Debug)]
+pub struct Tsp {
+    pub cities: Vec<(f64, f64)>,
+    /// The length of the shortest tour, negated, when it is known.
+    pub optimum: Option<f32>,
+}
+
+impl Tsp {
+    /// `count` cities evenly spaced around a unit circle, shuffled so
+    /// that the identity permutation isn't the answer. The shortest tour
+    /// goes around the circle.
+    pub fn circle(count: usize) -> Self {
+        // A fixed stride visits every city when it shares no factor with
+        // the count, which scrambles the order without any randomness
+        let stride = (count / 2..count)
+            .find(|stride| gcd(*stride, count) == 1)
+            .unwrap_or(1);
+        let cities = (0..count)
+            .map(|idx| {
+                let angle = 2.0 * PI * ((idx * stride) % count.max(1)) as f64 / count as f64;
+                (angle.cos(), angle.sin())
+            })
+            .collect();
+        Tsp {
+            cities,
+            optimum: Some(-(2.0 * count as f64 * (PI / count as f64).sin()) as f32),
+        }
+    }
+
+    pub fn algorithm(&self) -> Permutation {
+        Permutation::new(self.cities.len())
+    }
+
+    /// The length of the closed tour which visits the cities in order.
+    pub fn length(&self, tour: &[usize]) -> f64 {
+        let stops = tour.iter().map(|city| self.cities[*city]);
+        stops
+            .clone()
+            .zip(stops.cycle().skip(1))
+            .map(|(from, to)| ((to.0 - from.0).powi(2) + (to.1 - from.1).powi(2)).sqrt())
+            .sum()
+    }
+}
+
+fn gcd(left: usize, right: usize) -> usize {
+    if right == 0 {
+        left
+    } else {
+        gcd(right, left % right)
+    }
+}
+
+impl<InputData, FeatureFlags> Analyzer<InputData, Vec<usize>, FeatureFlags> for Tsp {
+    fn evaluate(&self, attempt: &Vec<usize>, _params: &TestParameters<FeatureFlags>) -> f32 {
+        -self.length(attempt) as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_algorithm;
+
+    #[test]
+    fn small_circles_are_solved() {
+        let problem = Tsp::circle(8);
+        let params = TestParameters::<()> {
+            generations: 100,
+            population: 200,
+            mutation_factor: 0.2,
+            ..Default::default()
+        };
+        let result = run_algorithm(&params, &(), &problem.algorithm(), &problem, None);
+        assert!(result.score >= problem.optimum.unwrap() - 1e-4);
+    }
+}
@@ -0,0 +1,218 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::float::Float;
+use crate::models::{algorithm::Algorithm, node::Node, test_parameters::TestParameters};
+use alloc::collections::BTreeSet;
+use alloc::vec::Vec;
+use rand::seq::index::sample;
+
+/// How many mutants of a duplicate are tried before it is replaced by a
+/// fresh random node instead.
+const MUTANT_ATTEMPTS: usize = 8;
+
+/// Create a single offspring from two parents. Every runner creates
+/// offspring through here so that the steps which follow recombination,
+/// such as repair, are applied consistently. When the algorithm has an
+/// operator bandit, the bandit picks the operator and the child records
+/// which one it was. Offspring always start out unscored.
+pub fn breed<InputData, OutputData, Solution, FeatureFlags>(
+    algo: &impl Algorithm<InputData, OutputData, Solution, FeatureFlags>,
+    left: Node<Solution>,
+    right: Node<Solution>,
+    params: &TestParameters<FeatureFlags>,
+) -> Node<Solution>
+where
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+{
+    let parent_score = left.score.max(right.score);
+    let parent_ids = [left.id, right.id];
+    let mut child = match algo.operator_selection() {
+        Some(bandit) => {
+            let operator = bandit.select();
+            let mut child = algo.combine_with_operator(operator, left, right, params);
+            child.operator = Some(operator);
+            child
+        }
+        None => algo.combine_node(left, right, params),
+    };
+    finish(algo, &mut child, parent_score, &parent_ids, params);
+    child
+}
+
+/// Create a single offspring from any number of parents with
+/// `Algorithm::combine_nodes`. The child is finished the same way as
+/// one from `breed`.
+pub fn breed_parents<InputData, OutputData, Solution, FeatureFlags>(
+    algo: &impl Algorithm<InputData, OutputData, Solution, FeatureFlags>,
+    parents: &[Node<Solution>],
+    params: &TestParameters<FeatureFlags>,
+) -> Node<Solution>
+where
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+{
+    let parent_score = parents
+        .iter()
+        .map(|parent| parent.score)
+        .fold(f32::MIN, f32::max);
+    let parent_ids: Vec<usize> = parents.iter().map(|parent| parent.id).collect();
+    let mut child = algo.combine_nodes(parents, params);
+    finish(algo, &mut child, parent_score, &parent_ids, params);
+    child
+}
+
+fn finish<InputData, OutputData, Solution, FeatureFlags>(
+    algo: &impl Algorithm<InputData, OutputData, Solution, FeatureFlags>,
+    child: &mut Node<Solution>,
+    parent_score: f32,
+    parent_ids: &[usize],
+    params: &TestParameters<FeatureFlags>,
+) where
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+{
+    algo.repair(&mut child.solution, params);
+    child.score = f32::MIN;
+    child.evaluated = false;
+    child.learned_score = None;
+    child.parent_score = parent_score;
+    // The runner gives the child its own id once it is evaluated
+    child.id = 0;
+    if params.track_lineage {
+        child.parents = parent_ids.to_vec();
+    }
+}
+
+/// Replace every node whose genome has already been seen earlier in the
+/// population. Earlier nodes win, so elites at the front are kept. Each
+/// duplicate is replaced by a mutant of itself, or a random node if no
+/// unseen mutant turns up. Does nothing unless the algorithm provides a
+/// fingerprint.
+pub fn deduplicate<InputData, OutputData, Solution, FeatureFlags>(
+    algo: &impl Algorithm<InputData, OutputData, Solution, FeatureFlags>,
+    nodes: &mut [Node<Solution>],
+    input: &InputData,
+    params: &TestParameters<FeatureFlags>,
+) where
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+{
+    let mut seen = BTreeSet::new();
+    for node in nodes.iter_mut() {
+        let Some(print) = algo.fingerprint(&node.solution) else {
+            return;
+        };
+        if seen.insert(print) {
+            continue;
+        }
+
+        let mutant = (0..MUTANT_ATTEMPTS)
+            .map(|_| breed(algo, node.clone(), node.clone(), params))
+            .find(|mutant| {
+                algo.fingerprint(&mutant.solution)
+                    .is_some_and(|print| !seen.contains(&print))
+            });
+        *node = mutant.unwrap_or_else(|| algo.allocate_node(input, params));
+        if let Some(print) = algo.fingerprint(&node.solution) {
+            seen.insert(print);
+        }
+    }
+}
+
+/// Replace a random `rate` of the nodes with freshly allocated ones. The
+/// best scoring node is never replaced.
+pub fn immigrate<InputData, OutputData, Solution, FeatureFlags>(
+    algo: &impl Algorithm<InputData, OutputData, Solution, FeatureFlags>,
+    nodes: &mut [Node<Solution>],
+    rate: f32,
+    input: &InputData,
+    params: &TestParameters<FeatureFlags>,
+) where
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+{
+    let best = (0..nodes.len())
+        .max_by(|left, right| nodes[*left].score.total_cmp(&nodes[*right].score))
+        .unwrap_or(0);
+    let candidates = nodes.len().saturating_sub(1);
+    let count = ((rate.clamp(0.0, 1.0) * nodes.len() as f32).round() as usize).min(candidates);
+
+    for idx in sample(&mut crate::random::rng(), candidates, count) {
+        // Skip over the best node
+        let idx = if idx >= best { idx + 1 } else { idx };
+        nodes[idx] = algo.allocate_node(input, params);
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::models::algorithm::fingerprint;
+
+    /// Offspring are always a copy of the left parent, so duplicates can
+    /// only be replaced by random nodes.
+    struct Cloning {}
+    impl Algorithm<(), usize, usize, ()> for Cloning {
+        fn output(&self, node: &Node<usize>, _input: &(), _params: &TestParameters<()>) -> usize {
+            node.solution
+        }
+
+        fn allocate_node(&self, _input: &(), _params: &TestParameters<()>) -> Node<usize> {
+            Node::new(rand::random::<usize>())
+        }
+
+        fn combine_node(
+            &self,
+            left: Node<usize>,
+            _right: Node<usize>,
+            _params: &TestParameters<()>,
+        ) -> Node<usize> {
+            left
+        }
+
+        fn fingerprint(&self, solution: &usize) -> Option<u64> {
+            Some(fingerprint(solution))
+        }
+    }
+
+    #[test]
+    fn duplicates_are_replaced() {
+        let mut nodes: Vec<Node<usize>> = [1, 2, 1, 1, 3].into_iter().map(Node::new).collect();
+        deduplicate(&Cloning {}, &mut nodes, &(), &TestParameters::default());
+
+        let unique: BTreeSet<usize> = nodes.iter().map(|node| node.solution).collect();
+        assert_eq!(unique.len(), 5);
+        assert_eq!(nodes[0].solution, 1);
+        assert_eq!(nodes[1].solution, 2);
+        assert_eq!(nodes[4].solution, 3);
+    }
+
+    #[test]
+    fn immigrants_spare_the_best() {
+        let mut nodes: Vec<Node<usize>> = (0..10)
+            .map(|idx| {
+                let mut node = Node::new(idx);
+                node.score = idx as f32;
+                node
+            })
+            .collect();
+        immigrate(
+            &Cloning {},
+            &mut nodes,
+            1.0,
+            &(),
+            &TestParameters::default(),
+        );
+
+        assert_eq!(nodes[9].solution, 9);
+        assert_eq!(
+            nodes.iter().filter(|node| node.score == f32::MIN).count(),
+            9
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! A cellular genetic algorithm runner. Nodes live on a wrapping 2D grid
+//! and each cell only mates with nodes in its local neighborhood. Good
+//! solutions spread slowly across the grid, which preserves diversity
+//! far better than a panmictic population while every cell can still be
+//! processed in parallel.
+use crate::parallel::*;
+use crate::{
+    breeding::breed,
+    math::constraints::{compare, PenaltyWeight},
+    math::selection::tournament,
+    models::algen_result::AlgenResult,
+    models::algorithm::Algorithm,
+    models::analyzer::Analyzer,
+    models::cellular::{CellularParameters, LocalReplacement},
+    models::node::Node,
+    models::statistics::GenerationStatistics,
+    models::test_parameters::TestParameters,
+    random::Seed,
+    scoring::{reward, score},
+    BREEDING, EVALUATION,
+};
+
+#[cfg(feature = "tracing")]
+use tracing::{event, Level};
+
+/// Run a cellular genetic algorithm. This accepts the same types and
+/// parameters as `run_algorithm`, plus the `cellular` parameters which
+/// describe the grid. Every generation, each cell picks two parents from
+/// its neighborhood via tournament selection, and the offspring replaces
+/// a node in that neighborhood according to `cellular.replacement`.
+pub fn run_cellular<
+    InputData: Send + Sync,
+    OutputData: Clone + Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    cellular: &CellularParameters,
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
+) -> AlgenResult<OutputData, Solution> {
+    params.reject_loop_only("run_cellular", algo.telemetry_sink());
+    let params = &params.for_run();
+    params.install(|| {
+        let seed = Seed::new(params.seed);
+        let _stream = seed.enter();
+        let weight = PenaltyWeight::new(&params.constraint_handling).weight();
+        let cells = cellular.width * cellular.height;
+        let neighborhoods: Vec<Vec<usize>> =
+            (0..cells).map(|cell| cellular.neighbors(cell)).collect();
+
+        let mut best_score = f32::MIN;
+        let mut best_node: Option<Node<Solution>> = None;
+        let mut best_output: Option<OutputData> = None;
+
+        let evaluate = |node: &mut Node<Solution>| {
+            let outputs = algo.output(node, input_data, params);
+            score(algo, analyzer, node, &outputs, params, weight);
+            reward(algo, node);
+            outputs
+        };
+
+        let mut grid: Vec<Node<Solution>> = (0..cells)
+            .map(|_| algo.allocate_node(input_data, params))
+            .collect();
+        let mut outputs: Vec<Option<OutputData>> = grid
+            .par_iter_mut()
+            .enumerate()
+            .map(|(idx, node)| {
+                let _stream = seed.derive(EVALUATION).derive(idx as u64).enter();
+                Some(evaluate(node))
+            })
+            .collect();
+
+        let mut history = Vec::new();
+        for generation in 0..params.generations {
+            params.state.set_generation(generation);
+            let streams = seed.derive(generation as u64);
+            let scores: Vec<f32> = grid.iter().map(|node| node.score).collect();
+            history.push(GenerationStatistics::new(generation, &scores));
+
+            for (node, output) in grid.iter().zip(outputs.iter_mut()) {
+                if node.score > best_score {
+                    if let Some(output) = output.take() {
+                        best_score = node.score;
+                        best_node = Some(node.clone());
+                        best_output = Some(output);
+                    }
+                }
+            }
+
+            // Every cell breeds within its neighborhood, synchronously
+            let offspring = neighborhoods
+                .par_iter()
+                .enumerate()
+                .map(|(cell, neighbors)| {
+                    let _stream = streams.derive(BREEDING).derive(cell as u64).enter();
+                    let left = neighborhood_tournament(&grid, neighbors, params);
+                    let right = neighborhood_tournament(&grid, neighbors, params);
+                    let mut child = breed(algo, grid[left].clone(), grid[right].clone(), params);
+                    let output = evaluate(&mut child);
+
+                    let target = match cellular.replacement {
+                        LocalReplacement::IfNotWorse | LocalReplacement::Always => cell,
+                        LocalReplacement::WorstNeighbor => neighbors
+                            .iter()
+                            .copied()
+                            .min_by(|left, right| {
+                                compare(&grid[*left], &grid[*right], &params.constraint_handling)
+                            })
+                            .unwrap_or(cell),
+                    };
+                    (target, child, output)
+                })
+                .collect::<Vec<(usize, Node<Solution>, OutputData)>>();
+
+            // Each cell's output has already been compared with the best
+            outputs.iter_mut().for_each(|output| *output = None);
+            for (target, child, output) in offspring {
+                let always = matches!(cellular.replacement, LocalReplacement::Always);
+                if always || compare(&child, &grid[target], &params.constraint_handling).is_ge() {
+                    grid[target] = child;
+                    outputs[target] = Some(output);
+                }
+            }
+
+            #[cfg(feature = "tracing")]
+            event!(
+                Level::INFO,
+                msg = "Generation finished processing",
+                generation = generation,
+                score = best_score
+            );
+
+            if let (Some(func), Some(output), Some(node)) =
+                (on_generation_complete, &best_output, &best_node)
+            {
+                if func(best_score, &node.solution, output) {
+                    break;
+                }
+            }
+        }
+
+        // Offspring from the final generation have not been compared yet
+        for (node, output) in grid.into_iter().zip(outputs) {
+            if let (true, Some(output)) = (node.score > best_score, output) {
+                best_score = node.score;
+                best_node = Some(node);
+                best_output = Some(output);
+            }
+        }
+
+        AlgenResult {
+            score: best_score,
+            output: best_output,
+            node: best_node,
+            pareto_front: Vec::new(),
+            archive: Vec::new(),
+            quality_diversity: Vec::new(),
+            hypervolume: Vec::new(),
+            history,
+            genealogy: Vec::new(),
+        }
+    })
+}
+
+/// Tournament selection restricted to a set of neighbors, run the same
+/// way as everywhere else. Returns the index of the winning cell.
+fn neighborhood_tournament<Solution, FeatureFlags>(
+    grid: &[Node<Solution>],
+    neighbors: &[usize],
+    params: &TestParameters<FeatureFlags>,
+) -> usize {
+    let winner = tournament(neighbors.len(), params, |left, right| {
+        compare(
+            &grid[neighbors[left]],
+            &grid[neighbors[right]],
+            &params.constraint_handling,
+        )
+    });
+    // Every cell is in its own neighborhood, so there is always a winner
+    neighbors[winner.unwrap_or(0)]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+    use crate::models::cellular::{CellularParameters, LocalReplacement, Neighborhood};
+
+    #[test]
+    fn neighbors_wrap_around_the_grid() {
+        let cellular = CellularParameters {
+            width: 3,
+            height: 3,
+            neighborhood: Neighborhood::VonNeumann,
+            replacement: LocalReplacement::default(),
+        };
+
+        let mut neighbors = cellular.neighbors(0);
+        neighbors.sort();
+        assert_eq!(neighbors, vec![0, 1, 2, 3, 6]);
+    }
+
+    #[test]
+    fn neighborhoods_have_their_shapes() {
+        let sizes = [
+            (Neighborhood::VonNeumann, 5),
+            (Neighborhood::Moore, 9),
+            (Neighborhood::Linear { radius: 2 }, 9),
+            (Neighborhood::Diamond { radius: 2 }, 13),
+            (Neighborhood::Compact { radius: 2 }, 25),
+        ];
+        for (neighborhood, size) in sizes {
+            assert_eq!(neighborhood.offsets().len(), size);
+        }
+    }
+
+    #[test]
+    fn cellular_works() {
+        let cellular = CellularParameters {
+            width: 10,
+            height: 10,
+            ..Default::default()
+        };
+        let result = run_cellular(
+            &parameters(),
+            &cellular,
+            &(),
+            &CountOnes {},
+            &CountOnesAnalyzer {},
+            None,
+        );
+        assert!(result.score >= 12.0);
+
+        let cellular = CellularParameters {
+            neighborhood: Neighborhood::Diamond { radius: 2 },
+            replacement: LocalReplacement::WorstNeighbor,
+            ..cellular
+        };
+        let result = run_cellular(
+            &parameters(),
+            &cellular,
+            &(),
+            &CountOnes {},
+            &CountOnesAnalyzer {},
+            None,
+        );
+        assert!(result.score >= 12.0);
+    }
+
+    #[test]
+    fn neighborhood_tournaments_follow_the_parameters() {
+        let mut infeasible = Node::new(());
+        infeasible.score = 10.0;
+        infeasible.violation = 1.0;
+        let mut feasible = Node::new(());
+        feasible.score = 5.0;
+        let grid = [infeasible, feasible];
+        let neighbors = [0, 1];
+
+        let params = TestParameters {
+            constraint_handling: crate::models::constraint::ConstraintHandling::FeasibilityFirst,
+            tournament_without_replacement: true,
+            ..parameters()
+        };
+        assert_eq!(neighborhood_tournament(&grid, &neighbors, &params), 1);
+
+        // The worst contestant takes every tournament nobody wins
+        let params = TestParameters {
+            tournament_win_probability: 0.0,
+            ..params
+        };
+        assert_eq!(neighborhood_tournament(&grid, &neighbors, &params), 0);
+    }
+}
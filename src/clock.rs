@@ -0,0 +1,41 @@
+//! The clock the runners time themselves with. WebAssembly without WASI
+//! has no clock in std, and `std::time::Instant::now` panics there, so
+//! on that target every stopwatch reads zero and deadlines never pass.
+
+#[cfg(not(all(target_family = "wasm", target_os = "unknown")))]
+pub use std::time::Instant;
+
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+pub use stopped::Instant;
+
+#[cfg(all(target_family = "wasm", target_os = "unknown"))]
+mod stopped {
+    use std::{ops::Add, time::Duration};
+
+    /// A moment on a clock which never moves.
+    #[derive(Clone, Copy, Debug)]
+    pub struct Instant;
+
+    impl Instant {
+        pub fn now() -> Self {
+            Instant
+        }
+
+        pub fn elapsed(&self) -> Duration {
+            Duration::ZERO
+        }
+
+        /// Without a clock, no deadline can be known to have passed.
+        pub fn saturating_duration_since(&self, _earlier: Instant) -> Duration {
+            Duration::MAX
+        }
+    }
+
+    impl Add<Duration> for Instant {
+        type Output = Instant;
+
+        fn add(self, _duration: Duration) -> Instant {
+            self
+        }
+    }
+}
@@ -0,0 +1,350 @@
+//! A competitive coevolution runner. Two populations, hosts and
+//! parasites, are scored by playing against each other rather than
+//! against a fixed analyzer, so each population is always tested at the
+//! level of its opponents. As one side improves the other must keep up,
+//! which drives an arms race where a fixed fitness function would be
+//! impossible to write.
+use crate::parallel::*;
+use crate::{
+    islands::evolve,
+    models::algen_result::AlgenResult,
+    models::algorithm::Algorithm,
+    models::competitive::{CompetitiveParameters, Game},
+    models::node::Node,
+    models::test_parameters::TestParameters,
+    random::Seed,
+    scoring::reward,
+    BREEDING, EVALUATION, GAMES,
+};
+use rand::prelude::*;
+
+#[cfg(feature = "tracing")]
+use tracing::{event, Level};
+
+/// Run competitive coevolution between hosts and parasites. Each
+/// population holds `params.population` nodes, and every node scores
+/// its mean payoff over games against sampled opponents from the other
+/// population and from its hall of fame.
+///
+/// Scores are only meaningful against the opponents of the moment, so
+/// each result holds the champion of the final generation rather than
+/// the best score ever seen, and its `archive` holds the hall of fame,
+/// oldest first.
+pub fn run_competitive<
+    InputData: Send + Sync,
+    HostOutput: Clone + Send + Sync,
+    Host: Clone + Send + Sync,
+    ParasiteOutput: Clone + Send + Sync,
+    Parasite: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    competitive: &CompetitiveParameters,
+    input_data: &InputData,
+    hosts: &(impl Algorithm<InputData, HostOutput, Host, FeatureFlags> + Sync),
+    parasites: &(impl Algorithm<InputData, ParasiteOutput, Parasite, FeatureFlags> + Sync),
+    game: &(impl Game<HostOutput, ParasiteOutput, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Host, &HostOutput) -> bool>,
+) -> (
+    AlgenResult<HostOutput, Host>,
+    AlgenResult<ParasiteOutput, Parasite>,
+) {
+    params.reject_loop_only(
+        "run_competitive",
+        hosts.telemetry_sink().or(parasites.telemetry_sink()),
+    );
+    let params = &params.for_run();
+    params.install(|| {
+        let seed = Seed::new(params.seed);
+        let _stream = seed.enter();
+        let size = params.population.max(2);
+        let mut host_population: Vec<Node<Host>> = (0..size)
+            .map(|_| hosts.allocate_node(input_data, params))
+            .collect();
+        let mut parasite_population: Vec<Node<Parasite>> = (0..size)
+            .map(|_| parasites.allocate_node(input_data, params))
+            .collect();
+
+        let mut host_archive: Vec<(Node<Host>, HostOutput)> = Vec::new();
+        let mut parasite_archive: Vec<(Node<Parasite>, ParasiteOutput)> = Vec::new();
+        let mut host_result = None;
+        let mut parasite_result = None;
+
+        for generation in 0..params.generations {
+            params.state.set_generation(generation);
+            let streams = seed.derive(generation as u64);
+            // Hosts and parasites draw from streams of their own
+            let (host_streams, parasite_streams) = (streams.derive(0), streams.derive(1));
+
+            let host_outputs: Vec<HostOutput> = host_population
+                .par_iter()
+                .enumerate()
+                .map(|(idx, node)| {
+                    let _stream = host_streams.derive(EVALUATION).derive(idx as u64).enter();
+                    hosts.output(node, input_data, params)
+                })
+                .collect();
+            let parasite_outputs: Vec<ParasiteOutput> = parasite_population
+                .par_iter()
+                .enumerate()
+                .map(|(idx, node)| {
+                    let _stream = parasite_streams
+                        .derive(EVALUATION)
+                        .derive(idx as u64)
+                        .enter();
+                    parasites.output(node, input_data, params)
+                })
+                .collect();
+
+            let host_scores: Vec<f32> = host_outputs
+                .par_iter()
+                .enumerate()
+                .map(|(idx, host)| {
+                    let _stream = host_streams.derive(GAMES).derive(idx as u64).enter();
+                    let opponents = sample_opponents(
+                        &parasite_outputs,
+                        parasite_archive.iter().map(|(_, output)| output),
+                        competitive,
+                    );
+                    mean(opponents.map(|parasite| game.play(host, parasite, params).0))
+                })
+                .collect();
+            let parasite_scores: Vec<f32> = parasite_outputs
+                .par_iter()
+                .enumerate()
+                .map(|(idx, parasite)| {
+                    let _stream = parasite_streams.derive(GAMES).derive(idx as u64).enter();
+                    let opponents = sample_opponents(
+                        &host_outputs,
+                        host_archive.iter().map(|(_, output)| output),
+                        competitive,
+                    );
+                    mean(opponents.map(|host| game.play(host, parasite, params).1))
+                })
+                .collect();
+
+            let host_champion = crown(hosts, &mut host_population, host_scores);
+            let parasite_champion = crown(parasites, &mut parasite_population, parasite_scores);
+            host_result = Some((
+                host_population[host_champion].clone(),
+                host_outputs[host_champion].clone(),
+            ));
+            parasite_result = Some((
+                parasite_population[parasite_champion].clone(),
+                parasite_outputs[parasite_champion].clone(),
+            ));
+            enshrine(
+                &mut host_archive,
+                &host_population,
+                &host_outputs,
+                host_champion,
+                competitive.archive_size,
+            );
+            enshrine(
+                &mut parasite_archive,
+                &parasite_population,
+                &parasite_outputs,
+                parasite_champion,
+                competitive.archive_size,
+            );
+
+            #[cfg(feature = "tracing")]
+            event!(
+                Level::INFO,
+                msg = "Generation finished processing",
+                generation = generation,
+                host_score = host_population[host_champion].score,
+                parasite_score = parasite_population[parasite_champion].score
+            );
+
+            let stop = on_generation_complete.is_some_and(|func| {
+                let champion = &host_population[host_champion];
+                func(
+                    champion.score,
+                    &champion.solution,
+                    &host_outputs[host_champion],
+                )
+            });
+            if stop || generation + 1 == params.generations {
+                break;
+            }
+
+            let (host_seed, parasite_seed) = (
+                host_streams.derive(BREEDING),
+                parasite_streams.derive(BREEDING),
+            );
+            host_population = evolve(host_population, size, params, hosts, generation, host_seed);
+            parasite_population = evolve(
+                parasite_population,
+                size,
+                params,
+                parasites,
+                generation,
+                parasite_seed,
+            );
+        }
+
+        (
+            result(host_result, host_archive),
+            result(parasite_result, parasite_archive),
+        )
+    })
+}
+
+/// The outputs a node plays against: a sample of the current opponents,
+/// followed by a sample of the opponents' hall of fame.
+fn sample_opponents<'a, Output>(
+    current: &'a [Output],
+    archive: impl ExactSizeIterator<Item = &'a Output>,
+    competitive: &CompetitiveParameters,
+) -> impl Iterator<Item = &'a Output> {
+    let mut rng = crate::random::rng();
+    let count = competitive.opponents.max(1);
+    let archived = competitive.archive_opponents.min(archive.len());
+    let current: Vec<&Output> = (0..count)
+        .filter_map(|_| current.choose(&mut rng))
+        .collect();
+    let archived: Vec<&Output> = archive.choose_multiple(&mut rng, archived);
+    current.into_iter().chain(archived)
+}
+
+fn mean(payoffs: impl Iterator<Item = f32>) -> f32 {
+    let (total, count) = payoffs.fold((0.0, 0), |(total, count), payoff| {
+        (total + payoff, count + 1)
+    });
+    if count == 0 {
+        0.0
+    } else {
+        total / count as f32
+    }
+}
+
+/// Score a population and return the index of its best node. The
+/// operator which created each node is credited with how much better it
+/// fared than its parents did against the opponents of their day.
+fn crown<InputData, OutputData, Solution, FeatureFlags>(
+    algo: &impl Algorithm<InputData, OutputData, Solution, FeatureFlags>,
+    population: &mut [Node<Solution>],
+    scores: Vec<f32>,
+) -> usize
+where
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+{
+    for (node, score) in population.iter_mut().zip(scores) {
+        node.score = score;
+        reward(algo, node);
+    }
+    (0..population.len())
+        .max_by(|left, right| population[*left].score.total_cmp(&population[*right].score))
+        .unwrap_or(0)
+}
+
+/// Add the champion to the hall of fame, forgetting the oldest entry
+/// once it is full.
+fn enshrine<Solution: Clone, Output: Clone>(
+    archive: &mut Vec<(Node<Solution>, Output)>,
+    population: &[Node<Solution>],
+    outputs: &[Output],
+    champion: usize,
+    limit: usize,
+) {
+    if limit == 0 {
+        return;
+    }
+    if archive.len() >= limit {
+        archive.remove(0);
+    }
+    archive.push((population[champion].clone(), outputs[champion].clone()));
+}
+
+/// The latest champion, with the whole hall of fame.
+fn result<Solution, Output>(
+    champion: Option<(Node<Solution>, Output)>,
+    archive: Vec<(Node<Solution>, Output)>,
+) -> AlgenResult<Output, Solution> {
+    let (score, node, output) = match champion {
+        Some((node, output)) => (node.score, Some(node), Some(output)),
+        None => (f32::MIN, None, None),
+    };
+    AlgenResult {
+        score,
+        output,
+        node,
+        pareto_front: Vec::new(),
+        archive: archive.into_iter().map(|(node, _)| node).collect(),
+        quality_diversity: Vec::new(),
+        hypervolume: Vec::new(),
+        history: Vec::new(),
+        genealogy: Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A player is a single strength, which drifts a little with every
+    /// generation.
+    struct Player {}
+    impl Algorithm<(), f32, f32, ()> for Player {
+        fn output(&self, node: &Node<f32>, _: &(), _: &TestParameters<()>) -> f32 {
+            node.solution
+        }
+
+        fn allocate_node(&self, _: &(), _: &TestParameters<()>) -> Node<f32> {
+            Node::new(crate::random::rng().gen_range(0.0..1.0))
+        }
+
+        fn combine_node(
+            &self,
+            left: Node<f32>,
+            right: Node<f32>,
+            _: &TestParameters<()>,
+        ) -> Node<f32> {
+            let drift = crate::random::rng().gen_range(-0.1..0.1);
+            Node::new((left.solution + right.solution) / 2.0 + drift)
+        }
+    }
+
+    /// The stronger player wins.
+    struct Duel {}
+    impl Game<f32, f32, ()> for Duel {
+        fn play(&self, host: &f32, parasite: &f32, _: &TestParameters<()>) -> (f32, f32) {
+            if host > parasite {
+                (1.0, 0.0)
+            } else {
+                (0.0, 1.0)
+            }
+        }
+    }
+
+    #[test]
+    fn populations_race_each_other() {
+        let params = TestParameters {
+            generations: 40,
+            population: 30,
+            ..Default::default()
+        };
+        let competitive = CompetitiveParameters {
+            archive_size: 10,
+            ..Default::default()
+        };
+        let (hosts, parasites) = run_competitive(
+            &params,
+            &competitive,
+            &(),
+            &Player {},
+            &Player {},
+            &Duel {},
+            None,
+        );
+
+        assert_eq!(hosts.archive.len(), 10);
+        assert_eq!(parasites.archive.len(), 10);
+        assert!(hosts.node.unwrap().solution > 1.0);
+        assert!(parasites.node.unwrap().solution > 1.0);
+    }
+}
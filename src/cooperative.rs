@@ -0,0 +1,332 @@
+//! A cooperative coevolution runner. The problem is split into
+//! components, each evolved in its own subpopulation. A node is scored
+//! by assembling it with a representative of every other subpopulation
+//! into a complete solution, so each subpopulation only has to search a
+//! small part of the space. This is what makes very high dimensional
+//! problems tractable.
+use crate::parallel::*;
+use crate::{
+    islands::evolve,
+    models::algen_result::AlgenResult,
+    models::algorithm::Algorithm,
+    models::analyzer::Analyzer,
+    models::cooperative::{CooperativeParameters, Decomposition},
+    models::node::Node,
+    models::test_parameters::TestParameters,
+    random::Seed,
+    BREEDING, EVALUATION,
+};
+use rand::prelude::*;
+use std::marker::PhantomData;
+
+#[cfg(feature = "tracing")]
+use tracing::{event, Level};
+
+/// Called after every generation with the best score, complete
+/// solution, and output so far. Returning true stops the run.
+pub type CooperativeCallback<Component, OutputData> = fn(f32, &[Component], &OutputData) -> bool;
+
+/// Run cooperative coevolution. Each subpopulation holds
+/// `params.population` values of its component, and every node is
+/// evaluated alongside the best member of each other subpopulation from
+/// the previous generation, plus any random collaborators.
+///
+/// The result holds the best complete solution found, as a vector with
+/// one value per component.
+///
+/// Components are bred with `Decomposition::combine_components`, so
+/// there is no operator bandit to pick or reward their operators.
+pub fn run_cooperative<
+    InputData: Send + Sync,
+    OutputData: Clone + Send + Sync,
+    Component: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    cooperative: &CooperativeParameters,
+    input_data: &InputData,
+    decomposition: &(impl Decomposition<InputData, OutputData, Component, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<CooperativeCallback<Component, OutputData>>,
+) -> AlgenResult<OutputData, Vec<Component>> {
+    params.reject_loop_only("run_cooperative", None);
+    let params = &params.for_run();
+    params.install(|| {
+        let seed = Seed::new(params.seed);
+        let _stream = seed.enter();
+        let components = decomposition.components();
+        let size = params.population.max(2);
+
+        let mut best_score = f32::MIN;
+        let mut best_node: Option<Node<Vec<Component>>> = None;
+        let mut best_output: Option<OutputData> = None;
+
+        let mut subpopulations: Vec<Vec<Node<Component>>> = (0..components)
+            .map(|component| {
+                (0..size)
+                    .map(|_| {
+                        Node::new(decomposition.allocate_component(component, input_data, params))
+                    })
+                    .collect()
+            })
+            .collect();
+
+        // Until a subpopulation has been scored, any member will do
+        let mut representatives: Vec<Component> = subpopulations
+            .iter()
+            .map(|subpopulation| subpopulation[0].solution.clone())
+            .collect();
+
+        let collaborate = |component: usize,
+                           value: &Component,
+                           representatives: &[Component],
+                           subpopulations: &[Vec<Node<Component>>]| {
+            let mut rng = crate::random::rng();
+            let mut best: Option<(f32, Vec<Component>, OutputData)> = None;
+            for collaboration in 0..=cooperative.random_collaborators {
+                let mut solution = representatives.to_vec();
+                if collaboration > 0 {
+                    for (other, subpopulation) in subpopulations.iter().enumerate() {
+                        if let Some(node) = subpopulation.choose(&mut rng) {
+                            solution[other] = node.solution.clone();
+                        }
+                    }
+                }
+                solution[component] = value.clone();
+                let output = decomposition.output(&solution, input_data, params);
+                let score = analyzer.evaluate(&output, params);
+                if best.as_ref().is_none_or(|(best, _, _)| score > *best) {
+                    best = Some((score, solution, output));
+                }
+            }
+            best.unwrap()
+        };
+
+        for generation in 0..params.generations {
+            params.state.set_generation(generation);
+            let streams = seed.derive(generation as u64);
+
+            // Score every node of every subpopulation against the
+            // representatives of the others
+            let results = subpopulations
+                .par_iter()
+                .enumerate()
+                .map(|(component, subpopulation)| {
+                    let streams = streams.derive(EVALUATION).derive(component as u64);
+                    let evaluated = subpopulation
+                        .par_iter()
+                        .enumerate()
+                        .map(|(idx, node)| {
+                            let _stream = streams.derive(idx as u64).enter();
+                            collaborate(
+                                component,
+                                &node.solution,
+                                &representatives,
+                                &subpopulations,
+                            )
+                        })
+                        .collect::<Vec<_>>();
+                    let scores: Vec<f32> = evaluated.iter().map(|(score, _, _)| *score).collect();
+                    let best = evaluated
+                        .into_iter()
+                        .max_by(|left, right| left.0.total_cmp(&right.0));
+                    (scores, best)
+                })
+                .collect::<Vec<_>>();
+
+            for (subpopulation, (scores, best)) in subpopulations.iter_mut().zip(results) {
+                for (node, score) in subpopulation.iter_mut().zip(scores) {
+                    node.score = score;
+                }
+                if let Some((score, solution, output)) = best {
+                    if score > best_score {
+                        best_score = score;
+                        let mut node = Node::new(solution);
+                        node.score = score;
+                        best_node = Some(node);
+                        best_output = Some(output);
+                    }
+                }
+            }
+
+            for (representative, subpopulation) in representatives.iter_mut().zip(&subpopulations) {
+                if let Some(node) = subpopulation
+                    .iter()
+                    .max_by(|left, right| left.score.total_cmp(&right.score))
+                {
+                    *representative = node.solution.clone();
+                }
+            }
+
+            subpopulations = subpopulations
+                .into_par_iter()
+                .enumerate()
+                .map(|(component, subpopulation)| {
+                    let algo = ComponentAlgorithm {
+                        decomposition,
+                        component,
+                        representatives: &representatives,
+                        data: PhantomData,
+                    };
+                    let seed = streams.derive(BREEDING).derive(component as u64);
+                    evolve(subpopulation, size, params, &algo, generation, seed)
+                })
+                .collect();
+
+            #[cfg(feature = "tracing")]
+            event!(
+                Level::INFO,
+                msg = "Generation finished processing",
+                generation = generation,
+                score = best_score
+            );
+
+            if let (Some(func), Some(output), Some(node)) =
+                (on_generation_complete, &best_output, &best_node)
+            {
+                if func(best_score, &node.solution, output) {
+                    break;
+                }
+            }
+        }
+
+        AlgenResult {
+            score: best_score,
+            output: best_output,
+            node: best_node,
+            pareto_front: Vec::new(),
+            archive: Vec::new(),
+            quality_diversity: Vec::new(),
+            hypervolume: Vec::new(),
+            history: Vec::new(),
+            genealogy: Vec::new(),
+        }
+    })
+}
+
+/// One component of a decomposition seen as an algorithm of its own, so
+/// that its subpopulation can be bred like any other population. Its
+/// output is the output of the complete solution assembled with the
+/// representatives of the other components.
+struct ComponentAlgorithm<'a, Decomposer, InputData, OutputData, Component> {
+    decomposition: &'a Decomposer,
+    component: usize,
+    representatives: &'a [Component],
+    data: PhantomData<fn(&InputData) -> OutputData>,
+}
+
+impl<'a, Decomposer, InputData, OutputData, Component, FeatureFlags>
+    Algorithm<InputData, OutputData, Component, FeatureFlags>
+    for ComponentAlgorithm<'a, Decomposer, InputData, OutputData, Component>
+where
+    Decomposer: Decomposition<InputData, OutputData, Component, FeatureFlags>,
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Component: Clone + Send + Sync,
+{
+    fn output(
+        &self,
+        node: &Node<Component>,
+        input: &InputData,
+        params: &TestParameters<FeatureFlags>,
+    ) -> OutputData {
+        let mut solution = self.representatives.to_vec();
+        solution[self.component] = node.solution.clone();
+        self.decomposition.output(&solution, input, params)
+    }
+
+    fn allocate_node(
+        &self,
+        input: &InputData,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<Component> {
+        Node::new(
+            self.decomposition
+                .allocate_component(self.component, input, params),
+        )
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<Component>,
+        right: Node<Component>,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<Component> {
+        Node::new(self.decomposition.combine_components(
+            self.component,
+            &left.solution,
+            &right.solution,
+            params,
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// The sphere function in 40 dimensions, split into 4 blocks of 10.
+    struct Sphere {}
+
+    impl Decomposition<(), f64, Vec<f64>, ()> for Sphere {
+        fn components(&self) -> usize {
+            4
+        }
+
+        fn allocate_component(&self, _: usize, _: &(), _: &TestParameters<()>) -> Vec<f64> {
+            let mut rng = crate::random::rng();
+            (0..10).map(|_| rng.gen_range(-5.0..5.0)).collect()
+        }
+
+        fn combine_components(
+            &self,
+            _: usize,
+            left: &Vec<f64>,
+            right: &Vec<f64>,
+            _: &TestParameters<()>,
+        ) -> Vec<f64> {
+            let mut rng = crate::random::rng();
+            left.iter()
+                .zip(right)
+                .map(|(left, right)| {
+                    let gene = if rng.gen_bool(0.5) { *left } else { *right };
+                    if rng.gen_bool(0.1) {
+                        gene + rng.gen_range(-0.5..0.5)
+                    } else {
+                        gene
+                    }
+                })
+                .collect()
+        }
+
+        fn output(&self, components: &[Vec<f64>], _: &(), _: &TestParameters<()>) -> f64 {
+            components.iter().flatten().map(|gene| gene * gene).sum()
+        }
+    }
+
+    struct Minimize {}
+    impl Analyzer<(), f64, ()> for Minimize {
+        fn evaluate(&self, attempt: &f64, _: &TestParameters<()>) -> f32 {
+            -*attempt as f32
+        }
+    }
+
+    #[test]
+    fn components_cooperate() {
+        let params = TestParameters {
+            generations: 60,
+            population: 40,
+            ..Default::default()
+        };
+        let cooperative = CooperativeParameters {
+            random_collaborators: 1,
+        };
+        let initial = -(40.0 * 25.0 / 3.0);
+
+        let result = run_cooperative(&params, &cooperative, &(), &Sphere {}, &Minimize {}, None);
+        let node = result.node.unwrap();
+        assert_eq!(node.solution.len(), 4);
+        assert!(result.score > initial / 10.0);
+    }
+}
@@ -0,0 +1,46 @@
+//! The time limit of the evaluation running on the calling thread. Rust
+//! can't stop a thread from the outside, so an evaluation which is over
+//! its time limit is only penalized once it returns. Algorithms whose
+//! evaluations might never return, such as interpreters for evolved
+//! programs, should check `expired()` as they go and give up once it is
+//! true.
+use crate::clock::Instant;
+use std::{cell::Cell, time::Duration};
+
+thread_local! {
+    static DEADLINE: Cell<Option<Instant>> = const { Cell::new(None) };
+}
+
+/// Whether the evaluation running on this thread has used up its time.
+/// This is always false outside of evaluations with a time limit.
+pub fn expired() -> bool {
+    remaining().is_some_and(|remaining| remaining.is_zero())
+}
+
+/// How much time the evaluation running on this thread has left, if it
+/// has a time limit.
+pub fn remaining() -> Option<Duration> {
+    DEADLINE
+        .with(Cell::get)
+        .map(|deadline| deadline.saturating_duration_since(Instant::now()))
+}
+
+/// Give the work on this thread `limit` to finish, until the returned
+/// guard is dropped.
+pub(crate) fn start(limit: Duration) -> Started {
+    Started {
+        previous: DEADLINE.with(|deadline| deadline.replace(Some(Instant::now() + limit))),
+    }
+}
+
+/// Puts back the deadline a thread had before one was started.
+#[must_use]
+pub(crate) struct Started {
+    previous: Option<Instant>,
+}
+
+impl Drop for Started {
+    fn drop(&mut self) {
+        DEADLINE.with(|deadline| deadline.set(self.previous));
+    }
+}
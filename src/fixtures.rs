@@ -0,0 +1,168 @@
+//! The problem every runner's tests evolve: sixteen bits, scored by how
+//! many of them are set. `Configured` wraps it with whichever optional
+//! parts of `Algorithm` a test needs.
+use crate::models::{
+    accelerator::Accelerator, algorithm::Algorithm, analyzer::Analyzer, constraint::Constraint,
+    local_search::LocalSearch, multi_objective_analyzer::MultiObjectiveAnalyzer, node::Node,
+    operator_selection::OperatorBandit, snapshot::SnapshotSink, telemetry::TelemetrySink,
+    test_parameters::TestParameters,
+};
+use crate::operators::{bit_flip, gene_pool};
+use rand::Rng;
+
+pub struct CountOnes {}
+impl Algorithm<(), Vec<bool>, Vec<bool>, ()> for CountOnes {
+    fn output(
+        &self,
+        node: &Node<Vec<bool>>,
+        _input: &(),
+        _params: &TestParameters<()>,
+    ) -> Vec<bool> {
+        node.solution.clone()
+    }
+
+    fn allocate_node(&self, _input: &(), _params: &TestParameters<()>) -> Node<Vec<bool>> {
+        let mut rng = crate::random::rng();
+        Node::new((0..16).map(|_| rng.gen_bool(0.5)).collect())
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<Vec<bool>>,
+        right: Node<Vec<bool>>,
+        params: &TestParameters<()>,
+    ) -> Node<Vec<bool>> {
+        let mut rng = crate::random::rng();
+        let solution = left
+            .solution
+            .iter()
+            .zip(right.solution.iter())
+            .map(|(l, r)| {
+                let bit = if rng.gen_bool(0.5) { *l } else { *r };
+                bit ^ rng.gen_bool(params.current_mutation_factor() as f64)
+            })
+            .collect();
+
+        Node::new(solution)
+    }
+}
+
+pub struct CountOnesAnalyzer {}
+impl Analyzer<(), Vec<bool>, ()> for CountOnesAnalyzer {
+    fn evaluate(&self, attempt: &Vec<bool>, _params: &TestParameters<()>) -> f32 {
+        attempt.iter().filter(|bit| **bit).count() as f32
+    }
+}
+
+/// Ones against zeros, which puts every bit string on the front.
+pub struct TradeOff {}
+impl MultiObjectiveAnalyzer<(), Vec<bool>, ()> for TradeOff {
+    fn evaluate(&self, attempt: &Vec<bool>, _params: &TestParameters<()>) -> Vec<f32> {
+        let ones = attempt.iter().filter(|bit| **bit).count() as f32;
+        vec![ones, attempt.len() as f32 - ones]
+    }
+}
+
+pub fn parameters() -> TestParameters<()> {
+    TestParameters {
+        generations: 50,
+        population: 100,
+        ..Default::default()
+    }
+}
+
+/// Violated by every bit set beyond the eighth.
+pub struct AtMostEight {}
+impl Constraint<Vec<bool>, Vec<bool>> for AtMostEight {
+    fn violation(&self, _solution: &Vec<bool>, output: &Vec<bool>) -> f32 {
+        (output.iter().filter(|bit| **bit).count() as f32 - 8.0).max(0.0)
+    }
+}
+
+/// `CountOnes` with whichever optional parts of `Algorithm` are set.
+/// Offspring of more than two parents are drawn from their gene pool.
+/// With a bandit, operator zero only ever copies a parent, so it never
+/// improves on it, and any other operator is the usual recombination.
+#[derive(Default)]
+pub struct Configured<'a> {
+    pub constraints: Vec<Box<dyn Constraint<Vec<bool>, Vec<bool>> + Send + Sync>>,
+    pub bandit: Option<OperatorBandit>,
+    pub repair: Option<fn(&mut Vec<bool>)>,
+    pub local_search: Option<&'a (dyn LocalSearch<Vec<bool>> + Sync)>,
+    pub accelerator: Option<&'a (dyn Accelerator<Vec<bool>> + Sync)>,
+    pub snapshots: Option<&'a (dyn SnapshotSink<Vec<bool>> + Sync)>,
+    pub telemetry: Option<&'a (dyn TelemetrySink + Sync)>,
+}
+
+impl Algorithm<(), Vec<bool>, Vec<bool>, ()> for Configured<'_> {
+    fn output(&self, node: &Node<Vec<bool>>, input: &(), params: &TestParameters<()>) -> Vec<bool> {
+        CountOnes {}.output(node, input, params)
+    }
+
+    fn allocate_node(&self, input: &(), params: &TestParameters<()>) -> Node<Vec<bool>> {
+        CountOnes {}.allocate_node(input, params)
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<Vec<bool>>,
+        right: Node<Vec<bool>>,
+        params: &TestParameters<()>,
+    ) -> Node<Vec<bool>> {
+        CountOnes {}.combine_node(left, right, params)
+    }
+
+    fn combine_nodes(
+        &self,
+        parents: &[Node<Vec<bool>>],
+        params: &TestParameters<()>,
+    ) -> Node<Vec<bool>> {
+        let solutions: Vec<&Vec<bool>> = parents.iter().map(|node| &node.solution).collect();
+        let mut solution = gene_pool(&solutions);
+        bit_flip(&mut solution, params.current_mutation_factor());
+        Node::new(solution)
+    }
+
+    fn operator_selection(&self) -> Option<&OperatorBandit> {
+        self.bandit.as_ref()
+    }
+
+    fn combine_with_operator(
+        &self,
+        operator: usize,
+        left: Node<Vec<bool>>,
+        right: Node<Vec<bool>>,
+        params: &TestParameters<()>,
+    ) -> Node<Vec<bool>> {
+        match operator {
+            0 => Node::new(left.solution),
+            _ => self.combine_node(left, right, params),
+        }
+    }
+
+    fn repair(&self, solution: &mut Vec<bool>, _params: &TestParameters<()>) {
+        if let Some(repair) = self.repair {
+            repair(solution);
+        }
+    }
+
+    fn local_search(&self) -> Option<&(dyn LocalSearch<Vec<bool>> + Sync)> {
+        self.local_search
+    }
+
+    fn accelerator(&self) -> Option<&dyn Accelerator<Vec<bool>>> {
+        self.accelerator.map(|accelerator| accelerator as _)
+    }
+
+    fn snapshot_sink(&self) -> Option<&dyn SnapshotSink<Vec<bool>>> {
+        self.snapshots.map(|snapshots| snapshots as _)
+    }
+
+    fn telemetry_sink(&self) -> Option<&dyn TelemetrySink> {
+        self.telemetry.map(|telemetry| telemetry as _)
+    }
+
+    fn constraints(&self) -> &[Box<dyn Constraint<Vec<bool>, Vec<bool>> + Send + Sync>] {
+        &self.constraints
+    }
+}
@@ -0,0 +1,11 @@
+pub mod bit_string;
+pub mod cartesian;
+pub mod derived;
+pub mod grammatical;
+pub mod gray;
+pub mod integer_vector;
+pub mod linear;
+pub mod permutation;
+pub mod real_vector;
+pub mod self_adaptive;
+pub mod tree;
@@ -0,0 +1,137 @@
+use crate::{
+    models::{
+        algorithm::{fingerprint, Algorithm},
+        distance::{Distance, Hamming},
+        node::Node,
+        test_parameters::TestParameters,
+    },
+    operators::{bit_flip, one_point, two_point, uniform},
+};
+use rand::prelude::*;
+
+/// The crossover used to combine two bit strings.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum BitCrossover {
+    /// Split both parents at one random point.
+    OnePoint,
+    /// Swap the segment between two random points.
+    TwoPoint,
+    /// Pick every bit from either parent, favoring the first parent with
+    /// probability `crossover_factor`.
+    #[default]
+    Uniform,
+}
+
+/// A ready made algorithm for problems encoded as a fixed length string
+/// of bits. The solution and the output are both the bits themselves, so
+/// only an analyzer needs to be written. Offspring are created with the
+/// chosen crossover, and then every bit is flipped with probability
+/// `mutation_factor`.
+#[derive(Clone, Copy, Debug)]
+pub struct BitString {
+    pub length: usize,
+    pub crossover: BitCrossover,
+}
+
+impl BitString {
+    /// A bit string of the given length using uniform crossover.
+    pub fn new(length: usize) -> Self {
+        BitString {
+            length,
+            crossover: BitCrossover::default(),
+        }
+    }
+}
+
+impl<InputData: Send + Sync, FeatureFlags> Algorithm<InputData, Vec<bool>, Vec<bool>, FeatureFlags>
+    for BitString
+{
+    fn output(
+        &self,
+        node: &Node<Vec<bool>>,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Vec<bool> {
+        node.solution.clone()
+    }
+
+    fn allocate_node(
+        &self,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Node<Vec<bool>> {
+        let mut rng = crate::random::rng();
+        Node::new((0..self.length).map(|_| rng.gen_bool(0.5)).collect())
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<Vec<bool>>,
+        right: Node<Vec<bool>>,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<Vec<bool>> {
+        let mut solution = match self.crossover {
+            BitCrossover::OnePoint => one_point(&left.solution, &right.solution),
+            BitCrossover::TwoPoint => two_point(&left.solution, &right.solution),
+            BitCrossover::Uniform => uniform(
+                &left.solution,
+                &right.solution,
+                params.current_crossover_factor(),
+            ),
+        };
+        bit_flip(&mut solution, params.current_mutation_factor());
+
+        Node::new(solution)
+    }
+
+    fn distance(&self) -> Option<&(dyn Distance<Vec<bool>> + Sync)> {
+        Some(&Hamming)
+    }
+
+    fn fingerprint(&self, solution: &Vec<bool>) -> Option<u64> {
+        Some(fingerprint(solution))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::analyzer::Analyzer, run_algorithm};
+
+    struct OneMax {}
+    impl Analyzer<(), Vec<bool>, ()> for OneMax {
+        fn evaluate(&self, output: &Vec<bool>, _params: &TestParameters<()>) -> f32 {
+            output.iter().filter(|bit| **bit).count() as f32
+        }
+    }
+
+    #[test]
+    fn one_max_needs_only_an_analyzer() {
+        let params = TestParameters {
+            generations: 50,
+            population: 100,
+            ..Default::default()
+        };
+
+        for crossover in [
+            BitCrossover::OnePoint,
+            BitCrossover::TwoPoint,
+            BitCrossover::Uniform,
+        ] {
+            let algo = BitString {
+                length: 16,
+                crossover,
+            };
+            let result = run_algorithm(&params, &(), &algo, &OneMax {}, None);
+            assert!(result.score >= 12.0);
+            assert_eq!(result.node.unwrap().solution.len(), 16);
+
+            // Random bit strings are nearly all different, and selection
+            // makes the population converge
+            let (first, last) = (&result.history[0], result.history.last().unwrap());
+            assert!(first.genotype_entropy.unwrap() > 6.5);
+            assert!(last.genotype_entropy < first.genotype_entropy);
+            assert!(last.mean_distance < first.mean_distance);
+        }
+    }
+}
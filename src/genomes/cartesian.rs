@@ -0,0 +1,348 @@
+use super::tree::Primitive;
+use crate::models::{algorithm::Algorithm, node::Node, test_parameters::TestParameters};
+use rand::prelude::*;
+
+/// One function node of a Cartesian program. `inputs` are addresses,
+/// where `0..inputs` are the program inputs and every address after that
+/// is the output of an earlier node.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CgpNode {
+    pub function: usize,
+    pub inputs: Vec<usize>,
+}
+
+/// A Cartesian program: a fixed grid of function nodes, stored column by
+/// column, plus the address each program output reads from. Only the
+/// nodes which an output depends on are active, so the rest of the
+/// genome is free to drift without changing the behavior.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CgpGenome {
+    pub nodes: Vec<CgpNode>,
+    pub outputs: Vec<usize>,
+}
+
+impl CgpGenome {
+    /// Which nodes are used by at least one output.
+    pub fn active<P: Primitive>(&self, inputs: usize, functions: &[P]) -> Vec<bool> {
+        let mut active = vec![false; self.nodes.len()];
+        let mut pending: Vec<usize> = self.outputs.clone();
+        while let Some(address) = pending.pop() {
+            if address < inputs || active[address - inputs] {
+                continue;
+            }
+
+            let node = &self.nodes[address - inputs];
+            active[address - inputs] = true;
+            let arity = functions[node.function].arity();
+            pending.extend(node.inputs.iter().take(arity));
+        }
+
+        active
+    }
+
+    /// Run the program. Only active nodes are evaluated, and `apply` is
+    /// given each node's function along with the values of its inputs.
+    pub fn evaluate<P: Primitive, Value: Clone>(
+        &self,
+        inputs: &[Value],
+        functions: &[P],
+        apply: impl Fn(&P, &[Value]) -> Value,
+    ) -> Vec<Value> {
+        let active = self.active(inputs.len(), functions);
+        let mut values: Vec<Option<Value>> = inputs.iter().cloned().map(Some).collect();
+        for (node, active) in self.nodes.iter().zip(active) {
+            let value = if active {
+                let function = &functions[node.function];
+                let arguments: Vec<Value> = node
+                    .inputs
+                    .iter()
+                    .take(function.arity())
+                    .filter_map(|address| values[*address].clone())
+                    .collect();
+                Some(apply(function, &arguments))
+            } else {
+                None
+            };
+            values.push(value);
+        }
+
+        self.outputs
+            .iter()
+            .filter_map(|address| values[*address].clone())
+            .collect()
+    }
+}
+
+/// How offspring are mutated.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum CgpMutation {
+    /// Every gene is replaced with a random valid value with probability
+    /// `mutation_factor`.
+    Point,
+    /// Genes are mutated one at a time until an active gene has changed.
+    /// Every offspring then behaves differently from its parent, while
+    /// the inactive genes changed along the way drift neutrally.
+    #[default]
+    SingleActive,
+}
+
+/// A ready made algorithm for Cartesian genetic programming. Programs are
+/// a grid of `rows * columns` function nodes, each of which may only read
+/// from the program inputs or from nodes up to `levels_back` columns to
+/// its left.
+///
+/// CGP does not use crossover, so each offspring is a mutated copy of
+/// its first parent. The output is the genome itself, which the analyzer
+/// runs with `CgpGenome::evaluate`. A small population with elitism
+/// approximates the (1 + λ) evolution strategy CGP is usually run with.
+#[derive(Clone, Debug)]
+pub struct Cgp<P> {
+    pub functions: Vec<P>,
+    pub inputs: usize,
+    pub outputs: usize,
+    pub rows: usize,
+    pub columns: usize,
+    pub levels_back: usize,
+    pub mutation: CgpMutation,
+}
+
+impl<P: Primitive> Cgp<P> {
+    /// A single row of 100 nodes, where every node may read from any
+    /// node before it.
+    ///
+    /// Panics if there are no inputs, since the first node would have
+    /// nothing to read, or no outputs, since no node would ever be active.
+    pub fn new(functions: Vec<P>, inputs: usize, outputs: usize) -> Self {
+        assert!(inputs > 0, "a Cartesian program needs at least one input");
+        assert!(outputs > 0, "a Cartesian program needs at least one output");
+        Cgp {
+            functions,
+            inputs,
+            outputs,
+            rows: 1,
+            columns: 100,
+            levels_back: 100,
+            mutation: CgpMutation::default(),
+        }
+    }
+
+    fn arity(&self) -> usize {
+        self.functions
+            .iter()
+            .map(Primitive::arity)
+            .max()
+            .unwrap_or(0)
+    }
+
+    /// A random address which the node at `index` is allowed to read.
+    fn connection(&self, index: usize, rng: &mut impl Rng) -> usize {
+        let rows = self.rows.max(1);
+        let column = index / rows;
+        let first = column.saturating_sub(self.levels_back.max(1)) * rows;
+        let choices = self.inputs + column * rows - first;
+        let pick = rng.gen_range(0..choices.max(1));
+        if pick < self.inputs {
+            pick
+        } else {
+            self.inputs + first + (pick - self.inputs)
+        }
+    }
+
+    fn random_node(&self, index: usize, rng: &mut impl Rng) -> CgpNode {
+        CgpNode {
+            function: rng.gen_range(0..self.functions.len().max(1)),
+            inputs: (0..self.arity())
+                .map(|_| self.connection(index, rng))
+                .collect(),
+        }
+    }
+
+    fn random_output(&self, rng: &mut impl Rng) -> usize {
+        rng.gen_range(0..self.inputs + self.rows.max(1) * self.columns)
+    }
+
+    /// Mutate a single random gene. Returns whether it was active.
+    fn mutate_gene(&self, genome: &mut CgpGenome, active: &[bool], rng: &mut impl Rng) -> bool {
+        let per_node = 1 + self.arity();
+        let gene = rng.gen_range(0..genome.nodes.len() * per_node + genome.outputs.len());
+        if gene >= genome.nodes.len() * per_node {
+            genome.outputs[gene - genome.nodes.len() * per_node] = self.random_output(rng);
+            return true;
+        }
+
+        let (index, offset) = (gene / per_node, gene % per_node);
+        if offset == 0 {
+            genome.nodes[index].function = rng.gen_range(0..self.functions.len().max(1));
+        } else {
+            genome.nodes[index].inputs[offset - 1] = self.connection(index, rng);
+        }
+        active[index]
+    }
+}
+
+impl<InputData: Send + Sync, FeatureFlags, P: Primitive>
+    Algorithm<InputData, CgpGenome, CgpGenome, FeatureFlags> for Cgp<P>
+{
+    fn output(
+        &self,
+        node: &Node<CgpGenome>,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> CgpGenome {
+        node.solution.clone()
+    }
+
+    fn allocate_node(
+        &self,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Node<CgpGenome> {
+        let mut rng = crate::random::rng();
+        let count = self.rows.max(1) * self.columns;
+        Node::new(CgpGenome {
+            nodes: (0..count)
+                .map(|idx| self.random_node(idx, &mut rng))
+                .collect(),
+            outputs: (0..self.outputs)
+                .map(|_| self.random_output(&mut rng))
+                .collect(),
+        })
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<CgpGenome>,
+        _right: Node<CgpGenome>,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<CgpGenome> {
+        let mut rng = crate::random::rng();
+        let mut genome = left.solution;
+        if genome.nodes.is_empty() && genome.outputs.is_empty() {
+            return Node::new(genome);
+        }
+
+        let active = genome.active(self.inputs, &self.functions);
+        match self.mutation {
+            CgpMutation::Point => {
+                let per_node = 1 + self.arity();
+                let genes = genome.nodes.len() * per_node + genome.outputs.len();
+                let rate = params.current_mutation_factor().clamp(0.0, 1.0) as f64;
+                for _ in 0..genes {
+                    if rng.gen_bool(rate) {
+                        self.mutate_gene(&mut genome, &active, &mut rng);
+                    }
+                }
+            }
+            CgpMutation::SingleActive => {
+                // Without outputs nothing is active, so give up once
+                // there have been as many tries as there are genes
+                let genes = genome.nodes.len() * (1 + self.arity()) + genome.outputs.len();
+                for _ in 0..genes {
+                    if self.mutate_gene(&mut genome, &active, &mut rng) {
+                        break;
+                    }
+                }
+            }
+        }
+
+        Node::new(genome)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::analyzer::Analyzer, run_algorithm};
+
+    #[derive(Clone, Debug)]
+    enum Gate {
+        And,
+        Or,
+        Nand,
+        Nor,
+    }
+
+    impl Primitive for Gate {
+        fn arity(&self) -> usize {
+            2
+        }
+    }
+
+    const GATES: [Gate; 4] = [Gate::And, Gate::Or, Gate::Nand, Gate::Nor];
+
+    fn run(genome: &CgpGenome, a: bool, b: bool) -> bool {
+        genome.evaluate(&[a, b], &GATES, |gate, args| match gate {
+            Gate::And => args[0] && args[1],
+            Gate::Or => args[0] || args[1],
+            Gate::Nand => !(args[0] && args[1]),
+            Gate::Nor => !(args[0] || args[1]),
+        })[0]
+    }
+
+    /// Rewards each row of the exclusive-or truth table.
+    struct Xor {}
+    impl Analyzer<(), CgpGenome, ()> for Xor {
+        fn evaluate(&self, attempt: &CgpGenome, _params: &TestParameters<()>) -> f32 {
+            [(false, false), (false, true), (true, false), (true, true)]
+                .iter()
+                .filter(|(a, b)| run(attempt, *a, *b) == (a ^ b))
+                .count() as f32
+        }
+    }
+
+    #[test]
+    fn only_active_nodes_are_evaluated() {
+        let genome = CgpGenome {
+            nodes: vec![
+                CgpNode {
+                    function: 0,
+                    inputs: vec![0, 1],
+                },
+                CgpNode {
+                    function: 3,
+                    inputs: vec![0, 0],
+                },
+            ],
+            outputs: vec![2],
+        };
+        assert_eq!(genome.active(2, &GATES), vec![true, false]);
+        assert!(run(&genome, true, true));
+        assert!(!run(&genome, true, false));
+    }
+
+    #[test]
+    #[should_panic(expected = "at least one input")]
+    fn programs_need_inputs() {
+        Cgp::new(GATES.to_vec(), 0, 1);
+    }
+
+    #[test]
+    fn single_active_mutation_gives_up_without_outputs() {
+        let algo = Cgp {
+            outputs: 0,
+            mutation: CgpMutation::SingleActive,
+            ..Cgp::new(GATES.to_vec(), 2, 1)
+        };
+        let params = TestParameters::<()>::default();
+        let parent: Node<CgpGenome> = algo.allocate_node(&(), &params);
+        let child = Algorithm::<(), _, _, _>::combine_node(&algo, parent.clone(), parent, &params);
+        assert!(child.solution.outputs.is_empty());
+    }
+
+    #[test]
+    fn xor_evolves_from_gates() {
+        let params = TestParameters {
+            generations: 100,
+            population: 50,
+            elitism_factor: 0.1,
+            ..Default::default()
+        };
+        let algo = Cgp {
+            columns: 20,
+            ..Cgp::new(GATES.to_vec(), 2, 1)
+        };
+
+        let result = run_algorithm(&params, &(), &algo, &Xor {}, None);
+        assert_eq!(result.score, 4.0);
+    }
+}
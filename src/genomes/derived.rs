@@ -0,0 +1,119 @@
+use crate::models::{
+    algorithm::Algorithm, genome::Genome, node::Node, test_parameters::TestParameters,
+};
+use std::marker::PhantomData;
+
+/// A ready made algorithm for any type which implements `Genome`, which
+/// is usually derived. New nodes are random genomes, and offspring are
+/// a crossover of their parents biased by `crossover_factor`, followed
+/// by mutation at `mutation_factor`. The solution and the output are
+/// both the genome itself, so only an analyzer needs to be written.
+pub struct GenomeAlgorithm<G> {
+    genome: PhantomData<fn() -> G>,
+}
+
+impl<G> GenomeAlgorithm<G> {
+    pub fn new() -> Self {
+        GenomeAlgorithm {
+            genome: PhantomData,
+        }
+    }
+}
+
+impl<G> Default for GenomeAlgorithm<G> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<InputData: Send + Sync, G: Genome, FeatureFlags> Algorithm<InputData, G, G, FeatureFlags>
+    for GenomeAlgorithm<G>
+{
+    fn output(
+        &self,
+        node: &Node<G>,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> G {
+        node.solution.clone()
+    }
+
+    fn allocate_node(&self, _input: &InputData, _params: &TestParameters<FeatureFlags>) -> Node<G> {
+        Node::new(G::random(&mut crate::random::rng()))
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<G>,
+        right: Node<G>,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<G> {
+        let mut rng = crate::random::rng();
+        let mut child =
+            left.solution
+                .crossover(&right.solution, params.current_crossover_factor(), &mut rng);
+        child.mutate(params.current_mutation_factor(), &mut rng);
+        Node::new(child)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::analyzer::Analyzer, run_algorithm};
+    use algen_derive::Genome;
+
+    #[derive(Clone, Copy, Debug, PartialEq, Genome)]
+    enum Shape {
+        Circle,
+        Square,
+        Triangle,
+    }
+
+    #[derive(Clone, Debug, Genome)]
+    struct Design {
+        #[gene(range = 0.0..10.0)]
+        width: f64,
+        #[gene(range = 1..=5)]
+        layers: u8,
+        hollow: bool,
+        shape: Shape,
+    }
+
+    struct DesignAnalyzer {}
+    impl Analyzer<(), Design, ()> for DesignAnalyzer {
+        fn evaluate(&self, design: &Design, _params: &TestParameters<()>) -> f32 {
+            let mut score = -(design.width - 7.0).abs() as f32 + design.layers as f32;
+            if design.hollow {
+                score += 1.0;
+            }
+            if design.shape == Shape::Triangle {
+                score += 1.0;
+            }
+            score
+        }
+    }
+
+    #[test]
+    fn derived_genomes_evolve() {
+        let params = TestParameters {
+            generations: 30,
+            population: 100,
+            mutation_factor: 0.1,
+            ..Default::default()
+        };
+        let result = run_algorithm(
+            &params,
+            &(),
+            &GenomeAlgorithm::new(),
+            &DesignAnalyzer {},
+            None,
+        );
+
+        let design = result.output.unwrap();
+        assert!((0.0..10.0).contains(&design.width));
+        assert_eq!(design.layers, 5);
+        assert!(design.hollow);
+        assert_eq!(design.shape, Shape::Triangle);
+    }
+}
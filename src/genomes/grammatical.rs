@@ -0,0 +1,348 @@
+use crate::{
+    models::{algorithm::Algorithm, node::Node, test_parameters::TestParameters},
+    operators::one_point,
+};
+use rand::prelude::*;
+use std::fmt;
+
+/// One symbol on the right hand side of a grammar rule.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Symbol {
+    /// The index of the rule to expand.
+    NonTerminal(usize),
+    /// Text which is copied into the phenotype as is.
+    Terminal(String),
+}
+
+/// Why a BNF grammar could not be parsed.
+#[derive(Clone, Debug, PartialEq)]
+pub enum GrammarError {
+    /// The grammar has no rules.
+    Empty,
+    /// A line which is neither a rule nor a continuation of one, or a
+    /// rule with a `<` which is never closed. Continued rules report the
+    /// line they start on.
+    Malformed { line: usize },
+    /// A non-terminal is used but never defined.
+    Undefined { name: String },
+}
+
+impl fmt::Display for GrammarError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            GrammarError::Empty => write!(f, "the grammar has no rules"),
+            GrammarError::Malformed { line } => write!(f, "line {line} is not a well formed rule"),
+            GrammarError::Undefined { name } => write!(f, "<{name}> is never defined"),
+        }
+    }
+}
+
+impl std::error::Error for GrammarError {}
+
+/// A context free grammar in Backus-Naur form. Each rule is a list of
+/// alternatives, and each alternative is a list of symbols. The first
+/// rule is the start symbol.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Grammar {
+    pub names: Vec<String>,
+    pub rules: Vec<Vec<Vec<Symbol>>>,
+}
+
+impl Grammar {
+    /// Parse a grammar written as BNF, one rule per line:
+    ///
+    /// ```text
+    /// <expr> ::= <expr> <op> <expr> | "(" <expr> ")" | <var>
+    /// <op>   ::= "+" | "*"
+    /// <var>  ::= x | y
+    /// ```
+    ///
+    /// Non-terminals are wrapped in angle brackets. Anything else is a
+    /// terminal, either a bare word or a quoted string, which may contain
+    /// spaces. A line which starts with `|` continues the rule above it.
+    pub fn parse(bnf: &str) -> Result<Grammar, GrammarError> {
+        let mut names: Vec<String> = Vec::new();
+        let mut bodies: Vec<(usize, String)> = Vec::new();
+        for (number, line) in bnf.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            if let Some((name, body)) = line.split_once("::=") {
+                let name = name.trim();
+                if !(name.starts_with('<') && name.ends_with('>')) {
+                    return Err(GrammarError::Malformed { line: number + 1 });
+                }
+                names.push(name[1..name.len() - 1].to_string());
+                bodies.push((number + 1, body.to_string()));
+            } else if let (true, Some((_, body))) = (line.starts_with('|'), bodies.last_mut()) {
+                body.push(' ');
+                body.push_str(line);
+            } else {
+                return Err(GrammarError::Malformed { line: number + 1 });
+            }
+        }
+
+        if names.is_empty() {
+            return Err(GrammarError::Empty);
+        }
+
+        let rules = bodies
+            .iter()
+            .map(|(line, body)| {
+                split_alternatives(body)
+                    .iter()
+                    .map(|alternative| tokenize(alternative, &names, *line))
+                    .collect::<Result<Vec<Vec<Symbol>>, GrammarError>>()
+            })
+            .collect::<Result<Vec<Vec<Vec<Symbol>>>, GrammarError>>()?;
+
+        Ok(Grammar { names, rules })
+    }
+
+    /// Map a string of codons to a phenotype with a leftmost derivation.
+    /// Each time a rule with more than one alternative is expanded, the
+    /// next codon modulo the number of alternatives picks which one. When
+    /// the codons run out, reading wraps back to the start up to
+    /// `max_wraps` times. Returns `None` when the derivation does not
+    /// finish within that budget.
+    pub fn map(&self, codons: &[u32], max_wraps: usize) -> Option<String> {
+        let budget = codons.len() * (max_wraps + 1);
+        let mut used = 0;
+        let mut phenotype = String::new();
+        let mut stack: Vec<&Symbol> = Vec::new();
+        let start = Symbol::NonTerminal(0);
+        stack.push(&start);
+
+        while let Some(symbol) = stack.pop() {
+            match symbol {
+                Symbol::Terminal(text) => phenotype.push_str(text),
+                Symbol::NonTerminal(rule) => {
+                    let alternatives = &self.rules[*rule];
+                    let choice = if alternatives.len() == 1 {
+                        0
+                    } else {
+                        if used >= budget {
+                            return None;
+                        }
+                        let codon = codons[used % codons.len()];
+                        used += 1;
+                        codon as usize % alternatives.len()
+                    };
+                    stack.extend(alternatives[choice].iter().rev());
+                }
+            }
+        }
+
+        Some(phenotype)
+    }
+}
+
+/// Split a rule body on `|`, ignoring any inside quotes.
+fn split_alternatives(body: &str) -> Vec<String> {
+    let mut alternatives = vec![String::new()];
+    let mut quote: Option<char> = None;
+    for character in body.chars() {
+        match (quote, character) {
+            (None, '|') => alternatives.push(String::new()),
+            (None, '"' | '\'') => {
+                quote = Some(character);
+                alternatives.last_mut().unwrap().push(character);
+            }
+            (Some(open), _) if open == character => {
+                quote = None;
+                alternatives.last_mut().unwrap().push(character);
+            }
+            _ => alternatives.last_mut().unwrap().push(character),
+        }
+    }
+
+    alternatives
+}
+
+/// Turn one alternative of the rule on `line` into symbols.
+fn tokenize(alternative: &str, names: &[String], line: usize) -> Result<Vec<Symbol>, GrammarError> {
+    let mut symbols = Vec::new();
+    let mut rest = alternative.trim_start();
+    while let Some(first) = rest.chars().next() {
+        let (symbol, length) = match first {
+            '<' => {
+                let end = rest.find('>').ok_or(GrammarError::Malformed { line })?;
+                let name = &rest[1..end];
+                let rule = names
+                    .iter()
+                    .position(|candidate| candidate == name)
+                    .ok_or_else(|| GrammarError::Undefined {
+                        name: name.to_string(),
+                    })?;
+                (Symbol::NonTerminal(rule), end + 1)
+            }
+            '"' | '\'' => {
+                let end = rest[1..].find(first).map_or(rest.len(), |end| end + 1);
+                (
+                    Symbol::Terminal(rest[1..end].to_string()),
+                    (end + 1).min(rest.len()),
+                )
+            }
+            _ => {
+                let end = rest
+                    .find(|c: char| c.is_whitespace() || c == '<' || c == '"' || c == '\'')
+                    .unwrap_or(rest.len());
+                (Symbol::Terminal(rest[..end].to_string()), end)
+            }
+        };
+        symbols.push(symbol);
+        rest = rest[length..].trim_start();
+    }
+
+    Ok(symbols)
+}
+
+/// A ready made algorithm for grammatical evolution. The genome is a
+/// string of integer codons which is mapped through the grammar to a
+/// phenotype. Codons are recombined with one-point crossover and each
+/// is replaced by a random codon with probability `mutation_factor`.
+///
+/// The output is the phenotype, or `None` when the codons could not be
+/// mapped to a complete program. Analyzers should give those the worst
+/// possible score.
+#[derive(Clone, Debug)]
+pub struct GrammaticalEvolution {
+    pub grammar: Grammar,
+    /// How many codons each genome holds.
+    pub length: usize,
+    /// How many times the mapping may wrap around the codons.
+    pub max_wraps: usize,
+    /// Codons are drawn from `0..codon_range`.
+    pub codon_range: u32,
+}
+
+impl GrammaticalEvolution {
+    /// Grammatical evolution over the given grammar, with 100 codons of
+    /// 8 bits each and up to two wraps.
+    pub fn new(grammar: Grammar) -> Self {
+        GrammaticalEvolution {
+            grammar,
+            length: 100,
+            max_wraps: 2,
+            codon_range: 256,
+        }
+    }
+}
+
+impl<InputData: Send + Sync, FeatureFlags>
+    Algorithm<InputData, Option<String>, Vec<u32>, FeatureFlags> for GrammaticalEvolution
+{
+    fn output(
+        &self,
+        node: &Node<Vec<u32>>,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Option<String> {
+        self.grammar.map(&node.solution, self.max_wraps)
+    }
+
+    fn allocate_node(
+        &self,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Node<Vec<u32>> {
+        let mut rng = crate::random::rng();
+        let range = self.codon_range.max(1);
+        Node::new((0..self.length).map(|_| rng.gen_range(0..range)).collect())
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<Vec<u32>>,
+        right: Node<Vec<u32>>,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<Vec<u32>> {
+        let mut rng = crate::random::rng();
+        let rate = params.current_mutation_factor().clamp(0.0, 1.0) as f64;
+        let range = self.codon_range.max(1);
+        let mut solution = one_point(&left.solution, &right.solution);
+        for codon in solution.iter_mut() {
+            if rng.gen_bool(rate) {
+                *codon = rng.gen_range(0..range);
+            }
+        }
+
+        Node::new(solution)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::analyzer::Analyzer, run_algorithm};
+
+    const WORDS: &str = r#"
+        <word>   ::= <letter> <word> | <letter>
+        <letter> ::= "a" | 'b'
+                   | c
+    "#;
+
+    #[test]
+    fn grammars_parse_and_map() {
+        let grammar = Grammar::parse(WORDS).unwrap();
+        assert_eq!(grammar.names, vec!["word", "letter"]);
+        assert_eq!(grammar.rules[1].len(), 3);
+
+        // word -> letter word (0), letter -> b (1), word -> letter (1), letter -> c (2)
+        assert_eq!(grammar.map(&[0, 1, 1, 2], 0), Some("bc".to_string()));
+        // Always choosing to recurse never finishes
+        assert_eq!(grammar.map(&[0, 0], 2), None);
+
+        assert_eq!(
+            Grammar::parse("<a> ::= <b>"),
+            Err(GrammarError::Undefined {
+                name: "b".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn unclosed_non_terminals_are_malformed() {
+        // A continued rule is reported on the line it starts on
+        let grammars = [
+            ("<a> ::= x <", 1),
+            ("<a> ::= <ab", 1),
+            ("<a> ::= x\n<b> ::= y\n | <a", 2),
+        ];
+        for (bnf, line) in grammars {
+            assert_eq!(Grammar::parse(bnf), Err(GrammarError::Malformed { line }));
+        }
+    }
+
+    struct Abba {}
+    impl Analyzer<(), Option<String>, ()> for Abba {
+        fn evaluate(&self, output: &Option<String>, _params: &TestParameters<()>) -> f32 {
+            match output {
+                Some(word) => {
+                    let matches = word.chars().zip("abba".chars()).filter(|(l, r)| l == r);
+                    matches.count() as f32 - word.len().abs_diff(4) as f32
+                }
+                None => f32::MIN,
+            }
+        }
+    }
+
+    #[test]
+    fn words_evolve_from_a_grammar() {
+        let params = TestParameters {
+            generations: 50,
+            population: 100,
+            mutation_factor: 0.05,
+            ..Default::default()
+        };
+        let algo = GrammaticalEvolution {
+            length: 20,
+            ..GrammaticalEvolution::new(Grammar::parse(WORDS).unwrap())
+        };
+
+        let result = run_algorithm(&params, &(), &algo, &Abba {}, None);
+        assert_eq!(result.output.unwrap(), Some("abba".to_string()));
+    }
+}
@@ -0,0 +1,166 @@
+use super::bit_string::BitString;
+use crate::models::{
+    algorithm::Algorithm, distance::Distance, node::Node, test_parameters::TestParameters,
+};
+
+/// Convert a binary number to its reflected Gray code. Consecutive
+/// numbers differ by exactly one bit in Gray code.
+pub fn gray_encode(value: u64) -> u64 {
+    value ^ (value >> 1)
+}
+
+/// Convert a reflected Gray code back to a binary number.
+pub fn gray_decode(mut gray: u64) -> u64 {
+    let mut shift = 1;
+    while shift < u64::BITS {
+        gray ^= gray >> shift;
+        shift <<= 1;
+    }
+
+    gray
+}
+
+/// Read bits, most significant first, as a Gray coded number. At most the
+/// last 64 bits are used.
+pub fn bits_to_gray(bits: &[bool]) -> u64 {
+    let start = bits.len().saturating_sub(u64::BITS as usize);
+    bits[start..]
+        .iter()
+        .fold(0, |value, bit| (value << 1) | *bit as u64)
+}
+
+/// Write a number as `width` Gray coded bits, most significant first.
+pub fn gray_to_bits(value: u64, width: usize) -> Vec<bool> {
+    let gray = gray_encode(value);
+    (0..width)
+        .rev()
+        .map(|bit| bit < u64::BITS as usize && (gray >> bit) & 1 == 1)
+        .collect()
+}
+
+/// A genome adapter which stores real valued parameters as Gray coded
+/// bit strings. Each parameter takes `bits` bits and is decoded evenly
+/// across its inclusive bounds. Because neighboring values differ by a
+/// single bit, a bit flip usually moves a parameter by a small step,
+/// which avoids the Hamming cliffs of plain binary encoding.
+///
+/// The solution is the bit string and the output is the decoded
+/// parameters, so only an analyzer over `Vec<f64>` needs to be written.
+/// Crossover and mutation are those of the wrapped `BitString`.
+#[derive(Clone, Debug)]
+pub struct GrayCoded {
+    pub bits: usize,
+    pub bounds: Vec<(f64, f64)>,
+    pub bit_string: BitString,
+}
+
+impl GrayCoded {
+    /// Gray coded parameters with the given bounds and bits per
+    /// parameter.
+    pub fn new(bounds: Vec<(f64, f64)>, bits: usize) -> Self {
+        let bits = bits.clamp(1, u64::BITS as usize);
+        GrayCoded {
+            bit_string: BitString::new(bits * bounds.len()),
+            bits,
+            bounds,
+        }
+    }
+
+    /// Decode a bit string into its parameters.
+    pub fn decode(&self, bits: &[bool]) -> Vec<f64> {
+        let largest = (u64::MAX >> (u64::BITS as usize - self.bits)) as f64;
+        bits.chunks(self.bits)
+            .zip(&self.bounds)
+            .map(|(chunk, (low, high))| {
+                let value = gray_decode(bits_to_gray(chunk)) as f64;
+                low + (high - low) * value / largest
+            })
+            .collect()
+    }
+}
+
+impl<InputData: Send + Sync, FeatureFlags> Algorithm<InputData, Vec<f64>, Vec<bool>, FeatureFlags>
+    for GrayCoded
+{
+    fn output(
+        &self,
+        node: &Node<Vec<bool>>,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Vec<f64> {
+        self.decode(&node.solution)
+    }
+
+    fn allocate_node(
+        &self,
+        input: &InputData,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<Vec<bool>> {
+        Algorithm::<InputData, Vec<bool>, Vec<bool>, FeatureFlags>::allocate_node(
+            &self.bit_string,
+            input,
+            params,
+        )
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<Vec<bool>>,
+        right: Node<Vec<bool>>,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<Vec<bool>> {
+        Algorithm::<InputData, Vec<bool>, Vec<bool>, FeatureFlags>::combine_node(
+            &self.bit_string,
+            left,
+            right,
+            params,
+        )
+    }
+
+    fn distance(&self) -> Option<&(dyn Distance<Vec<bool>> + Sync)> {
+        Algorithm::<InputData, Vec<bool>, Vec<bool>, FeatureFlags>::distance(&self.bit_string)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::analyzer::Analyzer, run_algorithm};
+
+    #[test]
+    fn neighbors_differ_by_one_bit() {
+        for value in 0..1000u64 {
+            assert_eq!(gray_decode(gray_encode(value)), value);
+            let step = gray_encode(value) ^ gray_encode(value + 1);
+            assert_eq!(step.count_ones(), 1);
+            assert_eq!(gray_decode(bits_to_gray(&gray_to_bits(value, 10))), value);
+        }
+    }
+
+    struct Sphere {}
+    impl Analyzer<(), Vec<f64>, ()> for Sphere {
+        fn evaluate(&self, output: &Vec<f64>, _params: &TestParameters<()>) -> f32 {
+            -output.iter().map(|x| (x - 1.0) * (x - 1.0)).sum::<f64>() as f32
+        }
+    }
+
+    #[test]
+    fn gray_coded_parameters_decode_within_bounds() {
+        let algo = GrayCoded::new(vec![(-5.0, 5.0); 3], 12);
+        assert_eq!(algo.decode(&[false; 36]), vec![-5.0; 3]);
+
+        let params = TestParameters {
+            generations: 50,
+            population: 100,
+            mutation_factor: 0.02,
+            ..Default::default()
+        };
+        let result = run_algorithm(&params, &(), &algo, &Sphere {}, None);
+        assert!(result
+            .output
+            .unwrap()
+            .iter()
+            .all(|x| (-5.0..=5.0).contains(x)));
+        assert!(result.score > -1.0);
+    }
+}
@@ -0,0 +1,169 @@
+use crate::{
+    models::{
+        algorithm::Algorithm,
+        distance::{Distance, Manhattan},
+        node::Node,
+        test_parameters::TestParameters,
+    },
+    operators::{creep_mutation, one_point, random_reset, two_point, uniform},
+};
+use rand::prelude::*;
+
+/// The crossover used to combine two integer vectors.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum IntegerCrossover {
+    /// Split both parents at one random point.
+    OnePoint,
+    /// Swap the segment between two random points.
+    TwoPoint,
+    /// Pick every gene from either parent, favoring the first parent with
+    /// probability `crossover_factor`.
+    #[default]
+    Uniform,
+}
+
+/// The mutation applied to each gene of an integer vector with
+/// probability `mutation_factor`.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum IntegerMutation {
+    /// Replace the gene with a random value from its range.
+    #[default]
+    RandomReset,
+    /// Move the gene up or down by at most `step`, which suits ordinal
+    /// values where nearby numbers mean similar things.
+    Creep { step: i64 },
+}
+
+/// A ready made algorithm for problems encoded as a fixed length vector
+/// of integers, each with its own range. The solution and the output are
+/// both the vector itself, so only an analyzer needs to be written.
+#[derive(Clone, Debug)]
+pub struct IntegerVector {
+    /// The inclusive `(low, high)` range of each gene. This also sets
+    /// the length of the vector.
+    pub bounds: Vec<(i64, i64)>,
+    pub crossover: IntegerCrossover,
+    pub mutation: IntegerMutation,
+}
+
+impl IntegerVector {
+    /// An integer vector with the given ranges, using the default
+    /// operators.
+    pub fn new(bounds: Vec<(i64, i64)>) -> Self {
+        IntegerVector {
+            bounds,
+            crossover: IntegerCrossover::default(),
+            mutation: IntegerMutation::default(),
+        }
+    }
+}
+
+impl<InputData: Send + Sync, FeatureFlags> Algorithm<InputData, Vec<i64>, Vec<i64>, FeatureFlags>
+    for IntegerVector
+{
+    fn output(
+        &self,
+        node: &Node<Vec<i64>>,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Vec<i64> {
+        node.solution.clone()
+    }
+
+    fn allocate_node(
+        &self,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Node<Vec<i64>> {
+        let mut rng = crate::random::rng();
+        Node::new(
+            self.bounds
+                .iter()
+                .map(|(low, high)| rng.gen_range(*low..=*high.max(low)))
+                .collect(),
+        )
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<Vec<i64>>,
+        right: Node<Vec<i64>>,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<Vec<i64>> {
+        let mut solution = match self.crossover {
+            IntegerCrossover::OnePoint => one_point(&left.solution, &right.solution),
+            IntegerCrossover::TwoPoint => two_point(&left.solution, &right.solution),
+            IntegerCrossover::Uniform => uniform(
+                &left.solution,
+                &right.solution,
+                params.current_crossover_factor(),
+            ),
+        };
+
+        match self.mutation {
+            IntegerMutation::RandomReset => random_reset(
+                &mut solution,
+                params.current_mutation_factor(),
+                &self.bounds,
+            ),
+            IntegerMutation::Creep { step } => creep_mutation(
+                &mut solution,
+                params.current_mutation_factor(),
+                &self.bounds,
+                step,
+            ),
+        }
+
+        Node::new(solution)
+    }
+
+    fn distance(&self) -> Option<&(dyn Distance<Vec<i64>> + Sync)> {
+        Some(&Manhattan)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::analyzer::Analyzer, run_algorithm};
+
+    /// Rewards genes which are close to their index.
+    struct Staircase {}
+    impl Analyzer<(), Vec<i64>, ()> for Staircase {
+        fn evaluate(&self, output: &Vec<i64>, _params: &TestParameters<()>) -> f32 {
+            -output
+                .iter()
+                .enumerate()
+                .map(|(idx, gene)| (idx as i64 - gene).abs())
+                .sum::<i64>() as f32
+        }
+    }
+
+    #[test]
+    fn staircase_needs_only_an_analyzer() {
+        let params = TestParameters {
+            generations: 50,
+            population: 100,
+            mutation_factor: 0.1,
+            ..Default::default()
+        };
+
+        for (crossover, mutation) in [
+            (IntegerCrossover::Uniform, IntegerMutation::RandomReset),
+            (
+                IntegerCrossover::TwoPoint,
+                IntegerMutation::Creep { step: 2 },
+            ),
+        ] {
+            let algo = IntegerVector {
+                crossover,
+                mutation,
+                ..IntegerVector::new(vec![(-10, 10); 8])
+            };
+            let result = run_algorithm(&params, &(), &algo, &Staircase {}, None);
+            let solution = result.node.unwrap().solution;
+            assert!(solution.iter().all(|gene| (-10..=10).contains(gene)));
+            assert!(result.score >= -4.0);
+        }
+    }
+}
@@ -0,0 +1,249 @@
+use super::tree::Primitive;
+use crate::{
+    models::{algorithm::Algorithm, node::Node, test_parameters::TestParameters},
+    operators::segment_crossover,
+};
+use rand::prelude::*;
+
+/// A single register machine instruction, which applies an operation to
+/// the operand registers and stores the result in the destination.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Instruction {
+    pub operation: usize,
+    pub destination: usize,
+    pub operands: Vec<usize>,
+}
+
+/// A linear program: a sequence of instructions which run in order over
+/// a bank of registers.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LinearProgram {
+    pub instructions: Vec<Instruction>,
+}
+
+impl LinearProgram {
+    /// Run every instruction in order. `apply` is given each operation
+    /// along with the values of its operands.
+    pub fn execute<P: Primitive, Value: Clone>(
+        &self,
+        registers: &mut [Value],
+        operations: &[P],
+        apply: impl Fn(&P, &[Value]) -> Value,
+    ) {
+        for instruction in self.instructions.iter() {
+            let operation = &operations[instruction.operation];
+            let arguments: Vec<Value> = instruction
+                .operands
+                .iter()
+                .take(operation.arity())
+                .map(|register| registers[*register].clone())
+                .collect();
+            registers[instruction.destination] = apply(operation, &arguments);
+        }
+    }
+}
+
+/// A ready made algorithm for linear genetic programming. Programs are
+/// variable length sequences of instructions over `calculation`
+/// registers, which may be written, followed by `inputs` read only
+/// registers. By convention the result is read from register 0.
+///
+/// Offspring are created with two-segment crossover, so their length may
+/// differ from both parents, followed by macro mutation, which inserts or
+/// deletes whole instructions, and micro mutation, which changes a
+/// single part of an instruction. Both happen with probability
+/// `mutation_factor` per instruction. The output is the program itself,
+/// which the analyzer runs with `LinearProgram::execute`.
+#[derive(Clone, Debug)]
+pub struct LinearGp<P> {
+    pub operations: Vec<P>,
+    pub calculation: usize,
+    pub inputs: usize,
+    /// The inclusive range of lengths for the initial population.
+    pub initial_length: (usize, usize),
+    /// Offspring longer than this are truncated.
+    pub max_length: usize,
+}
+
+impl<P: Primitive> LinearGp<P> {
+    /// Linear GP with initial programs of 5 to 20 instructions and a
+    /// length limit of 200.
+    pub fn new(operations: Vec<P>, calculation: usize, inputs: usize) -> Self {
+        LinearGp {
+            operations,
+            calculation,
+            inputs,
+            initial_length: (5, 20),
+            max_length: 200,
+        }
+    }
+
+    /// A random instruction.
+    pub fn instruction(&self, rng: &mut impl Rng) -> Instruction {
+        let arity = self
+            .operations
+            .iter()
+            .map(Primitive::arity)
+            .max()
+            .unwrap_or(0);
+        Instruction {
+            operation: rng.gen_range(0..self.operations.len().max(1)),
+            destination: rng.gen_range(0..self.calculation.max(1)),
+            operands: (0..arity).map(|_| self.operand(rng)).collect(),
+        }
+    }
+
+    fn operand(&self, rng: &mut impl Rng) -> usize {
+        rng.gen_range(0..(self.calculation + self.inputs).max(1))
+    }
+
+    fn micro_mutation(&self, instruction: &mut Instruction, rng: &mut impl Rng) {
+        let part = rng.gen_range(0..2 + instruction.operands.len());
+        match part {
+            0 => instruction.operation = rng.gen_range(0..self.operations.len().max(1)),
+            1 => instruction.destination = rng.gen_range(0..self.calculation.max(1)),
+            operand => instruction.operands[operand - 2] = self.operand(rng),
+        }
+    }
+}
+
+impl<InputData: Send + Sync, FeatureFlags, P: Primitive>
+    Algorithm<InputData, LinearProgram, LinearProgram, FeatureFlags> for LinearGp<P>
+{
+    fn output(
+        &self,
+        node: &Node<LinearProgram>,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> LinearProgram {
+        node.solution.clone()
+    }
+
+    fn allocate_node(
+        &self,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Node<LinearProgram> {
+        let mut rng = crate::random::rng();
+        let (low, high) = self.initial_length;
+        let length = rng.gen_range(low..=high.max(low));
+        Node::new(LinearProgram {
+            instructions: (0..length).map(|_| self.instruction(&mut rng)).collect(),
+        })
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<LinearProgram>,
+        right: Node<LinearProgram>,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<LinearProgram> {
+        let mut rng = crate::random::rng();
+        let rate = params.current_mutation_factor().clamp(0.0, 1.0) as f64;
+        let crossed = segment_crossover(&left.solution.instructions, &right.solution.instructions);
+
+        let mut instructions = Vec::with_capacity(crossed.len());
+        for mut instruction in crossed {
+            // Macro mutation deletes or inserts whole instructions
+            if rng.gen_bool(rate) {
+                if rng.gen_bool(0.5) {
+                    continue;
+                }
+                instructions.push(self.instruction(&mut rng));
+            }
+
+            if rng.gen_bool(rate) {
+                self.micro_mutation(&mut instruction, &mut rng);
+            }
+            instructions.push(instruction);
+        }
+        instructions.truncate(self.max_length);
+
+        Node::new(LinearProgram { instructions })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::analyzer::Analyzer, run_algorithm};
+
+    #[derive(Clone, Debug)]
+    enum Op {
+        Add,
+        Sub,
+        Mul,
+    }
+
+    impl Primitive for Op {
+        fn arity(&self) -> usize {
+            2
+        }
+    }
+
+    const OPS: [Op; 3] = [Op::Add, Op::Sub, Op::Mul];
+
+    fn run(program: &LinearProgram, x: f32) -> f32 {
+        // Two calculation registers, then the input and a constant
+        let mut registers = [0.0, 0.0, x, 1.0];
+        program.execute(&mut registers, &OPS, |op, args| match op {
+            Op::Add => args[0] + args[1],
+            Op::Sub => args[0] - args[1],
+            Op::Mul => args[0] * args[1],
+        });
+        registers[0]
+    }
+
+    /// Symbolic regression of `x * x - 1`.
+    struct Regression {}
+    impl Analyzer<(), LinearProgram, ()> for Regression {
+        fn evaluate(&self, attempt: &LinearProgram, _params: &TestParameters<()>) -> f32 {
+            let error: f32 = (-5..=5)
+                .map(|x| x as f32)
+                .map(|x| (run(attempt, x) - (x * x - 1.0)).abs())
+                .sum();
+            if error.is_finite() {
+                -error
+            } else {
+                f32::MIN
+            }
+        }
+    }
+
+    #[test]
+    fn programs_run_in_order() {
+        let program = LinearProgram {
+            instructions: vec![
+                Instruction {
+                    operation: 2,
+                    destination: 1,
+                    operands: vec![2, 2],
+                },
+                Instruction {
+                    operation: 1,
+                    destination: 0,
+                    operands: vec![1, 3],
+                },
+            ],
+        };
+        assert_eq!(run(&program, 3.0), 8.0);
+    }
+
+    #[test]
+    fn regression_needs_only_an_analyzer() {
+        let params = TestParameters {
+            generations: 50,
+            population: 200,
+            mutation_factor: 0.05,
+            ..Default::default()
+        };
+        let algo = LinearGp {
+            max_length: 40,
+            ..LinearGp::new(OPS.to_vec(), 2, 2)
+        };
+
+        let result = run_algorithm(&params, &(), &algo, &Regression {}, None);
+        assert!(result.node.unwrap().solution.instructions.len() <= 40);
+        assert_eq!(result.score, 0.0);
+    }
+}
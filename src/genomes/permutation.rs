@@ -0,0 +1,154 @@
+use crate::{
+    models::{
+        algorithm::Algorithm,
+        distance::{Distance, KendallTau},
+        node::Node,
+        test_parameters::TestParameters,
+    },
+    operators::{inversion_mutation, order_crossover, partially_mapped, swap_mutation},
+};
+use rand::prelude::*;
+
+/// The crossover used to combine two permutations.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum PermutationCrossover {
+    /// Order crossover, which preserves the relative order of values.
+    #[default]
+    Order,
+    /// Partially mapped crossover, which preserves absolute positions.
+    PartiallyMapped,
+}
+
+/// The mutation used on a permutation. Each position is mutated with
+/// probability `mutation_factor`.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum PermutationMutation {
+    /// Swap the value with the value at another random position.
+    Swap,
+    /// Reverse the segment between the position and another random
+    /// position, which suits problems such as routing where adjacency
+    /// matters.
+    #[default]
+    Inversion,
+}
+
+/// A ready made algorithm for problems encoded as an ordering of `length`
+/// items, such as routing and scheduling. The solution and the output
+/// are both a permutation of `0..length`, so only an analyzer needs to
+/// be written.
+#[derive(Clone, Copy, Debug)]
+pub struct Permutation {
+    pub length: usize,
+    pub crossover: PermutationCrossover,
+    pub mutation: PermutationMutation,
+}
+
+impl Permutation {
+    /// A permutation of `0..length` using the default operators.
+    pub fn new(length: usize) -> Self {
+        Permutation {
+            length,
+            crossover: PermutationCrossover::default(),
+            mutation: PermutationMutation::default(),
+        }
+    }
+}
+
+impl<InputData: Send + Sync, FeatureFlags>
+    Algorithm<InputData, Vec<usize>, Vec<usize>, FeatureFlags> for Permutation
+{
+    fn output(
+        &self,
+        node: &Node<Vec<usize>>,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Vec<usize> {
+        node.solution.clone()
+    }
+
+    fn allocate_node(
+        &self,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Node<Vec<usize>> {
+        let mut solution: Vec<usize> = (0..self.length).collect();
+        solution.shuffle(&mut crate::random::rng());
+        Node::new(solution)
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<Vec<usize>>,
+        right: Node<Vec<usize>>,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<Vec<usize>> {
+        let mut solution = match self.crossover {
+            PermutationCrossover::Order => order_crossover(&left.solution, &right.solution),
+            PermutationCrossover::PartiallyMapped => {
+                partially_mapped(&left.solution, &right.solution)
+            }
+        };
+
+        match self.mutation {
+            PermutationMutation::Swap => {
+                swap_mutation(&mut solution, params.current_mutation_factor())
+            }
+            PermutationMutation::Inversion => {
+                inversion_mutation(&mut solution, params.current_mutation_factor())
+            }
+        }
+
+        Node::new(solution)
+    }
+
+    fn distance(&self) -> Option<&(dyn Distance<Vec<usize>> + Sync)> {
+        Some(&KendallTau)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::analyzer::Analyzer, run_algorithm};
+
+    /// Rewards every value which sits at its own index.
+    struct Sorted {}
+    impl Analyzer<(), Vec<usize>, ()> for Sorted {
+        fn evaluate(&self, output: &Vec<usize>, _params: &TestParameters<()>) -> f32 {
+            output
+                .iter()
+                .enumerate()
+                .filter(|(idx, value)| idx == *value)
+                .count() as f32
+        }
+    }
+
+    #[test]
+    fn sorting_needs_only_an_analyzer() {
+        let params = TestParameters {
+            generations: 50,
+            population: 100,
+            mutation_factor: 0.05,
+            ..Default::default()
+        };
+
+        for (crossover, mutation) in [
+            (PermutationCrossover::Order, PermutationMutation::Inversion),
+            (
+                PermutationCrossover::PartiallyMapped,
+                PermutationMutation::Swap,
+            ),
+        ] {
+            let algo = Permutation {
+                length: 10,
+                crossover,
+                mutation,
+            };
+            let result = run_algorithm(&params, &(), &algo, &Sorted {}, None);
+            let mut solution = result.node.unwrap().solution;
+            solution.sort_unstable();
+            assert_eq!(solution, (0..10).collect::<Vec<usize>>());
+            assert!(result.score >= 6.0);
+        }
+    }
+}
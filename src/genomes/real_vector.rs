@@ -0,0 +1,187 @@
+use crate::{
+    models::{
+        algorithm::Algorithm,
+        distance::{Distance, Euclidean},
+        node::Node,
+        test_parameters::TestParameters,
+    },
+    operators::{arithmetic, gaussian_mutation, polynomial_mutation, simulated_binary},
+};
+use rand::prelude::*;
+
+/// The crossover used to combine two real vectors.
+#[derive(Clone, Copy, Debug)]
+pub enum RealCrossover {
+    /// Blend both parents with a single random weight.
+    Arithmetic,
+    /// Simulated binary crossover. A larger `eta` keeps the child closer
+    /// to its parents.
+    SimulatedBinary { eta: f64 },
+}
+
+impl Default for RealCrossover {
+    fn default() -> Self {
+        RealCrossover::SimulatedBinary { eta: 15.0 }
+    }
+}
+
+/// The mutation applied to each gene of a real vector with probability
+/// `mutation_factor`.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum RealMutation {
+    /// Add gaussian noise, using the step size of the gene as the
+    /// standard deviation.
+    #[default]
+    Gaussian,
+    /// Polynomial mutation, which scales its moves to the bounds of the
+    /// gene. A larger `eta` makes smaller moves.
+    Polynomial { eta: f64 },
+}
+
+/// A ready made algorithm for problems encoded as a fixed length vector
+/// of real numbers, each with its own bounds. The solution and the
+/// output are both the vector itself, so only an analyzer needs to be
+/// written. Genes are always kept within their bounds.
+#[derive(Clone, Debug)]
+pub struct RealVector {
+    /// The inclusive `(low, high)` range of each gene. This also sets
+    /// the length of the vector.
+    pub bounds: Vec<(f64, f64)>,
+    /// The standard deviation of gaussian mutation for each gene.
+    pub step_sizes: Vec<f64>,
+    pub crossover: RealCrossover,
+    pub mutation: RealMutation,
+}
+
+impl RealVector {
+    /// A real vector with the given bounds, using the default operators
+    /// and a step size of a tenth of each gene's range.
+    pub fn new(bounds: Vec<(f64, f64)>) -> Self {
+        let step_sizes = bounds
+            .iter()
+            .map(|(low, high)| (high - low) * 0.1)
+            .collect();
+        RealVector {
+            bounds,
+            step_sizes,
+            crossover: RealCrossover::default(),
+            mutation: RealMutation::default(),
+        }
+    }
+
+    fn clamp(&self, genes: &mut [f64]) {
+        for (gene, (low, high)) in genes.iter_mut().zip(&self.bounds) {
+            *gene = gene.clamp(*low, *high);
+        }
+    }
+}
+
+impl<InputData: Send + Sync, FeatureFlags> Algorithm<InputData, Vec<f64>, Vec<f64>, FeatureFlags>
+    for RealVector
+{
+    fn output(
+        &self,
+        node: &Node<Vec<f64>>,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Vec<f64> {
+        node.solution.clone()
+    }
+
+    fn allocate_node(
+        &self,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Node<Vec<f64>> {
+        let mut rng = crate::random::rng();
+        Node::new(
+            self.bounds
+                .iter()
+                .map(|(low, high)| {
+                    if low < high {
+                        rng.gen_range(*low..=*high)
+                    } else {
+                        *low
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<Vec<f64>>,
+        right: Node<Vec<f64>>,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<Vec<f64>> {
+        let mut solution = match self.crossover {
+            RealCrossover::Arithmetic => arithmetic(&left.solution, &right.solution),
+            RealCrossover::SimulatedBinary { eta } => {
+                simulated_binary(&left.solution, &right.solution, eta)
+            }
+        };
+        self.clamp(&mut solution);
+
+        match self.mutation {
+            RealMutation::Gaussian => gaussian_mutation(
+                &mut solution,
+                params.current_mutation_factor(),
+                &self.step_sizes,
+            ),
+            RealMutation::Polynomial { eta } => polynomial_mutation(
+                &mut solution,
+                params.current_mutation_factor(),
+                &self.bounds,
+                eta,
+            ),
+        }
+        self.clamp(&mut solution);
+
+        Node::new(solution)
+    }
+
+    fn distance(&self) -> Option<&(dyn Distance<Vec<f64>> + Sync)> {
+        Some(&Euclidean)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::analyzer::Analyzer, run_algorithm};
+
+    struct Sphere {}
+    impl Analyzer<(), Vec<f64>, ()> for Sphere {
+        fn evaluate(&self, output: &Vec<f64>, _params: &TestParameters<()>) -> f32 {
+            -output.iter().map(|x| x * x).sum::<f64>() as f32
+        }
+    }
+
+    #[test]
+    fn sphere_needs_only_an_analyzer() {
+        let params = TestParameters {
+            generations: 50,
+            population: 100,
+            mutation_factor: 0.2,
+            ..Default::default()
+        };
+
+        for (crossover, mutation) in [
+            (RealCrossover::default(), RealMutation::Gaussian),
+            (
+                RealCrossover::Arithmetic,
+                RealMutation::Polynomial { eta: 20.0 },
+            ),
+        ] {
+            let algo = RealVector {
+                crossover,
+                mutation,
+                ..RealVector::new(vec![(-5.0, 5.0); 4])
+            };
+            let result = run_algorithm(&params, &(), &algo, &Sphere {}, None);
+            let solution = result.node.unwrap().solution;
+            assert!(solution.iter().all(|x| (-5.0..=5.0).contains(x)));
+            assert!(result.score > -1.0);
+        }
+    }
+}
@@ -0,0 +1,146 @@
+use crate::{
+    models::{algorithm::Algorithm, node::Node, test_parameters::TestParameters},
+    operators::{gaussian, log_normal_update},
+};
+use rand::prelude::*;
+
+/// A real valued genome which carries its own mutation step sizes, one
+/// per gene. The step sizes evolve along with the genes, so the search
+/// learns how far to step in each direction without any tuning.
+#[derive(Clone, Debug, PartialEq)]
+pub struct SelfAdaptive {
+    pub genes: Vec<f64>,
+    pub step_sizes: Vec<f64>,
+}
+
+/// A ready made algorithm for evolution strategy style self-adaptation
+/// over bounded real vectors. Offspring take each gene from a random
+/// parent and average the parents' step sizes. The step sizes are then
+/// updated with the log-normal rule, and every gene is moved by gaussian
+/// noise using its new step size. `mutation_factor` is not used, since
+/// every gene is mutated.
+///
+/// The solution is the `SelfAdaptive` genome and the output is just its
+/// genes, so only an analyzer over `Vec<f64>` needs to be written.
+#[derive(Clone, Debug)]
+pub struct SelfAdaptiveVector {
+    /// The inclusive `(low, high)` range of each gene. This also sets
+    /// the length of the vector.
+    pub bounds: Vec<(f64, f64)>,
+    /// The step size of every gene in the initial population, as a
+    /// fraction of its range.
+    pub initial_step: f64,
+    /// Step sizes never shrink below this.
+    pub min_step: f64,
+}
+
+impl SelfAdaptiveVector {
+    /// Self-adaptation over the given bounds, starting with steps of a
+    /// tenth of each range.
+    pub fn new(bounds: Vec<(f64, f64)>) -> Self {
+        SelfAdaptiveVector {
+            bounds,
+            initial_step: 0.1,
+            min_step: 1e-6,
+        }
+    }
+}
+
+impl<InputData: Send + Sync, FeatureFlags>
+    Algorithm<InputData, Vec<f64>, SelfAdaptive, FeatureFlags> for SelfAdaptiveVector
+{
+    fn output(
+        &self,
+        node: &Node<SelfAdaptive>,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Vec<f64> {
+        node.solution.genes.clone()
+    }
+
+    fn allocate_node(
+        &self,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Node<SelfAdaptive> {
+        let mut rng = crate::random::rng();
+        Node::new(SelfAdaptive {
+            genes: self
+                .bounds
+                .iter()
+                .map(|(low, high)| {
+                    if low < high {
+                        rng.gen_range(*low..=*high)
+                    } else {
+                        *low
+                    }
+                })
+                .collect(),
+            step_sizes: self
+                .bounds
+                .iter()
+                .map(|(low, high)| ((high - low) * self.initial_step).max(self.min_step))
+                .collect(),
+        })
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<SelfAdaptive>,
+        right: Node<SelfAdaptive>,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Node<SelfAdaptive> {
+        let mut rng = crate::random::rng();
+        let (left, right) = (left.solution, right.solution);
+
+        let mut step_sizes: Vec<f64> = left
+            .step_sizes
+            .iter()
+            .zip(&right.step_sizes)
+            .map(|(l, r)| (l + r) / 2.0)
+            .collect();
+        log_normal_update(&mut step_sizes, self.min_step);
+
+        let genes = left
+            .genes
+            .iter()
+            .zip(&right.genes)
+            .zip(&step_sizes)
+            .zip(&self.bounds)
+            .map(|(((l, r), step), (low, high))| {
+                let gene = if rng.gen_bool(0.5) { l } else { r };
+                (gene + gaussian(0.0, *step)).clamp(*low, *high)
+            })
+            .collect();
+
+        Node::new(SelfAdaptive { genes, step_sizes })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{models::analyzer::Analyzer, run_algorithm};
+
+    struct Sphere {}
+    impl Analyzer<(), Vec<f64>, ()> for Sphere {
+        fn evaluate(&self, output: &Vec<f64>, _params: &TestParameters<()>) -> f32 {
+            -output.iter().map(|x| x * x).sum::<f64>() as f32
+        }
+    }
+
+    #[test]
+    fn step_sizes_shrink_near_the_optimum() {
+        let params = TestParameters {
+            generations: 100,
+            population: 50,
+            ..Default::default()
+        };
+        let algo = SelfAdaptiveVector::new(vec![(-5.0, 5.0); 4]);
+        let result = run_algorithm(&params, &(), &algo, &Sphere {}, None);
+
+        let solution = result.node.unwrap().solution;
+        assert!(result.score > -0.01);
+        assert!(solution.step_sizes.iter().all(|step| *step < 1.0));
+    }
+}
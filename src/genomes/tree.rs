@@ -0,0 +1,371 @@
+use crate::models::{
+    algorithm::Algorithm, analyzer::Analyzer, node::Node, test_parameters::TestParameters,
+};
+use rand::prelude::*;
+
+/// A function or terminal which can appear in an expression tree. The
+/// arity is how many children it takes, so terminals have an arity of
+/// zero. This is usually implemented on a plain enum.
+pub trait Primitive: Clone + Send + Sync {
+    fn arity(&self) -> usize;
+}
+
+/// An expression tree, stored in prefix order. Every subtree occupies a
+/// contiguous run of nodes, which makes copying and splicing cheap.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tree<P> {
+    pub nodes: Vec<P>,
+}
+
+impl<P: Primitive> Tree<P> {
+    /// How many nodes make up the tree.
+    pub fn len(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// Whether the tree has no nodes at all.
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty()
+    }
+
+    /// The index just past the end of the subtree rooted at `start`.
+    pub fn subtree_end(&self, start: usize) -> usize {
+        let mut open = 1;
+        let mut idx = start;
+        while open > 0 && idx < self.nodes.len() {
+            open += self.nodes[idx].arity();
+            open -= 1;
+            idx += 1;
+        }
+
+        idx
+    }
+
+    /// The depth of the tree, where a single terminal has a depth of zero.
+    pub fn depth(&self) -> usize {
+        let mut depth = 0;
+        let mut pending: Vec<usize> = Vec::new();
+        for node in self.nodes.iter() {
+            depth = depth.max(pending.len());
+            if let Some(remaining) = pending.last_mut() {
+                *remaining -= 1;
+            }
+            if node.arity() > 0 {
+                pending.push(node.arity());
+            } else {
+                while pending.last() == Some(&0) {
+                    pending.pop();
+                }
+            }
+        }
+
+        depth
+    }
+
+    /// Evaluate the tree from the leaves up. `apply` is given each node
+    /// along with the values of its children, in order.
+    pub fn evaluate<Value>(&self, apply: impl Fn(&P, Vec<Value>) -> Value) -> Option<Value> {
+        let mut stack: Vec<Value> = Vec::new();
+        for node in self.nodes.iter().rev() {
+            let arity = node.arity();
+            if stack.len() < arity {
+                return None;
+            }
+
+            let mut arguments = stack.split_off(stack.len() - arity);
+            arguments.reverse();
+            stack.push(apply(node, arguments));
+        }
+
+        stack.pop()
+    }
+}
+
+/// The mutation used on an expression tree.
+#[derive(Clone, Copy, Debug)]
+pub enum TreeMutation {
+    /// Each node is replaced, with probability `mutation_factor`, by a
+    /// random primitive with the same arity.
+    Point,
+    /// With probability `mutation_factor`, a random subtree is replaced
+    /// by a freshly grown one of at most `depth`.
+    Subtree { depth: usize },
+}
+
+impl Default for TreeMutation {
+    fn default() -> Self {
+        TreeMutation::Subtree { depth: 3 }
+    }
+}
+
+/// A ready made algorithm for tree based genetic programming. Trees are
+/// built from the given functions and terminals with ramped
+/// half-and-half initialization, recombined with subtree crossover, and
+/// then mutated.
+///
+/// The output is the tree itself, which the analyzer can run with
+/// `Tree::evaluate`. Bloat is held back by `max_depth`, since any
+/// offspring deeper than this is replaced by a copy of its first parent,
+/// and optionally by wrapping the analyzer in `Parsimony`.
+#[derive(Clone, Debug)]
+pub struct TreeGp<P> {
+    /// Primitives with an arity of one or more.
+    pub functions: Vec<P>,
+    /// Primitives with an arity of zero.
+    pub terminals: Vec<P>,
+    /// The inclusive range of depths used for the initial population.
+    pub initial_depth: (usize, usize),
+    pub max_depth: usize,
+    pub mutation: TreeMutation,
+}
+
+impl<P: Primitive> TreeGp<P> {
+    /// Tree GP over the given primitives, with initial depths from 2 to
+    /// 6 and a depth limit of 17.
+    pub fn new(functions: Vec<P>, terminals: Vec<P>) -> Self {
+        TreeGp {
+            functions,
+            terminals,
+            initial_depth: (2, 6),
+            max_depth: 17,
+            mutation: TreeMutation::default(),
+        }
+    }
+
+    /// Grow a random tree. With `full`, every branch reaches exactly
+    /// `depth`, otherwise branches may stop early at a terminal.
+    pub fn generate(&self, depth: usize, full: bool) -> Tree<P> {
+        let mut nodes = Vec::new();
+        self.generate_into(&mut nodes, depth, full, &mut crate::random::rng());
+        Tree { nodes }
+    }
+
+    fn generate_into(&self, nodes: &mut Vec<P>, depth: usize, full: bool, rng: &mut impl Rng) {
+        let total = self.functions.len() + self.terminals.len();
+        let terminal = self.functions.is_empty()
+            || depth == 0
+            || (!full && rng.gen_range(0..total.max(1)) < self.terminals.len());
+
+        if terminal {
+            if let Some(primitive) = self.terminals.choose(rng) {
+                nodes.push(primitive.clone());
+            }
+            return;
+        }
+
+        let function = self.functions.choose(rng).unwrap().clone();
+        let arity = function.arity();
+        nodes.push(function);
+        for _ in 0..arity {
+            self.generate_into(nodes, depth - 1, full, rng);
+        }
+    }
+
+    fn point_mutation(&self, tree: &mut Tree<P>, rate: f32) {
+        let mut rng = crate::random::rng();
+        let rate = rate.clamp(0.0, 1.0) as f64;
+        for node in tree.nodes.iter_mut() {
+            if rng.gen_bool(rate) {
+                let arity = node.arity();
+                let candidates = if arity == 0 {
+                    &self.terminals
+                } else {
+                    &self.functions
+                };
+                let same_arity: Vec<&P> = candidates
+                    .iter()
+                    .filter(|primitive| primitive.arity() == arity)
+                    .collect();
+                if let Some(primitive) = same_arity.choose(&mut rng) {
+                    *node = (*primitive).clone();
+                }
+            }
+        }
+    }
+}
+
+/// Replace the subtree of `target` rooted at `at` with `replacement`.
+fn splice<P: Primitive>(target: &Tree<P>, at: usize, replacement: &[P]) -> Tree<P> {
+    let end = target.subtree_end(at);
+    Tree {
+        nodes: target.nodes[..at]
+            .iter()
+            .chain(replacement)
+            .chain(&target.nodes[end..])
+            .cloned()
+            .collect(),
+    }
+}
+
+impl<InputData: Send + Sync, FeatureFlags, P: Primitive>
+    Algorithm<InputData, Tree<P>, Tree<P>, FeatureFlags> for TreeGp<P>
+{
+    fn output(
+        &self,
+        node: &Node<Tree<P>>,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Tree<P> {
+        node.solution.clone()
+    }
+
+    fn allocate_node(
+        &self,
+        _input: &InputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Node<Tree<P>> {
+        let mut rng = crate::random::rng();
+        let (low, high) = self.initial_depth;
+        let depth = rng.gen_range(low..=high.max(low));
+        Node::new(self.generate(depth, rng.gen_bool(0.5)))
+    }
+
+    fn combine_node(
+        &self,
+        left: Node<Tree<P>>,
+        right: Node<Tree<P>>,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<Tree<P>> {
+        let mut rng = crate::random::rng();
+        if left.solution.is_empty() || right.solution.is_empty() {
+            return Node::new(left.solution);
+        }
+
+        // Subtree crossover
+        let at = rng.gen_range(0..left.solution.len());
+        let from = rng.gen_range(0..right.solution.len());
+        let donor = &right.solution.nodes[from..right.solution.subtree_end(from)];
+        let mut child = splice(&left.solution, at, donor);
+
+        match self.mutation {
+            TreeMutation::Point => {
+                self.point_mutation(&mut child, params.current_mutation_factor())
+            }
+            TreeMutation::Subtree { depth } => {
+                if rng.gen_bool(params.current_mutation_factor().clamp(0.0, 1.0) as f64) {
+                    let at = rng.gen_range(0..child.len());
+                    let grown = self.generate(rng.gen_range(0..=depth), false);
+                    child = splice(&child, at, &grown.nodes);
+                }
+            }
+        }
+
+        if child.depth() > self.max_depth {
+            return Node::new(left.solution);
+        }
+
+        Node::new(child)
+    }
+}
+
+/// An adapter which applies parsimony pressure to a tree GP analyzer,
+/// subtracting `coefficient` for every node in the tree. This favors
+/// smaller trees when two score about the same, which keeps bloat in
+/// check.
+pub struct Parsimony<A> {
+    pub analyzer: A,
+    pub coefficient: f32,
+}
+
+impl<InputData, FeatureFlags, P, A> Analyzer<InputData, Tree<P>, FeatureFlags> for Parsimony<A>
+where
+    P: Primitive,
+    A: Analyzer<InputData, Tree<P>, FeatureFlags>,
+{
+    fn evaluate(&self, attempt: &Tree<P>, params: &TestParameters<FeatureFlags>) -> f32 {
+        self.analyzer.evaluate(attempt, params) - self.coefficient * attempt.len() as f32
+    }
+
+    fn evaluate_cases(
+        &self,
+        attempt: &Tree<P>,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Option<Vec<f32>> {
+        self.analyzer.evaluate_cases(attempt, params)
+    }
+
+    fn violation(&self, attempt: &Tree<P>, params: &TestParameters<FeatureFlags>) -> f32 {
+        self.analyzer.violation(attempt, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::run_algorithm;
+
+    #[derive(Clone, Debug, PartialEq)]
+    enum Op {
+        Add,
+        Mul,
+        X,
+        One,
+    }
+
+    impl Primitive for Op {
+        fn arity(&self) -> usize {
+            match self {
+                Op::Add | Op::Mul => 2,
+                Op::X | Op::One => 0,
+            }
+        }
+    }
+
+    fn run(tree: &Tree<Op>, x: f32) -> f32 {
+        tree.evaluate(|op, args: Vec<f32>| match op {
+            Op::Add => args[0] + args[1],
+            Op::Mul => args[0] * args[1],
+            Op::X => x,
+            Op::One => 1.0,
+        })
+        .unwrap_or(f32::NAN)
+    }
+
+    /// Symbolic regression of `x * x + x`.
+    struct Regression {}
+    impl Analyzer<(), Tree<Op>, ()> for Regression {
+        fn evaluate(&self, attempt: &Tree<Op>, _params: &TestParameters<()>) -> f32 {
+            let error: f32 = (-5..=5)
+                .map(|x| x as f32)
+                .map(|x| (run(attempt, x) - (x * x + x)).abs())
+                .sum();
+            if error.is_finite() {
+                -error
+            } else {
+                f32::MIN
+            }
+        }
+    }
+
+    #[test]
+    fn trees_know_their_shape() {
+        let tree = Tree {
+            nodes: vec![Op::Add, Op::Mul, Op::X, Op::X, Op::One],
+        };
+        assert_eq!(tree.depth(), 2);
+        assert_eq!(tree.subtree_end(1), 4);
+        assert_eq!(run(&tree, 3.0), 10.0);
+    }
+
+    #[test]
+    fn symbolic_regression_needs_only_an_analyzer() {
+        let params = TestParameters {
+            generations: 30,
+            population: 200,
+            mutation_factor: 0.1,
+            ..Default::default()
+        };
+        let algo = TreeGp {
+            max_depth: 6,
+            ..TreeGp::new(vec![Op::Add, Op::Mul], vec![Op::X, Op::One])
+        };
+        let analyzer = Parsimony {
+            analyzer: Regression {},
+            coefficient: 0.001,
+        };
+
+        let result = run_algorithm(&params, &(), &algo, &analyzer, None);
+        assert!(result.node.unwrap().solution.depth() <= 6);
+        assert!(result.score > -1.0);
+    }
+}
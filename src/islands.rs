@@ -0,0 +1,771 @@
+//! An island model runner. The population is split into several islands
+//! which evolve in parallel without interbreeding, so each one is free
+//! to converge on its own region of the search space. Every so often a
+//! few of the best nodes migrate to a neighboring island, which spreads
+//! good genetic material without collapsing the islands into one.
+use crate::parallel::*;
+use crate::{
+    breeding::breed,
+    math::constraints::{better, improves, PenaltyWeight},
+    math::scaling::apply_scaling,
+    math::selection::{compatible, Selector},
+    models::algen_result::AlgenResult,
+    models::algorithm::Algorithm,
+    models::analyzer::Analyzer,
+    models::islands::{EmigrantSelection, ImmigrantReplacement, IslandExecution, IslandParameters},
+    models::node::Node,
+    models::statistics::GenerationStatistics,
+    models::test_parameters::TestParameters,
+    random::Seed,
+    scoring::{reward, score},
+    BREEDING, EVALUATION,
+};
+use rand::{prelude::*, seq::index::sample};
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Sender},
+        Mutex,
+    },
+    thread,
+};
+
+/// The best score, node, and output found so far.
+type Best<OutputData, Solution> = (f32, Option<Node<Solution>>, Option<OutputData>);
+
+#[cfg(feature = "tracing")]
+use tracing::{event, Level};
+
+/// Run a genetic algorithm using the island model. This accepts the same
+/// types and parameters as `run_algorithm`, plus the `islands`
+/// parameters which describe the islands and how nodes migrate between
+/// them. The `params.population` is split evenly across the islands.
+pub fn run_islands<
+    InputData: Send + Sync,
+    OutputData: Clone + Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    islands: &IslandParameters,
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
+) -> AlgenResult<OutputData, Solution> {
+    params.reject_loop_only("run_islands", algo.telemetry_sink());
+    let params = &params.for_run();
+    let island_count = islands.islands.max(1);
+    let island_size = (params.population / island_count).max(2);
+    let island_params: Vec<(TestParameters<FeatureFlags>, usize)> = (0..island_count)
+        .map(|_| (params.for_island(params), island_size))
+        .collect();
+    run(
+        params,
+        &island_params,
+        islands,
+        input_data,
+        algo,
+        analyzer,
+        on_generation_complete,
+    )
+}
+
+/// Run the island model where every island has its own parameters, so
+/// islands can differ in mutation rate, selection pressure, or even
+/// selection strategy. There is one island per entry of `island_params`,
+/// and each island holds that entry's `population` nodes, so
+/// `islands.islands` is ignored. The run lasts for `params.generations`
+/// generations.
+pub fn run_heterogeneous_islands<
+    InputData: Send + Sync,
+    OutputData: Clone + Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    island_params: &[TestParameters<FeatureFlags>],
+    islands: &IslandParameters,
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
+) -> AlgenResult<OutputData, Solution> {
+    if island_params.is_empty() {
+        return run_islands(
+            params,
+            islands,
+            input_data,
+            algo,
+            analyzer,
+            on_generation_complete,
+        );
+    }
+
+    params.reject_loop_only("run_heterogeneous_islands", algo.telemetry_sink());
+    let params = &params.for_run();
+    let island_params: Vec<(TestParameters<FeatureFlags>, usize)> = island_params
+        .iter()
+        .map(|island| {
+            island.reject_loop_only("run_heterogeneous_islands", None);
+            (params.for_island(island), island.population.max(2))
+        })
+        .collect();
+    run(
+        params,
+        &island_params,
+        islands,
+        input_data,
+        algo,
+        analyzer,
+        on_generation_complete,
+    )
+}
+
+/// The island model itself. Each island is described by its parameters
+/// and its size, and works on its own copy of the parameters so that
+/// islands never share their run state.
+fn run<
+    InputData: Send + Sync,
+    OutputData: Clone + Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    island_params: &[(TestParameters<FeatureFlags>, usize)],
+    islands: &IslandParameters,
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
+) -> AlgenResult<OutputData, Solution> {
+    // WebAssembly can't spawn threads, so its islands take turns
+    let threaded = matches!(islands.execution, IslandExecution::Threaded);
+    if threaded && !cfg!(target_family = "wasm") {
+        return run_threaded(
+            params,
+            island_params,
+            islands,
+            input_data,
+            algo,
+            analyzer,
+            on_generation_complete,
+        );
+    }
+
+    params.install(|| {
+        let interval = islands.migration_interval.max(1);
+        let seed = Seed::new(params.seed);
+        let _stream = seed.enter();
+
+        let mut best_score = f32::MIN;
+        let mut best_node: Option<Node<Solution>> = None;
+        let mut best_output: Option<OutputData> = None;
+        let mut history = Vec::new();
+
+        let evaluate = |node: &mut Node<Solution>, params: &TestParameters<FeatureFlags>| {
+            let weight = PenaltyWeight::new(&params.constraint_handling).weight();
+            let outputs = algo.output(node, input_data, params);
+            score(algo, analyzer, node, &outputs, params, weight);
+            reward(algo, node);
+            outputs
+        };
+
+        let mut population: Vec<Vec<Node<Solution>>> = island_params
+            .iter()
+            .map(|(params, size)| {
+                (0..*size)
+                    .map(|_| algo.allocate_node(input_data, params))
+                    .collect()
+            })
+            .collect();
+
+        for generation in 0..params.generations {
+            for (island, _) in island_params {
+                island.state.set_generation(generation);
+            }
+            let streams = seed.derive(generation as u64);
+
+            // Score every island at once, keeping only the best output
+            let handling = &params.constraint_handling;
+            let generation_best = population
+                .par_iter_mut()
+                .zip(island_params)
+                .enumerate()
+                .flat_map(|(idx, (island, (params, _)))| {
+                    let streams = streams.derive(EVALUATION).derive(idx as u64);
+                    island.par_iter_mut().enumerate().map(move |(idx, node)| {
+                        let _stream = streams.derive(idx as u64).enter();
+                        (evaluate(node, params), &*node)
+                    })
+                })
+                .reduce_with(|left, right| better(left, right, handling));
+
+            if let Some((output, node)) = generation_best {
+                if improves(node, &best_node, best_score, handling) {
+                    best_score = node.score;
+                    best_node = Some(node.clone());
+                    best_output = Some(output);
+                }
+            }
+            let scores: Vec<f32> = population.iter().flatten().map(|node| node.score).collect();
+            history.push(GenerationStatistics::new(generation, &scores));
+
+            if generation > 0 && generation % interval == 0 {
+                migrate(&mut population, islands);
+            }
+
+            population = population
+                .into_par_iter()
+                .zip(island_params)
+                .enumerate()
+                .map(|(idx, (island, (params, size)))| {
+                    let seed = streams.derive(BREEDING).derive(idx as u64);
+                    evolve(island, *size, params, algo, generation, seed)
+                })
+                .collect();
+
+            #[cfg(feature = "tracing")]
+            event!(
+                Level::INFO,
+                msg = "Generation finished processing",
+                generation = generation,
+                score = best_score
+            );
+
+            if let (Some(func), Some(output), Some(node)) =
+                (on_generation_complete, &best_output, &best_node)
+            {
+                if func(best_score, &node.solution, output) {
+                    break;
+                }
+            }
+        }
+
+        AlgenResult {
+            score: best_score,
+            output: best_output,
+            node: best_node,
+            pareto_front: Vec::new(),
+            archive: Vec::new(),
+            quality_diversity: Vec::new(),
+            hypervolume: Vec::new(),
+            history,
+            genealogy: Vec::new(),
+        }
+    })
+}
+
+/// The island model with a thread per island. Islands never wait for
+/// each other: emigrants are posted to their neighbors through channels
+/// and immigrants are settled whenever they have arrived. The run ends
+/// when every island has finished its generations, or as soon as the
+/// callback asks to stop.
+fn run_threaded<
+    InputData: Send + Sync,
+    OutputData: Clone + Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    island_params: &[(TestParameters<FeatureFlags>, usize)],
+    islands: &IslandParameters,
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
+) -> AlgenResult<OutputData, Solution> {
+    let interval = islands.migration_interval.max(1);
+    let island_count = island_params.len();
+    let best: Mutex<Best<OutputData, Solution>> = Mutex::new((f32::MIN, None, None));
+    let stop = AtomicBool::new(false);
+    // The scores of every island, by generation
+    let scores: Mutex<Vec<Vec<f32>>> = Mutex::new(Vec::new());
+
+    let (senders, receivers): (Vec<_>, Vec<_>) = (0..island_count)
+        .map(|_| mpsc::channel::<Vec<Node<Solution>>>())
+        .unzip();
+
+    thread::scope(|scope| {
+        for (idx, ((island_params, size), inbox)) in island_params.iter().zip(receivers).enumerate()
+        {
+            let neighbors: Vec<Sender<Vec<Node<Solution>>>> = islands
+                .topology
+                .neighbors(idx, island_count)
+                .into_iter()
+                .map(|neighbor| senders[neighbor].clone())
+                .collect();
+            let (best, stop, scores) = (&best, &stop, &scores);
+
+            // Islands keep their own threads, and only join the run's pool
+            // to score and breed, so that an island never holds one of its
+            // threads for the whole run
+            scope.spawn(move || {
+                let seed = Seed::new(params.seed).derive(idx as u64);
+                let _stream = seed.enter();
+                let weight = PenaltyWeight::new(&island_params.constraint_handling).weight();
+                let mut island: Vec<Node<Solution>> = (0..*size)
+                    .map(|_| algo.allocate_node(input_data, island_params))
+                    .collect();
+
+                for generation in 0..params.generations {
+                    if stop.load(Ordering::Relaxed) {
+                        break;
+                    }
+                    island_params.state.set_generation(generation);
+                    let streams = seed.derive(generation as u64);
+
+                    let handling = &island_params.constraint_handling;
+                    let island_best = params.install(|| {
+                        island
+                            .par_iter_mut()
+                            .enumerate()
+                            .map(|(idx, node)| {
+                                let _stream = streams.derive(EVALUATION).derive(idx as u64).enter();
+                                let outputs = algo.output(node, input_data, island_params);
+                                score(algo, analyzer, node, &outputs, island_params, weight);
+                                reward(algo, node);
+                                ((outputs, idx), &*node)
+                            })
+                            .reduce_with(|left, right| better(left, right, handling))
+                            .map(|(best, _)| best)
+                    });
+
+                    {
+                        let mut scores = scores.lock().unwrap();
+                        if scores.len() <= generation {
+                            scores.resize_with(generation + 1, Vec::new);
+                        }
+                        scores[generation].extend(island.iter().map(|node| node.score));
+                    }
+
+                    if let Some((output, idx)) = island_best {
+                        let mut best = best.lock().unwrap();
+                        let node = &island[idx];
+                        if improves(node, &best.1, best.0, &params.constraint_handling) {
+                            *best = (node.score, Some(node.clone()), Some(output));
+                        }
+                        if let (Some(func), (score, Some(node), Some(output))) =
+                            (on_generation_complete, &*best)
+                        {
+                            if func(*score, &node.solution, output) {
+                                stop.store(true, Ordering::Relaxed);
+                            }
+                        }
+                    }
+
+                    if generation > 0 && generation % interval == 0 {
+                        let count = islands.migrants(island.len());
+                        let emigrants = select_emigrants(&island, count, islands.emigrants);
+                        for neighbor in &neighbors {
+                            // A neighbor which has already finished no longer
+                            // needs migrants.
+                            let _ = neighbor
+                                .send(emigrants.iter().map(|idx| island[*idx].clone()).collect());
+                        }
+                    }
+
+                    for immigrants in inbox.try_iter() {
+                        receive(&mut island, immigrants, islands.immigrants);
+                    }
+
+                    let seed = streams.derive(BREEDING);
+                    island = params
+                        .install(|| evolve(island, *size, island_params, algo, generation, seed));
+                }
+            });
+        }
+    });
+
+    let (score, node, output) = best.into_inner().unwrap();
+    let history = params.install(|| {
+        scores
+            .into_inner()
+            .unwrap()
+            .iter()
+            .enumerate()
+            .map(|(generation, scores)| GenerationStatistics::new(generation, scores))
+            .collect()
+    });
+    AlgenResult {
+        score,
+        output,
+        node,
+        pareto_front: Vec::new(),
+        archive: Vec::new(),
+        quality_diversity: Vec::new(),
+        hypervolume: Vec::new(),
+        history,
+        genealogy: Vec::new(),
+    }
+}
+
+/// Send copies of the emigrants of every island to each of its
+/// neighbors, where they replace residents according to the policy.
+fn migrate<Solution: Clone>(islands: &mut [Vec<Node<Solution>>], policy: &IslandParameters) {
+    let island_count = islands.len();
+    let mut arrivals: Vec<Vec<Node<Solution>>> = vec![Vec::new(); island_count];
+    for (source, island) in islands.iter().enumerate() {
+        let count = policy.migrants(island.len());
+        let emigrants = select_emigrants(island, count, policy.emigrants);
+        for destination in policy.topology.neighbors(source, island_count) {
+            arrivals[destination].extend(emigrants.iter().map(|idx| island[*idx].clone()));
+        }
+    }
+
+    for (island, immigrants) in islands.iter_mut().zip(arrivals) {
+        receive(island, immigrants, policy.immigrants);
+    }
+}
+
+/// Settle immigrants on an island by replacing some of its residents.
+pub(crate) fn receive<Solution>(
+    island: &mut Vec<Node<Solution>>,
+    mut immigrants: Vec<Node<Solution>>,
+    replacement: ImmigrantReplacement,
+) {
+    immigrants.truncate(island.len());
+    match replacement {
+        ImmigrantReplacement::Worst => {
+            island.sort_by(|left, right| right.score.total_cmp(&left.score));
+            island.truncate(island.len() - immigrants.len());
+            island.extend(immigrants);
+        }
+        ImmigrantReplacement::Random => {
+            let mut rng = crate::random::rng();
+            let residents = sample(&mut rng, island.len(), immigrants.len());
+            for (resident, immigrant) in residents.into_iter().zip(immigrants) {
+                island[resident] = immigrant;
+            }
+        }
+    }
+}
+
+/// The indices of the nodes which leave an island.
+pub(crate) fn select_emigrants<Solution>(
+    island: &[Node<Solution>],
+    count: usize,
+    selection: EmigrantSelection,
+) -> Vec<usize> {
+    let count = count.min(island.len());
+    let mut rng = crate::random::rng();
+    match selection {
+        EmigrantSelection::Best => {
+            let mut ranked: Vec<usize> = (0..island.len()).collect();
+            ranked.sort_by(|left, right| island[*right].score.total_cmp(&island[*left].score));
+            ranked.truncate(count);
+            ranked
+        }
+        EmigrantSelection::Random => sample(&mut rng, island.len(), count).into_vec(),
+        EmigrantSelection::Tournament { size } => (0..count)
+            .filter_map(|_| {
+                (0..size.max(1))
+                    .map(|_| rng.gen_range(0..island.len()))
+                    .max_by(|left, right| island[*left].score.total_cmp(&island[*right].score))
+            })
+            .collect(),
+    }
+}
+
+/// Create the next generation of a single island. The island's elites
+/// are kept and the rest is filled with offspring of its own nodes, up
+/// to `size` nodes, each bred from its own stream of `seed`.
+pub(crate) fn evolve<InputData, OutputData, Solution, FeatureFlags>(
+    mut island: Vec<Node<Solution>>,
+    size: usize,
+    params: &TestParameters<FeatureFlags>,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    generation: usize,
+    seed: Seed,
+) -> Vec<Node<Solution>>
+where
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Send + Sync,
+{
+    island.sort_by(|left, right| right.score.total_cmp(&left.score));
+    let mut next: Vec<Node<Solution>> = island
+        .iter()
+        .take((params.elitism_factor * size as f32) as usize)
+        .cloned()
+        .collect();
+
+    let raw_scores: Vec<f32> = island.iter().map(|node| node.score).collect();
+    apply_scaling(island.as_mut_slice(), &params.scaling);
+    let selector = Selector::new(island.as_slice(), params, generation);
+    let offspring = (0..size.saturating_sub(next.len()))
+        .into_par_iter()
+        .filter_map(|idx| {
+            let _stream = seed.derive(idx as u64).enter();
+            selector
+                .select_pair(island.as_slice(), params, |left, right| {
+                    compatible(params, algo.distance(), left, right)
+                })
+                .map(|(left, right)| {
+                    let mut child =
+                        breed(algo, island[left].clone(), island[right].clone(), params);
+                    // Offspring are judged against their parents' raw scores
+                    child.parent_score = raw_scores[left].max(raw_scores[right]);
+                    child
+                })
+        })
+        .collect::<Vec<Node<Solution>>>();
+
+    next.extend(offspring);
+    next
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+    use crate::models::{
+        constraint::ConstraintHandling, evaluation::EvaluationTimeout, islands::Topology,
+        scaling::Scaling,
+    };
+    use std::time::Duration;
+
+    fn island(scores: &[f32]) -> Vec<Node<f32>> {
+        scores
+            .iter()
+            .map(|score| Node {
+                score: *score,
+                ..Node::new(*score)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn migrants_replace_the_worst_nodes_of_the_next_island() {
+        let mut islands = vec![island(&[1.0, 9.0, 5.0]), island(&[2.0, 3.0, 4.0])];
+        migrate(
+            &mut islands,
+            &IslandParameters {
+                migration_rate: 0.0,
+                ..Default::default()
+            },
+        );
+
+        let solutions = |island: &Vec<Node<f32>>| {
+            let mut solutions: Vec<f32> = island.iter().map(|node| node.solution).collect();
+            solutions.sort_by(f32::total_cmp);
+            solutions
+        };
+        assert_eq!(solutions(&islands[0]), vec![4.0, 5.0, 9.0]);
+        assert_eq!(solutions(&islands[1]), vec![3.0, 4.0, 9.0]);
+    }
+
+    #[test]
+    fn fully_connected_islands_hear_from_everyone() {
+        let mut islands = vec![
+            island(&[1.0, 2.0]),
+            island(&[3.0, 4.0]),
+            island(&[5.0, 6.0]),
+        ];
+        let policy = IslandParameters {
+            migration_rate: 0.0,
+            topology: Topology::FullyConnected,
+            ..Default::default()
+        };
+        migrate(&mut islands, &policy);
+
+        let mut first: Vec<f32> = islands[0].iter().map(|node| node.solution).collect();
+        first.sort_by(f32::total_cmp);
+        assert_eq!(first, vec![4.0, 6.0]);
+    }
+
+    #[test]
+    fn offspring_are_judged_against_raw_parent_scores() {
+        let params = TestParameters {
+            scaling: Scaling::Sigma,
+            ..parameters()
+        };
+        let island: Vec<Node<Vec<bool>>> = (10..20)
+            .map(|ones| Node {
+                score: ones as f32,
+                ..Node::new(vec![true; ones])
+            })
+            .collect();
+
+        let next = evolve(island, 10, &params, &CountOnes {}, 0, Seed::new(Some(1)));
+        let elites = (params.elitism_factor * 10.0) as usize;
+        let offspring: Vec<f32> = next
+            .iter()
+            .skip(elites)
+            .map(|node| node.parent_score)
+            .collect();
+        assert!(!offspring.is_empty());
+        assert!(offspring.iter().all(|score| *score >= 10.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "`run_islands` does not support `evaluation_timeout`")]
+    fn islands_reject_parameters_they_do_not_honor() {
+        let params = TestParameters {
+            evaluation_timeout: Some(EvaluationTimeout {
+                limit: Duration::from_secs(1),
+                penalty: 0.0,
+            }),
+            ..parameters()
+        };
+        run_islands(
+            &params,
+            &IslandParameters::default(),
+            &(),
+            &CountOnes {},
+            &CountOnesAnalyzer {},
+            None,
+        );
+    }
+
+    #[test]
+    fn islands_work() {
+        let result = run_islands(
+            &parameters(),
+            &IslandParameters::default(),
+            &(),
+            &CountOnes {},
+            &CountOnesAnalyzer {},
+            None,
+        );
+        assert!(result.score >= 12.0);
+        assert!(result.node.is_some());
+    }
+
+    #[test]
+    fn threaded_islands_work() {
+        let islands = IslandParameters {
+            execution: IslandExecution::Threaded,
+            ..Default::default()
+        };
+        let result = run_islands(
+            &parameters(),
+            &islands,
+            &(),
+            &CountOnes {},
+            &CountOnesAnalyzer {},
+            None,
+        );
+        assert!(result.score >= 12.0);
+        assert!(result.node.is_some());
+    }
+
+    #[test]
+    fn islands_prefer_feasible_champions() {
+        let algo = Configured {
+            constraints: vec![Box::new(AtMostEight {})],
+            ..Default::default()
+        };
+        let params = TestParameters {
+            constraint_handling: ConstraintHandling::FeasibilityFirst,
+            generations: 10,
+            ..parameters()
+        };
+        for execution in [IslandExecution::LockStep, IslandExecution::Threaded] {
+            let islands = IslandParameters {
+                execution,
+                ..Default::default()
+            };
+            let result = run_islands(&params, &islands, &(), &algo, &CountOnesAnalyzer {}, None);
+            assert_eq!(result.node.unwrap().violation, 0.0);
+            assert!(result.score <= 8.0);
+        }
+    }
+
+    #[test]
+    fn threaded_islands_record_their_history() {
+        let params = TestParameters {
+            generations: 10,
+            ..parameters()
+        };
+        let islands = IslandParameters {
+            execution: IslandExecution::Threaded,
+            ..Default::default()
+        };
+        let result = run_islands(
+            &params,
+            &islands,
+            &(),
+            &CountOnes {},
+            &CountOnesAnalyzer {},
+            None,
+        );
+        assert_eq!(result.history.len(), 10);
+        assert!(result
+            .history
+            .iter()
+            .enumerate()
+            .all(|(generation, statistics)| statistics.generation == generation));
+    }
+
+    #[test]
+    fn threaded_islands_breed_in_the_pool_of_the_run() {
+        // Remembers every thread offspring were bred on
+        struct Threads(Mutex<Vec<thread::ThreadId>>);
+        impl Algorithm<(), Vec<bool>, Vec<bool>, ()> for Threads {
+            fn output(
+                &self,
+                node: &Node<Vec<bool>>,
+                input: &(),
+                params: &TestParameters<()>,
+            ) -> Vec<bool> {
+                CountOnes {}.output(node, input, params)
+            }
+
+            fn allocate_node(&self, input: &(), params: &TestParameters<()>) -> Node<Vec<bool>> {
+                CountOnes {}.allocate_node(input, params)
+            }
+
+            fn combine_node(
+                &self,
+                left: Node<Vec<bool>>,
+                right: Node<Vec<bool>>,
+                params: &TestParameters<()>,
+            ) -> Node<Vec<bool>> {
+                let mut threads = self.0.lock().unwrap();
+                if !threads.contains(&thread::current().id()) {
+                    threads.push(thread::current().id());
+                }
+                CountOnes {}.combine_node(left, right, params)
+            }
+        }
+
+        let params = TestParameters {
+            generations: 5,
+            parallelism: crate::models::parallelism::Parallelism::Sequential,
+            ..parameters()
+        };
+        let islands = IslandParameters {
+            islands: 4,
+            execution: IslandExecution::Threaded,
+            ..Default::default()
+        };
+        let algo = Threads(Mutex::new(Vec::new()));
+        run_islands(&params, &islands, &(), &algo, &CountOnesAnalyzer {}, None);
+        assert_eq!(algo.0.into_inner().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn heterogeneous_islands_work() {
+        let island_params = [0.001, 0.01, 0.1].map(|mutation_factor| TestParameters {
+            population: 30,
+            mutation_factor,
+            ..parameters()
+        });
+        let result = run_heterogeneous_islands(
+            &parameters(),
+            &island_params,
+            &IslandParameters::default(),
+            &(),
+            &CountOnes {},
+            &CountOnesAnalyzer {},
+            None,
+        );
+        assert!(result.score >= 12.0);
+    }
+}
@@ -23,35 +23,214 @@
 //! some kind of **Input Data** which is fed to your algorithm.
 //!
 //! See the example in the examples folder for more details.
-//!
-//! ```no_run
-//!     run_algorithm(
-//!         &parameters,
-//!         test_data,
-//!         algo,
-//!         analyzer,
-//!         Some(after_generation),
-//!     );
-//! ```
+#![cfg_attr(
+    feature = "std",
+    doc = r##"
+```no_run
+# use algen::models::{
+#     algorithm::Algorithm, analyzer::Analyzer, node::Node, test_parameters::TestParameters,
+# };
+# use algen::run_algorithm;
+# use rand::Rng;
+# struct Bits;
+# impl Algorithm<(), Vec<bool>, Vec<bool>, ()> for Bits {
+#     fn output(&self, node: &Node<Vec<bool>>, _: &(), _: &TestParameters<()>) -> Vec<bool> {
+#         node.solution.clone()
+#     }
+#     fn allocate_node(&self, _: &(), _: &TestParameters<()>) -> Node<Vec<bool>> {
+#         Node::new((0..16).map(|_| algen::random::rng().gen()).collect())
+#     }
+#     fn combine_node(
+#         &self,
+#         left: Node<Vec<bool>>,
+#         _right: Node<Vec<bool>>,
+#         _: &TestParameters<()>,
+#     ) -> Node<Vec<bool>> {
+#         left
+#     }
+# }
+# struct CountOnes;
+# impl Analyzer<(), Vec<bool>, ()> for CountOnes {
+#     fn evaluate(&self, attempt: &Vec<bool>, _: &TestParameters<()>) -> f32 {
+#         attempt.iter().filter(|bit| **bit).count() as f32
+#     }
+# }
+# let (parameters, test_data, algo, analyzer) =
+#     (TestParameters::default(), &(), &Bits, &CountOnes);
+# fn after_generation(score: f32, _: &Vec<bool>, _: &Vec<bool>) -> bool {
+#     score >= 16.0
+# }
+run_algorithm(
+    &parameters,
+    test_data,
+    algo,
+    analyzer,
+    Some(after_generation),
+);
+```
+"##
+)]
+#![cfg_attr(not(any(feature = "std", test)), no_std)]
+
+extern crate alloc;
+extern crate self as algen;
+
+#[cfg(feature = "std")]
+pub mod alps;
+#[cfg(feature = "benchmarks")]
+pub mod benchmarks;
+// Without std, only the preallocated runner is built, and it uses just
+// part of the crate's internals
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+mod breeding;
+#[cfg(feature = "std")]
+pub mod cellular;
+#[cfg(feature = "std")]
+mod clock;
+#[cfg(feature = "std")]
+pub mod competitive;
+#[cfg(feature = "std")]
+pub mod cooperative;
+#[cfg(feature = "std")]
+pub mod deadline;
+#[cfg(all(test, feature = "std"))]
+mod fixtures;
+#[cfg(feature = "std")]
+pub mod genomes;
+#[cfg(feature = "std")]
+pub mod islands;
+#[cfg(feature = "std")]
+pub mod map_elites;
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
 mod math;
+#[cfg(feature = "memory")]
+pub mod memory;
 pub mod models;
+#[cfg(feature = "std")]
+pub mod multi_objective;
+#[cfg(feature = "network")]
+pub mod network;
+#[cfg(feature = "std")]
+pub mod novelty;
+#[cfg(feature = "std")]
+pub mod operators;
+#[cfg_attr(not(feature = "std"), allow(dead_code))]
+mod parallel;
+pub mod preallocated;
+pub mod random;
+#[cfg(feature = "network")]
+pub mod remote;
+mod scoring;
+#[cfg(feature = "std")]
+pub mod stores;
+mod sync;
 
+#[cfg(feature = "std")]
+use crate::parallel::*;
+#[cfg(feature = "std")]
 use crate::{
-    math::tournament_selection, models::algorithm::*, models::analyzer::Analyzer,
-    models::node::Node, models::test_parameters::TestParameters,
+    breeding::{breed, breed_parents, deduplicate, immigrate},
+    clock::Instant,
+    math::accelerator::offload,
+    math::columns::{permute, ranking, select_best},
+    math::constraints::{better, improves, penalize, PenaltyWeight},
+    math::elite_archive::archive_elites,
+    math::fitness_cache::{CachedFitness, FitnessCache},
+    math::isolation::{guard, isolate},
+    math::local_search::hill_climb,
+    math::niching::apply_fitness_sharing,
+    math::queue::queue_map,
+    math::replacement::{deterministic_crowding, restricted_tournament},
+    math::scaling::apply_scaling,
+    math::selection::{compatible, front, Selector},
+    math::speciation::Speciator,
+    math::statistics::entropy,
+    models::algorithm::*,
+    models::analyzer::Analyzer,
+    models::constraint::ConstraintHandling,
+    models::evaluation::Evaluation,
+    models::input_provider::{InputProvider, Rescoring},
+    models::lineage::Lineage,
+    models::local_search::Learning,
+    models::node::Node,
+    models::population_store::PopulationStore,
+    models::replacement::Replacement,
+    models::snapshot::Snapshot,
+    models::statistics::{GenerationStatistics, DIVERSITY_SAMPLE},
+    models::telemetry::IterationTelemetry,
+    models::test_parameters::TestParameters,
+    random::Seed,
+    scoring::{constraint_violation, reward},
 };
+#[cfg(feature = "std")]
 use models::algen_result::AlgenResult;
-use rayon::prelude::*;
+#[cfg(feature = "std")]
+use rand::{
+    seq::{index::sample, SliceRandom},
+    Rng,
+};
+#[cfg(feature = "std")]
+use std::{
+    cmp::Reverse,
+    io,
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    time::Duration,
+};
+
+#[cfg(feature = "std")]
+pub use alps::run_alps;
+#[cfg(feature = "std")]
+pub use cellular::run_cellular;
+#[cfg(feature = "std")]
+pub use competitive::run_competitive;
+#[cfg(feature = "std")]
+pub use cooperative::run_cooperative;
+#[cfg(feature = "std")]
+pub use islands::{run_heterogeneous_islands, run_islands};
+#[cfg(feature = "std")]
+pub use map_elites::run_map_elites;
+#[cfg(feature = "std")]
+pub use multi_objective::{run_multi_objective, run_nsga2};
+#[cfg(feature = "network")]
+pub use network::run_network_island;
+#[cfg(feature = "std")]
+pub use novelty::run_novelty_search;
+pub use preallocated::run_preallocated;
+
+// The parts of a generation which give each piece of their work its own
+// random stream, so that a seeded run doesn't depend on how it is
+// scheduled
+#[cfg(feature = "std")]
+const EVALUATION: u64 = 0;
+#[cfg(feature = "std")]
+const BREEDING: u64 = 1;
+#[cfg(feature = "std")]
+const CROWDING: u64 = 2;
+#[cfg(feature = "std")]
+const OFFSPRING: u64 = 3;
+#[cfg(feature = "std")]
+const NEWCOMERS: u64 = 4;
+#[cfg(feature = "std")]
+const GAMES: u64 = 5;
 
 #[cfg(feature = "tracing")]
 use tracing::{event, span, Level};
 
+// Used by the code generated by `#[derive(Genome)]`
+#[doc(hidden)]
+pub mod __private {
+    pub use rand::Rng;
+}
+
 /// The primary algorithm runner. This method will accept the types:
 /// - InputData: The shape of data which is passed to each solution.
 /// - OutputData: The shape of data which a solution will output
 /// - Solution: The chromosome which represents a solution
 /// - FeatureFlags: An additinoal object to add functionality to the
-/// TestParameters structure.
+///   TestParameters structure.
 ///
 /// Additionally, it takes the following parameters:
 /// - params: Test parameters that define the rules of the runner
@@ -59,12 +238,13 @@ use tracing::{event, span, Level};
 /// - algo: A struct which implements the Algorithm trait
 /// - analyzer: A struct which implements the Analyzer trait
 /// - on_generation_complete: A method which is run at the end of each
-/// generation and, if it returns true, the test will be stopped.
+///   generation and, if it returns true, the test will be stopped.
+#[cfg(feature = "std")]
 pub fn run_algorithm<
     InputData: Send + Sync,
     OutputData: Clone + Send + Sync,
     Solution: Clone + Send + Sync,
-    FeatureFlags: Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
 >(
     params: &TestParameters<FeatureFlags>,
     input_data: &InputData,
@@ -73,158 +253,2017 @@ pub fn run_algorithm<
 
     on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
 ) -> AlgenResult<OutputData, Solution> {
-    // Generate the initial population
-    let mut population = Vec::new();
-    let mut next_population = Vec::new();
-    let mut best_score = 0.0;
-    let mut best_node: Option<Node<Solution>> = None;
-    let mut best_solution: Option<Solution> = None;
-    let mut best_output = None;
-
-    for _ in 0..params.population {
-        population.push(algo.allocate_node(&input_data, &params));
-    }
-
-    // Iterate over each generation
-    for generation in 0..params.generations {
-        #[cfg(feature = "tracing")]
-        let generation_span = span!(Level::TRACE, "generation", generation = generation);
-        #[cfg(feature = "tracing")]
-        let generation_span_entered = generation_span.enter();
-
-        // Compute the score for each node, in parallel
-        #[cfg(feature = "tracing")]
-        let compute_span = span!(Level::TRACE, "compute");
-        #[cfg(feature = "tracing")]
-        let compute_span_entered = compute_span.enter();
-
-        let mut winning_condition_found = false;
-
-        let computation_results = population
-            .par_iter_mut()
-            .map(|node| {
-                // Score each test case
-                let outputs = algo.output(node, &input_data, &params);
-                node.score = analyzer.evaluate(&outputs, params);
-                return (node.score, node.solution.clone(), outputs.clone(), node);
-            })
-            .collect::<Vec<(f32, Solution, OutputData, &mut Node<Solution>)>>();
-
-        for (score, solution, computation, node) in computation_results {
-            if score > best_score {
-                best_score = score;
-                best_node = Some(node.clone());
-                best_solution = Some(solution.clone());
-                best_output = Some(computation.clone());
-            }
-        }
-
-        #[cfg(feature = "tracing")]
-        drop(compute_span_entered);
-
-        // Retain the best and worst
-        population.sort_by(|node_left, node_right| {
-            node_right.score.partial_cmp(&node_left.score).unwrap()
-        });
-
-        #[cfg(feature = "tracing")]
-        let next_generation_span = span!(Level::TRACE, "recombination");
-        #[cfg(feature = "tracing")]
-        let next_generation_span_entered = next_generation_span.enter();
-
-        // Take the creme of the crop, in both directions. And we multiply by 0.5
-        // because each iteration takes 2 nodes.
-        for i in 0..(params.elitism_factor * 0.5 * population.len() as f32) as usize {
-            let bottom_idx = population.len() - i - 1;
-            let top_node = population.get(i).unwrap().clone();
-            let bottom_node = population.get(bottom_idx).unwrap().clone();
-            next_population.push(top_node);
-            next_population.push(bottom_node);
-        }
-
-        // NOTE!!! Consult Kozac on this logic
-        // Now we need to fill up the population remaining with a population selection
-        let children = population
-            .par_iter()
-            .map(|_| {
-                let left = tournament_selection(population.as_slice(), params);
-                let right = tournament_selection(population.as_slice(), params);
-
-                if left.is_some() && right.is_some() {
-                    return Some(algo.combine_node(left.unwrap(), right.unwrap(), params));
-                } else {
+    run(
+        params,
+        None,
+        None,
+        input_data,
+        algo,
+        analyzer,
+        on_generation_complete,
+    )
+}
+
+/// Run the algorithm exactly like `run_algorithm`, but save the
+/// population to `store` at the end of every generation. If the store
+/// already holds a population, the run resumes from it at the generation
+/// it was saved for, and goes on until `params.generations`. A store
+/// which can't be read is an error, rather than a reason to start again
+/// and overwrite what it holds.
+#[cfg(feature = "std")]
+pub fn run_with_store<
+    InputData: Send + Sync,
+    OutputData: Clone + Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    store: &(dyn PopulationStore<Solution> + Sync),
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
+) -> io::Result<AlgenResult<OutputData, Solution>> {
+    let saved = store.load()?;
+    Ok(run(
+        params,
+        Some((store, saved)),
+        None,
+        input_data,
+        algo,
+        analyzer,
+        on_generation_complete,
+    ))
+}
+
+/// Run the algorithm exactly like `run_algorithm`, but on input which
+/// changes as the run goes on. `input_data` is the input of the first
+/// generation, and `inputs` is asked for the input of every generation
+/// after it. Whenever the input changes, the nodes chosen by
+/// `params.rescoring` are scored again on the new input.
+#[cfg(feature = "std")]
+pub fn run_dynamic<
+    InputData: Send + Sync,
+    OutputData: Clone + Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    inputs: &(dyn InputProvider<InputData> + Sync),
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
+) -> AlgenResult<OutputData, Solution> {
+    run(
+        params,
+        None,
+        Some(inputs),
+        input_data,
+        algo,
+        analyzer,
+        on_generation_complete,
+    )
+}
+
+/// A store, along with the generation and population it held when the
+/// run started.
+#[cfg(feature = "std")]
+type Resume<'a, Solution> = (
+    &'a (dyn PopulationStore<Solution> + Sync),
+    (usize, Vec<Node<Solution>>),
+);
+
+#[cfg(feature = "std")]
+fn run<
+    InputData: Send + Sync,
+    OutputData: Clone + Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    store: Option<Resume<Solution>>,
+    inputs: Option<&(dyn InputProvider<InputData> + Sync)>,
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
+) -> AlgenResult<OutputData, Solution> {
+    let params = &params.for_run();
+    params.install(|| {
+        // Everything done on this thread draws from the run's own stream
+        let seed = Seed::new(params.seed);
+        let _stream = seed.enter();
+
+        // Resume from the store, or generate the initial population
+        let (store, (first_generation, mut population)) = match store {
+            Some((store, saved)) => (Some(store), saved),
+            None => (None, Default::default()),
+        };
+        if let Some(last_id) = population.iter().map(|node| node.id).max() {
+            params.state.resume_ids(last_id);
+        }
+        let mut next_population = Vec::new();
+        let mut best_score = f32::MIN;
+        let mut best_node: Option<Node<Solution>> = None;
+        let mut best_output = None;
+
+        if population.is_empty() {
+            for _ in 0..params.population {
+                population.push(algo.allocate_node(input_data, params));
+            }
+        }
+
+        let mut elites: Vec<Node<Solution>> = Vec::new();
+        let mut history = Vec::new();
+        let mut genealogy = Vec::new();
+        let mut penalty = PenaltyWeight::new(&params.constraint_handling);
+        let mut speciator = Speciator::new();
+        let fitness_cache = Mutex::new(FitnessCache::new(params.fitness_cache.unwrap_or(0)));
+        let mut niches = Speciator::new();
+        let mut current_input = None;
+
+        // Iterate over each generation
+        for generation in first_generation..params.generations {
+            params.state.set_generation(generation);
+            let streams = seed.derive(generation as u64);
+            let generation_started = Instant::now();
+            #[cfg(feature = "memory")]
+            let allocations_before = memory::allocations();
+
+            // When the input moves on, scores earned on the old input no
+            // longer hold
+            let changed = inputs
+                .filter(|_| generation > 0)
+                .and_then(|inputs| inputs.input(generation));
+            let input_changed = changed.is_some();
+            if input_changed {
+                current_input = changed;
+            }
+            let input_data = current_input.as_ref().unwrap_or(input_data);
+            if input_changed {
+                // Nodes which survived an earlier generation are numbered,
+                // while offspring which won their place last generation
+                // are not yet
+                for node in &mut population {
+                    node.evaluated &= match params.rescoring {
+                        Rescoring::All => false,
+                        Rescoring::Elites => node.id == 0,
+                    };
+                }
+                fitness_cache.lock().unwrap().clear();
+                elites.clear();
+                if let Some(best) = &mut best_node {
+                    let outputs = algo.output(best, input_data, params);
+                    scoring::score(algo, analyzer, best, &outputs, params, penalty.weight());
+                    best_score = best.score;
+                    best_output = Some(outputs);
+                }
+            }
+
+            // Number the newborn, whose lineage is recorded once they're
+            // scored, and age everyone else
+            let mut newborn = Vec::new();
+            for (idx, node) in population.iter_mut().enumerate() {
+                if node.id == 0 {
+                    node.id = params.state.next_id();
+                    node.birth_generation = generation;
+                    node.age = 0;
+                    if params.track_lineage {
+                        newborn.push((idx, node.operator));
+                    }
+                } else if generation > 0 {
+                    node.age += 1;
+                }
+            }
+
+            // Score a single node, including each test case and constraint
+            let penalty_weight = penalty.weight();
+            let offspring = AtomicUsize::new(0);
+            let successes = AtomicUsize::new(0);
+            // Nodes carried over unchanged keep their evaluation, unless an
+            // adaptive penalty weight has moved since they were penalized.
+            let cached = |node: &Node<Solution>| {
+                node.evaluated
+                    && !matches!(
+                        params.constraint_handling,
+                        ConstraintHandling::AdaptivePenalty { .. }
+                    )
+            };
+            // Record a node's evaluation, however it was come by
+            let settle = |node: &mut Node<Solution>, fitness: CachedFitness, fresh_offspring| {
+                node.score = node.learned_score.unwrap_or(fitness.score);
+                node.case_scores = fitness.case_scores;
+                node.violation = fitness.violation;
+                penalize(node, &params.constraint_handling, penalty_weight);
+                reward(algo, node);
+                if fresh_offspring {
+                    offspring.fetch_add(1, Ordering::Relaxed);
+                    if node.score > node.parent_score {
+                        successes.fetch_add(1, Ordering::Relaxed);
+                    }
+                }
+                node.evaluated = true;
+            };
+            // Get a node ready to be scored, which means local search and a
+            // look in the fitness cache. Nodes which need no scoring give
+            // back nothing, and the rest give back whether they are fresh
+            // offspring and their fingerprint.
+            let prepare = |node: &mut Node<Solution>| {
+                if cached(node) {
                     return None;
                 }
+                let fresh_offspring = node.score == f32::MIN && node.parent_score > f32::MIN;
+                if let (true, Some(local), Some(search)) =
+                    (fresh_offspring, &params.local_search, algo.local_search())
+                {
+                    if crate::random::rng().gen_bool(local.probability.clamp(0.0, 1.0) as f64) {
+                        let score = |solution: &Solution| {
+                            let outputs =
+                                algo.output(&Node::new(solution.clone()), input_data, params);
+                            analyzer.evaluate(&outputs, params)
+                        };
+                        let start = score(&node.solution);
+                        let (improved, learned_score) =
+                            hill_climb(node.solution.clone(), start, local.steps, search, score);
+                        match local.learning {
+                            Learning::Lamarckian => node.solution = improved,
+                            Learning::Baldwinian => node.learned_score = Some(learned_score),
+                        }
+                    }
+                }
+                // Genomes seen before are looked up rather than scored
+                let print = params
+                    .fitness_cache
+                    .and_then(|_| algo.fingerprint(&node.solution));
+                match print.and_then(|print| fitness_cache.lock().unwrap().get(print)) {
+                    Some(fitness) => {
+                        settle(node, fitness, fresh_offspring);
+                        None
+                    }
+                    None => Some((fresh_offspring, print)),
+                }
+            };
+            // Finish scoring a prepared node from its outputs and the
+            // analyzer's score, including each test case and constraint
+            let score = |node: &mut Node<Solution>,
+                         outputs: &OutputData,
+                         score: f32,
+                         (fresh_offspring, print): (bool, Option<u64>)| {
+                let fitness = CachedFitness {
+                    score,
+                    case_scores: analyzer.evaluate_cases(outputs, params).unwrap_or_default(),
+                    violation: analyzer.violation(outputs, params)
+                        + constraint_violation(algo, &node.solution, outputs),
+                };
+                // A learned score belongs to the node, not its genome
+                if let (Some(print), None) = (print, node.learned_score) {
+                    fitness_cache.lock().unwrap().insert(print, fitness.clone());
+                }
+                settle(node, fitness, fresh_offspring);
+            };
+            // Give a node a penalty in place of its score, for an
+            // evaluation which panicked or ran over time. The penalty
+            // belongs to this run of the node, so it is neither learned nor
+            // cached.
+            let forfeit = |node: &mut Node<Solution>, penalty: f32, fresh_offspring| {
+                #[cfg(feature = "tracing")]
+                event!(
+                    Level::WARN,
+                    msg = "Evaluation forfeited",
+                    node = node.id,
+                    penalty = penalty
+                );
+                node.learned_score = None;
+                let fitness = CachedFitness {
+                    score: penalty,
+                    case_scores: Vec::new(),
+                    violation: 0.0,
+                };
+                settle(node, fitness, fresh_offspring);
+            };
+            // Score a single node, within the time limit if there is one
+            let evaluate = |node: &mut Node<Solution>| {
+                let prepared = prepare(node)?;
+                let evaluation = guard(params, || {
+                    let outputs = algo.output(node, input_data, params);
+                    let raw_score = match node.learned_score {
+                        Some(learned_score) => learned_score,
+                        None => analyzer.evaluate(&outputs, params),
+                    };
+                    (outputs, raw_score)
+                });
+                match evaluation {
+                    Ok((outputs, raw_score)) => {
+                        score(node, &outputs, raw_score, prepared);
+                        Some(outputs)
+                    }
+                    Err(penalty) => {
+                        forfeit(node, penalty, prepared.0);
+                        None
+                    }
+                }
+            };
+
+            #[cfg(feature = "tracing")]
+            let generation_span = span!(Level::TRACE, "generation", generation = generation);
+            #[cfg(feature = "tracing")]
+            let generation_span_entered = generation_span.enter();
+
+            // Compute the score for each node, in parallel
+            #[cfg(feature = "tracing")]
+            let compute_span = span!(Level::TRACE, "compute");
+            #[cfg(feature = "tracing")]
+            let compute_span_entered = compute_span.enter();
+            let compute_started = Instant::now();
+
+            let mut winning_condition_found = false;
+
+            let chunk_size = params
+                .evaluation
+                .chunk_size(population.len(), parallel::current_num_threads());
+            // Only the output of the generation's best node is kept, and
+            // every other output is dropped as soon as it loses out. Ties
+            // go to the earlier node.
+            let better = |left, right| better(left, right, &params.constraint_handling);
+            let generation_best = match (algo.accelerator(), params.evaluation) {
+                (None, Evaluation::Parallel | Evaluation::Chunked { .. }) => population
+                    .par_iter_mut()
+                    .enumerate()
+                    .with_min_len(chunk_size)
+                    .map(|(idx, node)| {
+                        let _stream = streams.derive(EVALUATION).derive(idx as u64).enter();
+                        (evaluate(node), &*node)
+                    })
+                    .reduce_with(better),
+                (
+                    None,
+                    Evaluation::Queue {
+                        workers,
+                        record_durations,
+                    },
+                ) => {
+                    let mut queue: Vec<(usize, &mut Node<Solution>)> =
+                        population.iter_mut().enumerate().collect();
+                    if record_durations {
+                        // Longest first, with untimed nodes ahead of them all
+                        queue.sort_by_key(|(_, node)| {
+                            Reverse(node.evaluation_time.unwrap_or(Duration::MAX))
+                        });
+                    }
+                    let workers = match workers {
+                        0 => parallel::current_num_threads(),
+                        workers => workers,
+                    };
+                    queue_map(queue, workers, |(idx, node)| {
+                        let _stream = streams.derive(EVALUATION).derive(idx as u64).enter();
+                        let started = Instant::now();
+                        let outputs = evaluate(node);
+                        if record_durations {
+                            node.evaluation_time = Some(started.elapsed());
+                        }
+                        (outputs, &*node)
+                    })
+                    .into_iter()
+                    .reduce(better)
+                }
+                (accelerator, _) => {
+                    // Every output which needs scoring is gathered into one
+                    // slice, and the lot is scored in one go, on the device
+                    // if there is one
+                    let (pending, outputs): (Vec<_>, Vec<OutputData>) = population
+                        .par_iter_mut()
+                        .enumerate()
+                        .filter_map(|(idx, node)| {
+                            let _stream = streams.derive(EVALUATION).derive(idx as u64).enter();
+                            let prepared = prepare(node)?;
+                            match guard(params, || algo.output(node, input_data, params)) {
+                                Ok(outputs) => Some(((idx, prepared), outputs)),
+                                Err(penalty) => {
+                                    forfeit(node, penalty, prepared.0);
+                                    None
+                                }
+                            }
+                        })
+                        .unzip();
+                    let mut scores = vec![0.0; outputs.len()];
+                    // A batch which panics takes every node in it down
+                    let scored = isolate(params.panic_penalty.is_some(), || match accelerator {
+                        Some(accelerator) => {
+                            let solutions: Vec<&Solution> = pending
+                                .iter()
+                                .map(|(idx, _)| &population[*idx].solution)
+                                .collect();
+                            offload(accelerator, &solutions, &mut scores)
+                        }
+                        None => analyzer.evaluate_batch(&outputs, &mut scores, params),
+                    })
+                    .is_some();
+
+                    let mut slots: Vec<Option<_>> = (0..population.len()).map(|_| None).collect();
+                    for (((idx, prepared), outputs), raw_score) in
+                        pending.into_iter().zip(outputs).zip(scores)
+                    {
+                        slots[idx] = Some((prepared, outputs, raw_score));
+                    }
+                    population
+                        .par_iter_mut()
+                        .zip(slots)
+                        .map(|(node, slot)| match slot {
+                            Some((prepared, outputs, raw_score)) if scored => {
+                                let raw_score = node.learned_score.unwrap_or(raw_score);
+                                score(node, &outputs, raw_score, prepared);
+                                (Some(outputs), &*node)
+                            }
+                            Some((prepared, _, _)) => {
+                                let penalty = params.panic_penalty.unwrap_or(f32::MIN);
+                                forfeit(node, penalty, prepared.0);
+                                (None, &*node)
+                            }
+                            None => (None, &*node),
+                        })
+                        .reduce_with(better)
+                }
+            };
+
+            let generation_best_feasible = generation_best
+                .as_ref()
+                .map(|(_, node)| node.violation <= 0.0)
+                .unwrap_or(true);
+            penalty.record(&params.constraint_handling, generation_best_feasible);
+
+            // Only an improvement on the best node is worth a copy
+            if let Some((computation, node)) = generation_best {
+                let improved = improves(node, &best_node, best_score, &params.constraint_handling);
+                // A node whose evaluation panicked may panic again
+                let output = match computation {
+                    Some(output) => Some(output),
+                    None if improved => isolate(params.panic_penalty.is_some(), || {
+                        algo.output(node, input_data, params)
+                    }),
+                    None => None,
+                };
+                if let (true, Some(output)) = (improved, output) {
+                    best_score = node.score;
+                    best_node = Some(node.clone());
+                    best_output = Some(output);
+                }
+            }
+
+            let compute_time = compute_started.elapsed();
+            #[cfg(feature = "tracing")]
+            drop(compute_span_entered);
+
+            genealogy.extend(newborn.into_iter().map(|(idx, operator)| {
+                let node = &population[idx];
+                Lineage {
+                    id: node.id,
+                    parents: node.parents.clone(),
+                    birth_generation: node.birth_generation,
+                    operator,
+                    score: node.score,
+                }
+            }));
+
+            if let Some(archive) = &params.elite_archive {
+                archive_elites(
+                    &mut elites,
+                    &population,
+                    archive.size,
+                    &params.constraint_handling,
+                    |solution| algo.fingerprint(solution),
+                );
+            }
+
+            if let (true, Some(sink)) = (
+                params.snapshot_interval > 0 && generation % params.snapshot_interval == 0,
+                algo.snapshot_sink(),
+            ) {
+                sink.record(&Snapshot {
+                    generation,
+                    nodes: &population,
+                });
+            }
+
+            // Sharing and scaling only shape selection, so replacement
+            // strategies which compare offspring with parents use these.
+            let mut raw_scores: Vec<f32> = population.iter().map(|node| node.score).collect();
+            let mut statistics = GenerationStatistics::new(generation, &raw_scores);
+            statistics.genotype_entropy = population
+                .iter()
+                .map(|node| algo.fingerprint(&node.solution))
+                .collect::<Option<Vec<u64>>>()
+                .map(entropy);
+            statistics.mean_distance = algo.distance().map(|distance| {
+                let solutions: Vec<&Solution> = population
+                    .choose_multiple(&mut crate::random::rng(), DIVERSITY_SAMPLE)
+                    .map(|node| &node.solution)
+                    .collect();
+                distance.diversity(&solutions)
+            });
+
+            // Derate crowded nodes so that multiple niches survive selection
+            if let (Some(sharing), Some(distance)) = (&params.fitness_sharing, algo.distance()) {
+                niches.assign(population.as_slice(), distance, sharing.radius);
+                statistics.species = niches.report();
+                apply_fitness_sharing(population.as_mut_slice(), distance, sharing);
+            }
+
+            // Share scores within each species and cull the stagnant ones
+            if let (Some(speciation), Some(distance)) = (&params.speciation, algo.distance()) {
+                speciator.apply(population.as_mut_slice(), distance, speciation);
+                statistics.species = speciator.report();
+            }
+
+            #[cfg(feature = "tracing")]
+            for species in &statistics.species {
+                event!(
+                    Level::DEBUG,
+                    msg = "Species",
+                    generation = generation,
+                    species = species.id,
+                    size = species.size,
+                    best_score = species.best,
+                    age = species.age,
+                    stagnant = species.stagnant
+                );
+            }
+            history.push(statistics);
+
+            // Rescale the scores before they are used for selection
+            apply_scaling(population.as_mut_slice(), &params.scaling);
+
+            // Truncation selection needs the best nodes at the front, but
+            // they are partitioned out rather than the whole lot sorted
+            let truncated = front(params, population.len());
+            if truncated > 0 {
+                let mut order: Vec<usize> = (0..population.len()).collect();
+                select_best(&mut order, truncated, ranking(&population, params));
+                permute(&mut population, &order);
+                permute(&mut raw_scores, &order);
+            }
+
+            #[cfg(feature = "tracing")]
+            let next_generation_span = span!(Level::TRACE, "recombination");
+            #[cfg(feature = "tracing")]
+            let next_generation_span_entered = next_generation_span.enter();
+            let recombination_started = Instant::now();
+
+            // Parents are picked from the scored population
+            let selector = Selector::new(population.as_slice(), params, generation);
+            let compatible = |left: &Node<Solution>, right: &Node<Solution>| {
+                compatible(params, algo.distance(), left, right)
+            };
+            let breed_many = |count: usize| {
+                (0..count)
+                    .into_par_iter()
+                    .filter_map(|idx| {
+                        let _stream = streams.derive(BREEDING).derive(idx as u64).enter();
+                        if params.parents > 2 {
+                            return selector
+                                .select_parents(
+                                    population.as_slice(),
+                                    params,
+                                    params.parents,
+                                    compatible,
+                                )
+                                .map(|parents| {
+                                    let nodes: Vec<Node<Solution>> = parents
+                                        .iter()
+                                        .map(|idx| population[*idx].clone())
+                                        .collect();
+                                    let mut child = breed_parents(algo, &nodes, params);
+                                    child.parent_score = parents
+                                        .iter()
+                                        .map(|idx| raw_scores[*idx])
+                                        .fold(f32::MIN, f32::max);
+                                    child
+                                });
+                        }
+
+                        selector
+                            .select_pair(population.as_slice(), params, compatible)
+                            .map(|(left, right)| {
+                                let mut child = breed(
+                                    algo,
+                                    population[left].clone(),
+                                    population[right].clone(),
+                                    params,
+                                );
+                                // Offspring are judged against their parents' raw scores
+                                child.parent_score = raw_scores[left].max(raw_scores[right]);
+                                child
+                            })
+                    })
+                    .collect::<Vec<Node<Solution>>>()
+            };
+
+            match params.replacement {
+                Replacement::Generational => {
+                    // Take the creme of the crop, in both directions. And we multiply by 0.5
+                    // because each iteration takes 2 nodes.
+                    // Nodes past the maximum age have retired from elitism.
+                    // Elites keep their raw score, so they need not be evaluated again.
+                    let mut eligible: Vec<usize> = (0..population.len())
+                        .filter(|idx| {
+                            params
+                                .max_age
+                                .is_none_or(|max_age| population[*idx].age < max_age)
+                        })
+                        .collect();
+                    let pairs = ((params.elitism_factor * 0.5 * population.len() as f32) as usize)
+                        .min(eligible.len() / 2);
+                    // Only the best and worst few are needed, so they are
+                    // partitioned out rather than everything being sorted
+                    let better = ranking(&population, params);
+                    select_best(&mut eligible, pairs, &better);
+                    select_best(&mut eligible[pairs..], pairs, |left, right| {
+                        better(right, left)
+                    });
+                    for i in 0..pairs {
+                        for idx in [eligible[i], eligible[pairs + i]] {
+                            let mut elite = population[idx].clone();
+                            elite.score = raw_scores[idx];
+                            next_population.push(elite);
+                        }
+                    }
+
+                    // NOTE!!! Consult Kozac on this logic
+                    // Now we need to fill up the population remaining with a population selection
+                    let children = breed_many(population.len() - next_population.len());
+                    for child in children {
+                        next_population.push(child);
+                    }
+                }
+                Replacement::DeterministicCrowding => {
+                    for (node, score) in population.iter_mut().zip(&raw_scores) {
+                        node.score = *score;
+                    }
+                    next_population = deterministic_crowding(
+                        population.as_slice(),
+                        algo.distance(),
+                        |left, right| breed(algo, left.clone(), right.clone(), params),
+                        |node| {
+                            evaluate(node);
+                        },
+                        streams.derive(CROWDING),
+                    );
+                }
+                Replacement::RestrictedTournament { window } => {
+                    let mut offspring = breed_many(population.len());
+                    offspring
+                        .par_iter_mut()
+                        .enumerate()
+                        .for_each(|(idx, node)| {
+                            let _stream = streams.derive(OFFSPRING).derive(idx as u64).enter();
+                            evaluate(node);
+                        });
+                    let mut incumbents = std::mem::take(&mut population);
+                    for (node, score) in incumbents.iter_mut().zip(&raw_scores) {
+                        node.score = *score;
+                    }
+                    next_population =
+                        restricted_tournament(incumbents, offspring, window, algo.distance());
+                }
+            }
+
+            // Archived elites rejoin the population now and then
+            if let Some(archive) = &params.elite_archive {
+                let interval = archive.reinsertion_interval;
+                if interval > 0 && (generation + 1) % interval == 0 {
+                    let count = archive.reinsertions.min(elites.len());
+                    let targets = sample(&mut crate::random::rng(), next_population.len(), count);
+                    for (target, elite) in targets.into_iter().zip(&elites) {
+                        next_population[target] = elite.clone();
+                    }
+                }
+            }
+
+            // Fresh blood keeps the population from settling
+            if params.random_immigrant_rate > 0.0 {
+                immigrate(
+                    algo,
+                    next_population.as_mut_slice(),
+                    params.random_immigrant_rate,
+                    input_data,
+                    params,
+                );
+            }
+
+            // Keep clones of the elites from taking over
+            if params.deduplicate {
+                deduplicate(algo, next_population.as_mut_slice(), input_data, params);
+            }
+
+            let recombination_time = recombination_started.elapsed();
+            #[cfg(feature = "tracing")]
+            drop(next_generation_span_entered);
+
+            // Now promote next_pop into real pop. Swapping the buffers moves
+            // the nodes and keeps both allocations for the next generation.
+            std::mem::swap(&mut population, &mut next_population);
+            next_population.clear();
+
+            // The store keeps a copy of the population to resume from. The
+            // run carries on with its own, since a stored node need not
+            // keep everything the runner knows about it.
+            if let Some(store) = store {
+                match store.save(generation + 1, &population) {
+                    Ok(()) => {}
+                    Err(_error) => {
+                        #[cfg(feature = "tracing")]
+                        event!(
+                            Level::WARN,
+                            msg = "Population store failed",
+                            error = %_error
+                        );
+                    }
+                }
+            }
+
+            // Grow or shrink mutation based on how often offspring improved
+            if let Some(rule) = &params.success_rule {
+                params.state.set_mutation_scale(rule.adapt(
+                    params.state.mutation_scale(),
+                    offspring.load(Ordering::Relaxed),
+                    successes.load(Ordering::Relaxed),
+                ));
+            }
+
+            #[cfg(feature = "tracing")]
+            let statistics = &history[history.len() - 1];
+            #[cfg(feature = "tracing")]
+            event!(
+                Level::INFO,
+                msg = "Generation finished processing",
+                generation = generation,
+                score = best_score,
+                mean_score = statistics.mean,
+                median_score = statistics.median,
+                score_standard_deviation = statistics.standard_deviation,
+                score_variance = statistics.score_variance,
+                genotype_entropy = statistics.genotype_entropy,
+                genotype_diversity = statistics.mean_distance,
+                species = statistics.species.len()
+            );
+
+            if let Some(sink) = algo.telemetry_sink() {
+                sink.record(&IterationTelemetry {
+                    generation,
+                    compute_time,
+                    recombination_time,
+                    generation_time: generation_started.elapsed(),
+                    best_score,
+                    #[cfg(feature = "memory")]
+                    population_bytes: memory::population_bytes(&population, |solution| {
+                        algo.solution_bytes(solution)
+                    }),
+                    #[cfg(feature = "memory")]
+                    allocations: memory::allocations().0 - allocations_before.0,
+                    #[cfg(feature = "memory")]
+                    allocated_bytes: memory::allocations().1 - allocations_before.1,
+                });
+            }
+
+            // Invoke the callback if present
+            if let (Some(func), Some(output), Some(node)) =
+                (on_generation_complete, &best_output, &best_node)
+            {
+                if func(best_score, &node.solution, output) {
+                    #[cfg(feature = "tracing")]
+                    event!(
+                        Level::INFO,
+                        msg = "Winning condition met",
+                        best_score = best_score
+                    );
+
+                    winning_condition_found = true;
+                }
+            }
+
+            #[cfg(feature = "tracing")]
+            drop(generation_span_entered);
+
+            if winning_condition_found {
+                break;
+            }
+        }
+
+        AlgenResult {
+            score: best_score,
+            output: best_output,
+            node: best_node,
+            pareto_front: Vec::new(),
+            archive: elites,
+            quality_diversity: Vec::new(),
+            hypervolume: Vec::new(),
+            history,
+            genealogy,
+        }
+    })
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+    use crate::models::local_search::{Learning, LocalSearch, LocalSearchParameters};
+    use crate::models::operator_selection::{OperatorBandit, OperatorSelection};
+    use crate::models::success_rule::SuccessRule;
+
+    #[test]
+    fn it_works() {
+        let result = run_algorithm(
+            &parameters(),
+            &(),
+            &CountOnes {},
+            &CountOnesAnalyzer {},
+            None,
+        );
+        assert!(result.score >= 12.0);
+        assert!(result.node.is_some());
+
+        assert_eq!(result.history.len(), 50);
+        let last = result.history.last().unwrap();
+        assert_eq!(last.best, result.score);
+        assert!(last.worst <= last.lower_quartile && last.upper_quartile <= last.best);
+    }
+
+    #[test]
+    fn negative_scores_still_have_a_best() {
+        /// Scores every genome below zero, the fewer zeros the better.
+        struct Zeros {}
+        impl Analyzer<(), Vec<bool>, ()> for Zeros {
+            fn evaluate(&self, attempt: &Vec<bool>, params: &TestParameters<()>) -> f32 {
+                CountOnesAnalyzer {}.evaluate(attempt, params) - 17.0
+            }
+        }
+
+        let result = run_algorithm(&parameters(), &(), &CountOnes {}, &Zeros {}, None);
+        assert!(result.score < 0.0 && result.score >= -5.0);
+        assert_eq!(result.node.unwrap().score, result.score);
+        assert!(result.output.is_some());
+    }
+
+    #[test]
+    fn column_layout_works() {
+        let params = TestParameters {
+            layout: models::layout::PopulationLayout::Columns,
+            ..parameters()
+        };
+        let result = run_algorithm(&params, &(), &CountOnes {}, &CountOnesAnalyzer {}, None);
+        assert!(result.score >= 12.0);
+        assert_eq!(result.history.last().unwrap().best, result.score);
+    }
+
+    #[cfg(feature = "parallel")]
+    #[test]
+    fn runs_use_the_given_thread_pool() {
+        struct PoolSize(AtomicUsize);
+        impl Analyzer<(), Vec<bool>, ()> for PoolSize {
+            fn evaluate(&self, attempt: &Vec<bool>, params: &TestParameters<()>) -> f32 {
+                self.0
+                    .fetch_max(rayon::current_num_threads(), Ordering::Relaxed);
+                CountOnesAnalyzer {}.evaluate(attempt, params)
+            }
+        }
+
+        let pool = rayon::ThreadPoolBuilder::new()
+            .num_threads(3)
+            .build()
+            .unwrap();
+        let params = TestParameters {
+            generations: 5,
+            thread_pool: Some(std::sync::Arc::new(pool)),
+            ..parameters()
+        };
+        let analyzer = PoolSize(AtomicUsize::new(0));
+        run_algorithm(&params, &(), &CountOnes {}, &analyzer, None);
+        assert_eq!(analyzer.0.into_inner(), 3);
+
+        // A thread count takes precedence over the pool
+        let params = TestParameters {
+            parallelism: models::parallelism::Parallelism::Sequential,
+            ..params
+        };
+        let analyzer = PoolSize(AtomicUsize::new(0));
+        run_algorithm(&params, &(), &CountOnes {}, &analyzer, None);
+        assert_eq!(analyzer.0.into_inner(), 1);
+    }
+
+    #[test]
+    fn seeded_runs_repeat_on_any_number_of_threads() {
+        let run = |parallelism, replacement| {
+            let params = TestParameters {
+                seed: Some(42),
+                generations: 20,
+                parallelism,
+                replacement,
+                ..parameters()
+            };
+            run_algorithm(&params, &(), &CountOnes {}, &CountOnesAnalyzer {}, None)
+        };
+
+        for replacement in [
+            Replacement::Generational,
+            Replacement::DeterministicCrowding,
+        ] {
+            let threaded = run(models::parallelism::Parallelism::Threads(4), replacement);
+            let sequential = run(models::parallelism::Parallelism::Sequential, replacement);
+            assert_eq!(threaded.history, sequential.history);
+            assert_eq!(
+                threaded.node.unwrap().solution,
+                sequential.node.unwrap().solution
+            );
+        }
+    }
+
+    #[test]
+    fn every_runner_repeats_a_seeded_run() {
+        let params = |parallelism| TestParameters {
+            seed: Some(7),
+            generations: 5,
+            parallelism,
+            ..parameters()
+        };
+        let descriptor =
+            |output: &Vec<bool>| vec![output.iter().filter(|bit| **bit).count() as f32];
+        let runs = |parallelism| {
+            let params = params(parallelism);
+            let (algo, analyzer) = (&CountOnes {}, &CountOnesAnalyzer {});
+            let islands = models::islands::IslandParameters::default();
+            let alps = models::alps::AlpsParameters::default();
+            let cellular = models::cellular::CellularParameters::default();
+            let novelty = models::novelty::NoveltyParameters::default();
+            [
+                run_islands(&params, &islands, &(), algo, analyzer, None),
+                run_alps(&params, &alps, &(), algo, analyzer, None),
+                run_cellular(&params, &cellular, &(), algo, analyzer, None),
+                run_novelty_search(&params, &novelty, &(), algo, analyzer, &descriptor, None),
+                run_nsga2(&params, &(), algo, &TradeOff {}, None),
+            ]
+            .map(|result| {
+                let front = result.pareto_front.into_iter().map(|node| node.solution);
+                (
+                    result.node.map(|node| node.solution),
+                    front.collect::<Vec<_>>(),
+                )
             })
-            .take(population.len() - next_population.len())
-            .filter(|x| x.is_some())
-            .map(|x| x.unwrap())
-            .collect::<Vec<Node<Solution>>>();
+        };
+
+        let threaded = runs(models::parallelism::Parallelism::Threads(4));
+        let sequential = runs(models::parallelism::Parallelism::Sequential);
+        assert_eq!(threaded, sequential);
+    }
 
-        for child in children {
-            next_population.push(child);
+    #[test]
+    fn every_runner_records_its_history() {
+        let params = TestParameters {
+            generations: 5,
+            ..parameters()
+        };
+        let descriptor =
+            |output: &Vec<bool>| vec![output.iter().filter(|bit| **bit).count() as f32];
+        let (algo, analyzer) = (&CountOnes {}, &CountOnesAnalyzer {});
+        let islands = models::islands::IslandParameters::default();
+        let alps = models::alps::AlpsParameters::default();
+        let cellular = models::cellular::CellularParameters::default();
+        let novelty = models::novelty::NoveltyParameters::default();
+        let map_elites = models::map_elites::MapElitesParameters {
+            dimensions: vec![models::map_elites::FeatureDimension {
+                min: 0.0,
+                max: 16.0,
+                bins: 4,
+            }],
+        };
+        for result in [
+            run_islands(&params, &islands, &(), algo, analyzer, None),
+            run_alps(&params, &alps, &(), algo, analyzer, None),
+            run_cellular(&params, &cellular, &(), algo, analyzer, None),
+            run_novelty_search(&params, &novelty, &(), algo, analyzer, &descriptor, None),
+            run_map_elites(&params, &map_elites, &(), algo, analyzer, &descriptor, None),
+        ] {
+            let generations: Vec<usize> = result.history.iter().map(|s| s.generation).collect();
+            assert_eq!(generations, (0..5).collect::<Vec<usize>>());
+            // Cellular offspring of the last generation are scored after it
+            let best = result
+                .history
+                .iter()
+                .map(|s| s.best)
+                .fold(f32::MIN, f32::max);
+            assert!(best > 0.0 && best <= result.score);
         }
+    }
+
+    #[test]
+    fn chunked_evaluation_works() {
+        let params = TestParameters {
+            evaluation: Evaluation::Chunked { chunk_size: 0 },
+            ..parameters()
+        };
+        let result = run_algorithm(&params, &(), &CountOnes {}, &CountOnesAnalyzer {}, None);
+        assert!(result.score >= 12.0);
+    }
 
-        #[cfg(feature = "tracing")]
-        drop(next_generation_span_entered);
+    #[test]
+    fn batch_evaluation_scores_whole_generations() {
+        struct Batched(AtomicUsize);
+        impl Analyzer<(), Vec<bool>, ()> for Batched {
+            fn evaluate(&self, _: &Vec<bool>, _: &TestParameters<()>) -> f32 {
+                unreachable!("every output is scored in a batch")
+            }
 
-        // Now promote next_pop into real pop
-        population.clear();
-        for node in next_population.clone() {
-            population.push(node.clone());
+            fn evaluate_batch(
+                &self,
+                attempts: &[Vec<bool>],
+                scores: &mut [f32],
+                params: &TestParameters<()>,
+            ) {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                for (attempt, score) in attempts.iter().zip(scores.iter_mut()) {
+                    *score = CountOnesAnalyzer {}.evaluate(attempt, params);
+                }
+            }
         }
-        next_population.clear();
 
-        #[cfg(feature = "tracing")]
-        event!(
-            Level::INFO,
-            msg = "Generation finished processing",
-            generation = generation,
-            score = best_score
-        );
+        let params = TestParameters {
+            evaluation: Evaluation::Batch,
+            ..parameters()
+        };
+        let analyzer = Batched(AtomicUsize::new(0));
+        let result = run_algorithm(&params, &(), &CountOnes {}, &analyzer, None);
+        assert!(result.score >= 12.0);
+        assert_eq!(analyzer.0.into_inner(), 50);
+    }
 
-        // Invoke the callback if present
-        match on_generation_complete {
-            None => {}
-            Some(func) => match &best_output {
-                None => {}
-                Some(output) => match &best_solution {
-                    None => {}
-                    Some(solution) => {
-                        if func(best_score, &solution, &output) {
-                            #[cfg(feature = "tracing")]
-                            event!(
-                                Level::INFO,
-                                msg = "Winning condition met",
-                                best_score = best_score
-                            );
-
-                            winning_condition_found = true;
-                        }
+    /// Counts ones on a pretend device, one byte per bit.
+    struct Device(AtomicUsize);
+
+    impl models::accelerator::Accelerator<Vec<bool>> for Device {
+        fn encode(&self, solution: &Vec<bool>, out: &mut Vec<u8>) {
+            out.extend(solution.iter().map(|bit| *bit as u8));
+        }
+
+        fn capacity(&self) -> usize {
+            16 * 40
+        }
+
+        fn score(&self, genomes: &[u8], offsets: &[usize], scores: &mut [f32]) {
+            self.0.fetch_add(1, Ordering::Relaxed);
+            for (offset, score) in offsets.iter().zip(scores) {
+                *score = genomes[*offset..*offset + 16].iter().sum::<u8>() as f32;
+            }
+        }
+    }
+
+    #[test]
+    fn accelerators_score_whole_generations() {
+        struct Unused {}
+        impl Analyzer<(), Vec<bool>, ()> for Unused {
+            fn evaluate(&self, _: &Vec<bool>, _: &TestParameters<()>) -> f32 {
+                unreachable!("the accelerator does the scoring")
+            }
+        }
+
+        let device = Device(AtomicUsize::new(0));
+        let algo = Configured {
+            accelerator: Some(&device),
+            ..Default::default()
+        };
+        let result = run_algorithm(&parameters(), &(), &algo, &Unused {}, None);
+        assert!(result.score >= 12.0);
+        assert_eq!(result.output, result.node.map(|node| node.solution));
+        // A hundred genomes only fit on the device forty at a time
+        assert!(device.0.into_inner() >= 50 * 3);
+    }
+
+    /// Turns a genome into the bits which match the input, and can send
+    /// snapshots of the population somewhere.
+    #[derive(Default)]
+    struct Target<'a> {
+        snapshots: Option<&'a (dyn models::snapshot::SnapshotSink<Vec<bool>> + Sync)>,
+    }
+
+    impl Algorithm<bool, Vec<bool>, Vec<bool>, ()> for Target<'_> {
+        fn output(
+            &self,
+            node: &Node<Vec<bool>>,
+            target: &bool,
+            _params: &TestParameters<()>,
+        ) -> Vec<bool> {
+            node.solution.iter().map(|bit| bit == target).collect()
+        }
+
+        fn allocate_node(&self, _: &bool, params: &TestParameters<()>) -> Node<Vec<bool>> {
+            CountOnes {}.allocate_node(&(), params)
+        }
+
+        fn combine_node(
+            &self,
+            left: Node<Vec<bool>>,
+            right: Node<Vec<bool>>,
+            params: &TestParameters<()>,
+        ) -> Node<Vec<bool>> {
+            CountOnes {}.combine_node(left, right, params)
+        }
+
+        fn snapshot_sink(&self) -> Option<&dyn models::snapshot::SnapshotSink<Vec<bool>>> {
+            self.snapshots.map(|snapshots| snapshots as _)
+        }
+    }
+
+    /// Count the bits which match the input.
+    struct Agreement {}
+    impl Analyzer<bool, Vec<bool>, ()> for Agreement {
+        fn evaluate(&self, attempt: &Vec<bool>, params: &TestParameters<()>) -> f32 {
+            CountOnesAnalyzer {}.evaluate(attempt, params)
+        }
+    }
+
+    #[test]
+    fn dynamic_runs_follow_the_input() {
+        // The target flips from ones to zeros halfway through
+        let inputs = |generation: usize| (generation == 25).then_some(false);
+        for rescoring in [Rescoring::All, Rescoring::Elites] {
+            let params = TestParameters {
+                rescoring,
+                ..parameters()
+            };
+            let result = run_dynamic(
+                &params,
+                &inputs,
+                &true,
+                &Target::default(),
+                &Agreement {},
+                None,
+            );
+            let zeros = result
+                .node
+                .unwrap()
+                .solution
+                .iter()
+                .filter(|bit| !**bit)
+                .count();
+            assert!(result.score >= 12.0);
+            assert_eq!(zeros as f32, result.score);
+        }
+    }
+
+    #[test]
+    fn survivors_are_rescored_when_the_input_changes() {
+        for replacement in [
+            Replacement::DeterministicCrowding,
+            Replacement::RestrictedTournament { window: 8 },
+        ] {
+            // Survivors of generation nine are seen right after they have
+            // been scored on the flipped input
+            let (survivors, stale) = (AtomicUsize::new(0), AtomicUsize::new(0));
+            let sink = |snapshot: &Snapshot<Vec<bool>>| {
+                let nodes = snapshot.nodes.iter();
+                for node in nodes.filter(|node| node.birth_generation < snapshot.generation) {
+                    let zeros = node.solution.iter().filter(|bit| !**bit).count();
+                    survivors.fetch_add(1, Ordering::Relaxed);
+                    if node.score != zeros as f32 {
+                        stale.fetch_add(1, Ordering::Relaxed);
                     }
-                },
+                }
+            };
+            let params = TestParameters {
+                generations: 11,
+                snapshot_interval: 10,
+                rescoring: Rescoring::Elites,
+                replacement,
+                ..parameters()
+            };
+            let algo = Target {
+                snapshots: Some(&sink),
+            };
+            let inputs = |generation: usize| (generation == 10).then_some(false);
+            run_dynamic(&params, &inputs, &true, &algo, &Agreement {}, None);
+            assert!(survivors.into_inner() > 0);
+            assert_eq!(stale.into_inner(), 0);
+        }
+    }
+
+    #[test]
+    fn archived_elites_are_forgotten_when_the_input_changes() {
+        let params = TestParameters {
+            generations: 30,
+            elite_archive: Some(models::elite_archive::EliteArchive::default()),
+            ..parameters()
+        };
+        let inputs = |generation: usize| (generation == 25).then_some(false);
+        let result = run_dynamic(
+            &params,
+            &inputs,
+            &true,
+            &Target::default(),
+            &Agreement {},
+            None,
+        );
+        assert!(!result.archive.is_empty());
+        for elite in result.archive {
+            let zeros = elite.solution.iter().filter(|bit| !**bit).count();
+            assert_eq!(elite.score, zeros as f32);
+        }
+    }
+
+    #[test]
+    fn slow_evaluations_are_penalized() {
+        /// Hangs on any genome which starts with a one, until its time
+        /// is up.
+        struct Hangs {}
+        impl Analyzer<(), Vec<bool>, ()> for Hangs {
+            fn evaluate(&self, attempt: &Vec<bool>, params: &TestParameters<()>) -> f32 {
+                while attempt[0] && !deadline::expired() {
+                    std::thread::yield_now();
+                }
+                CountOnesAnalyzer {}.evaluate(attempt, params)
+            }
+        }
+
+        let params = TestParameters {
+            generations: 20,
+            evaluation_timeout: Some(models::evaluation::EvaluationTimeout {
+                limit: Duration::from_millis(5),
+                penalty: -1.0,
+            }),
+            ..parameters()
+        };
+        let result = run_algorithm(&params, &(), &CountOnes {}, &Hangs {}, None);
+        assert!(result.score >= 10.0);
+        assert!(!result.node.unwrap().solution[0]);
+        assert!(!deadline::expired() && deadline::remaining().is_none());
+    }
+
+    #[test]
+    fn panics_are_penalized() {
+        /// Panics on any genome which starts with three ones.
+        struct Panics {}
+        impl Analyzer<(), Vec<bool>, ()> for Panics {
+            fn evaluate(&self, attempt: &Vec<bool>, params: &TestParameters<()>) -> f32 {
+                if attempt[..3].iter().all(|bit| *bit) {
+                    // Unwinds like a panic, without printing one
+                    std::panic::resume_unwind(Box::new("evaluation failed"));
+                }
+                CountOnesAnalyzer {}.evaluate(attempt, params)
+            }
+        }
+
+        let params = TestParameters {
+            panic_penalty: Some(-1.0),
+            ..parameters()
+        };
+        let result = run_algorithm(&params, &(), &CountOnes {}, &Panics {}, None);
+        assert!(result.score >= 10.0);
+        assert!(!result.node.unwrap().solution[..3].iter().all(|bit| *bit));
+    }
+
+    #[test]
+    fn batches_survive_panicking_outputs() {
+        /// Panics on any genome which starts with three ones.
+        struct Panics {}
+        impl Algorithm<(), Vec<bool>, Vec<bool>, ()> for Panics {
+            fn output(
+                &self,
+                node: &Node<Vec<bool>>,
+                input: &(),
+                params: &TestParameters<()>,
+            ) -> Vec<bool> {
+                if node.solution[..3].iter().all(|bit| *bit) {
+                    std::panic::resume_unwind(Box::new("output failed"));
+                }
+                CountOnes {}.output(node, input, params)
+            }
+
+            fn allocate_node(&self, input: &(), params: &TestParameters<()>) -> Node<Vec<bool>> {
+                CountOnes {}.allocate_node(input, params)
+            }
+
+            fn combine_node(
+                &self,
+                left: Node<Vec<bool>>,
+                right: Node<Vec<bool>>,
+                params: &TestParameters<()>,
+            ) -> Node<Vec<bool>> {
+                CountOnes {}.combine_node(left, right, params)
+            }
+        }
+
+        let params = TestParameters {
+            evaluation: Evaluation::Batch,
+            panic_penalty: Some(-1.0),
+            ..parameters()
+        };
+        let result = run_algorithm(&params, &(), &Panics {}, &CountOnesAnalyzer {}, None);
+        assert!(result.score >= 10.0);
+        assert!(!result.node.unwrap().solution[..3].iter().all(|bit| *bit));
+    }
+
+    #[test]
+    fn deterministic_crowding_works() {
+        let params = TestParameters {
+            replacement: Replacement::DeterministicCrowding,
+            ..parameters()
+        };
+        let result = run_algorithm(&params, &(), &CountOnes {}, &CountOnesAnalyzer {}, None);
+        assert!(result.score >= 12.0);
+    }
+
+    #[test]
+    fn restricted_tournament_works() {
+        let params = TestParameters {
+            replacement: Replacement::RestrictedTournament { window: 8 },
+            ..parameters()
+        };
+        let result = run_algorithm(&params, &(), &CountOnes {}, &CountOnesAnalyzer {}, None);
+        assert!(result.score >= 12.0);
+    }
+
+    #[test]
+    fn speciation_works() {
+        let params = TestParameters {
+            speciation: Some(models::speciation::Speciation {
+                threshold: 4.0,
+                stagnation: 5,
+            }),
+            ..parameters()
+        };
+        let algo = genomes::bit_string::BitString::new(16);
+        let result = run_algorithm(&params, &(), &algo, &CountOnesAnalyzer {}, None);
+        assert!(result.score >= 12.0);
+
+        // Every node belongs to exactly one species
+        for statistics in &result.history {
+            assert!(!statistics.species.is_empty());
+            let size: usize = statistics.species.iter().map(|species| species.size).sum();
+            assert_eq!(size, params.population);
+        }
+        let last = result.history.last().unwrap();
+        assert!(last.species.iter().any(|species| species.age > 0));
+    }
+
+    /// The generation, size, best score, and oldest age of every
+    /// snapshot.
+    #[derive(Default)]
+    struct Snapshots(std::sync::Mutex<Vec<(usize, usize, f32, usize)>>);
+
+    impl models::snapshot::SnapshotSink<Vec<bool>> for Snapshots {
+        fn record(&self, snapshot: &Snapshot<Vec<bool>>) {
+            let best = snapshot
+                .nodes
+                .iter()
+                .map(|node| node.score)
+                .fold(f32::MIN, f32::max);
+            let oldest = snapshot
+                .nodes
+                .iter()
+                .map(|node| node.age)
+                .max()
+                .unwrap_or(0);
+            self.0
+                .lock()
+                .unwrap()
+                .push((snapshot.generation, snapshot.nodes.len(), best, oldest));
+        }
+    }
+
+    #[test]
+    fn snapshots_are_taken_at_intervals() {
+        let params = TestParameters {
+            generations: 20,
+            snapshot_interval: 5,
+            ..parameters()
+        };
+        let sink = Snapshots::default();
+        let algo = Configured {
+            snapshots: Some(&sink),
+            ..Default::default()
+        };
+        run_algorithm(&params, &(), &algo, &CountOnesAnalyzer {}, None);
+
+        let snapshots = sink.0.into_inner().unwrap();
+        let generations: Vec<usize> = snapshots
+            .iter()
+            .map(|(generation, _, _, _)| *generation)
+            .collect();
+        assert_eq!(generations, vec![0, 5, 10, 15]);
+        assert!(snapshots
+            .iter()
+            .all(|(_, size, best, _)| *size == 100 && *best > 0.0));
+    }
+
+    /// The telemetry of every generation.
+    #[derive(Default)]
+    struct Telemetry(std::sync::Mutex<Vec<IterationTelemetry>>);
+
+    impl models::telemetry::TelemetrySink for Telemetry {
+        fn record(&self, telemetry: &IterationTelemetry) {
+            self.0.lock().unwrap().push(*telemetry);
+        }
+    }
+
+    #[test]
+    fn telemetry_is_recorded_every_generation() {
+        let sink = Telemetry::default();
+        let algo = Configured {
+            telemetry: Some(&sink),
+            ..Default::default()
+        };
+        let result = run_algorithm(&parameters(), &(), &algo, &CountOnesAnalyzer {}, None);
+
+        let telemetry = sink.0.into_inner().unwrap();
+        assert_eq!(telemetry.len(), 50);
+        for (generation, entry) in telemetry.iter().enumerate() {
+            assert_eq!(entry.generation, generation);
+            assert!(entry.generation_time >= entry.compute_time + entry.recombination_time);
+        }
+        assert!(telemetry
+            .windows(2)
+            .all(|pair| pair[0].best_score <= pair[1].best_score));
+        assert_eq!(telemetry[49].best_score, result.score);
+        #[cfg(feature = "memory")]
+        assert!(telemetry.iter().all(|entry| {
+            entry.population_bytes >= 100 * std::mem::size_of::<Node<Vec<bool>>>()
+                && entry.allocations > 0
+        }));
+    }
+
+    #[test]
+    fn old_elites_retire() {
+        let oldest = |max_age| {
+            let params = TestParameters {
+                generations: 30,
+                snapshot_interval: 1,
+                max_age,
+                ..parameters()
+            };
+            let sink = Snapshots::default();
+            let algo = Configured {
+                snapshots: Some(&sink),
+                ..Default::default()
+            };
+            run_algorithm(&params, &(), &algo, &CountOnesAnalyzer {}, None);
+            let snapshots = sink.0.into_inner().unwrap();
+            snapshots
+                .iter()
+                .map(|(_, _, _, oldest)| *oldest)
+                .max()
+                .unwrap()
+        };
+
+        assert!(oldest(None) >= 10);
+        assert!(oldest(Some(3)) <= 3);
+    }
+
+    #[test]
+    fn lineage_leads_back_to_the_first_generation() {
+        let params = TestParameters {
+            generations: 20,
+            track_lineage: true,
+            ..parameters()
+        };
+        let result = run_algorithm(&params, &(), &CountOnes {}, &CountOnesAnalyzer {}, None);
+        let genealogy: std::collections::HashMap<usize, &models::lineage::Lineage> = result
+            .genealogy
+            .iter()
+            .map(|lineage| (lineage.id, lineage))
+            .collect();
+        assert_eq!(genealogy.len(), result.genealogy.len());
+
+        let mut lineage = genealogy[&result.node.unwrap().id];
+        while let Some(parent) = lineage.parents.first() {
+            let parent = genealogy[parent];
+            assert!(parent.birth_generation < lineage.birth_generation);
+            lineage = parent;
+        }
+        assert_eq!(lineage.birth_generation, 0);
+    }
+
+    #[test]
+    fn elites_are_not_evaluated_again() {
+        struct Counting(AtomicUsize);
+        impl Analyzer<(), Vec<bool>, ()> for Counting {
+            fn evaluate(&self, attempt: &Vec<bool>, params: &TestParameters<()>) -> f32 {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                CountOnesAnalyzer {}.evaluate(attempt, params)
+            }
+        }
+
+        let params = TestParameters {
+            generations: 10,
+            elitism_factor: 0.5,
+            ..parameters()
+        };
+        let analyzer = Counting(AtomicUsize::new(0));
+        let result = run_algorithm(&params, &(), &CountOnes {}, &analyzer, None);
+        assert!(result.score >= 12.0);
+        // Everyone is scored in the first generation, and only the
+        // offspring after that
+        assert_eq!(analyzer.0.into_inner(), 100 + 9 * 50);
+    }
+
+    #[test]
+    fn fitness_cache_scores_each_genome_once() {
+        struct Counting(AtomicUsize);
+        impl Analyzer<(), Vec<bool>, ()> for Counting {
+            fn evaluate(&self, attempt: &Vec<bool>, params: &TestParameters<()>) -> f32 {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                CountOnesAnalyzer {}.evaluate(attempt, params)
+            }
+        }
+
+        // Short genomes are rediscovered all the time
+        let params = TestParameters {
+            generations: 20,
+            fitness_cache: Some(1000),
+            ..parameters()
+        };
+        let analyzer = Counting(AtomicUsize::new(0));
+        let algo = genomes::bit_string::BitString::new(6);
+        let result = run_algorithm(&params, &(), &algo, &analyzer, None);
+        assert_eq!(result.score, 6.0);
+        // There are only 64 genomes, though two threads may both miss
+        // the cache on the same genome at once
+        assert!(analyzer.0.into_inner() < 400);
+    }
+
+    #[test]
+    fn elite_archive_keeps_the_best() {
+        let params = TestParameters {
+            elitism_factor: 0.0,
+            elite_archive: Some(models::elite_archive::EliteArchive {
+                size: 5,
+                ..Default::default()
+            }),
+            ..parameters()
+        };
+        let result = run_algorithm(&params, &(), &CountOnes {}, &CountOnesAnalyzer {}, None);
+        assert!(result.archive.len() <= 5);
+        assert_eq!(result.archive[0].score, result.score);
+    }
+
+    #[test]
+    fn queued_evaluation_works() {
+        let params = TestParameters {
+            evaluation: models::evaluation::Evaluation::Queue {
+                workers: 3,
+                record_durations: true,
             },
+            ..parameters()
+        };
+        let result = run_algorithm(&params, &(), &CountOnes {}, &CountOnesAnalyzer {}, None);
+        assert!(result.score >= 12.0);
+        assert!(result.node.unwrap().evaluation_time.is_some());
+    }
+
+    #[test]
+    fn stored_populations_keep_their_evaluations() {
+        struct Counting(AtomicUsize);
+        impl Analyzer<(), Vec<bool>, ()> for Counting {
+            fn evaluate(&self, attempt: &Vec<bool>, params: &TestParameters<()>) -> f32 {
+                self.0.fetch_add(1, Ordering::Relaxed);
+                CountOnesAnalyzer {}.evaluate(attempt, params)
+            }
         }
 
-        #[cfg(feature = "tracing")]
-        drop(generation_span_entered);
+        // A file only keeps part of each node, which the run mustn't lose
+        let path = std::env::temp_dir().join(format!("algen-run-{}", std::process::id()));
+        let store = stores::file::FileStore::new(&path);
+        let params = TestParameters {
+            generations: 10,
+            elitism_factor: 0.5,
+            track_lineage: true,
+            ..parameters()
+        };
+        let analyzer = Counting(AtomicUsize::new(0));
+        let result = run_with_store(&params, &store, &(), &CountOnes {}, &analyzer, None).unwrap();
+        std::fs::remove_file(path).unwrap();
+
+        assert_eq!(analyzer.0.into_inner(), 100 + 9 * 50);
+        assert!(result
+            .genealogy
+            .iter()
+            .any(|lineage| !lineage.parents.is_empty()));
+    }
+
+    #[test]
+    fn stored_populations_resume() {
+        let store = stores::memory::MemoryStore::new();
+        let params = TestParameters {
+            generations: 20,
+            ..parameters()
+        };
+        let (algo, analyzer) = (&CountOnes {}, &CountOnesAnalyzer {});
+        let result = run_with_store(&params, &store, &(), algo, analyzer, None).unwrap();
+        assert!(result.score >= 10.0);
+
+        let (generation, stored) = store.load().unwrap();
+        assert_eq!((generation, stored.len()), (20, params.population));
+        let last_id = stored.iter().map(|node| node.id).max().unwrap();
+
+        // The resumed run picks up the count where the first one stopped
+        let resumed = TestParameters {
+            generations: 25,
+            track_lineage: true,
+            ..params
+        };
+        let result = run_with_store(&resumed, &store, &(), algo, analyzer, None).unwrap();
+        let generations: Vec<usize> = result.history.iter().map(|s| s.generation).collect();
+        assert_eq!(generations, (20..25).collect::<Vec<usize>>());
+        assert_eq!(store.load().unwrap().0, 25);
+        assert!(result.genealogy.iter().all(|lineage| lineage.id > last_id));
+        assert!(
+            result.score
+                >= stored
+                    .iter()
+                    .map(|node| node.score)
+                    .fold(f32::MIN, f32::max)
+        );
+    }
+
+    #[test]
+    fn unreadable_stores_are_errors() {
+        let path = std::env::temp_dir().join(format!("algen-corrupt-{}", std::process::id()));
+        std::fs::write(&path, [0, 0, 0]).unwrap();
+        let store = stores::file::FileStore::new(&path);
+        let result = run_with_store(
+            &parameters(),
+            &store,
+            &(),
+            &CountOnes {},
+            &CountOnesAnalyzer {},
+            None,
+        );
+        assert!(result.is_err());
+        // The population which couldn't be read is left alone
+        assert_eq!(std::fs::read(&path).unwrap(), [0, 0, 0]);
+        std::fs::remove_file(path).unwrap();
+    }
 
-        if winning_condition_found {
-            break;
+    #[test]
+    fn constraints_are_respected() {
+        let algo = Configured {
+            constraints: vec![Box::new(AtMostEight {})],
+            ..Default::default()
+        };
+
+        for handling in [
+            models::constraint::ConstraintHandling::DeathPenalty,
+            models::constraint::ConstraintHandling::FeasibilityFirst,
+        ] {
+            let params = TestParameters {
+                constraint_handling: handling,
+                ..parameters()
+            };
+            let result = run_algorithm(&params, &(), &algo, &CountOnesAnalyzer {}, None);
+            let node = result.node.unwrap();
+            assert_eq!(node.violation, 0.0);
+            assert_eq!(result.score, 8.0);
         }
     }
 
-    return AlgenResult {
-        score: best_score,
-        output: best_output,
-        node: best_node,
-    };
-}
+    #[test]
+    fn accelerated_runs_respect_constraints() {
+        let device = Device(AtomicUsize::new(0));
+        let algo = Configured {
+            accelerator: Some(&device),
+            constraints: vec![Box::new(AtMostEight {})],
+            ..Default::default()
+        };
+        let params = TestParameters {
+            constraint_handling: models::constraint::ConstraintHandling::FeasibilityFirst,
+            ..parameters()
+        };
+        let result = run_algorithm(&params, &(), &algo, &CountOnesAnalyzer {}, None);
+        assert_eq!(result.node.unwrap().violation, 0.0);
+        assert_eq!(result.score, 8.0);
+    }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    #[test]
+    fn every_runner_respects_constraints() {
+        let algo = &Configured {
+            constraints: vec![Box::new(AtMostEight {})],
+            ..Default::default()
+        };
+        let analyzer = &CountOnesAnalyzer {};
+        let params = TestParameters {
+            constraint_handling: models::constraint::ConstraintHandling::DeathPenalty,
+            generations: 10,
+            ..parameters()
+        };
+        let descriptor =
+            |output: &Vec<bool>| vec![output.iter().filter(|bit| **bit).count() as f32];
+        let islands = models::islands::IslandParameters::default();
+        let map_elites = models::map_elites::MapElitesParameters::default();
+        let novelty = models::novelty::NoveltyParameters::default();
+        let results = [
+            run_islands(&params, &islands, &(), algo, analyzer, None),
+            run_alps(&params, &Default::default(), &(), algo, analyzer, None),
+            run_cellular(&params, &Default::default(), &(), algo, analyzer, None),
+            run_novelty_search(&params, &novelty, &(), algo, analyzer, &descriptor, None),
+            run_map_elites(&params, &map_elites, &(), algo, analyzer, &descriptor, None),
+        ];
+        for result in results {
+            assert!(result.score <= 8.0);
+            assert_eq!(result.node.unwrap().violation, 0.0);
+        }
+    }
 
     #[test]
-    fn it_works() {}
+    fn offspring_are_repaired() {
+        let params = TestParameters {
+            generations: 2,
+            ..parameters()
+        };
+        let algo = Configured {
+            repair: Some(|solution| solution.iter_mut().for_each(|bit| *bit = true)),
+            ..Default::default()
+        };
+        let result = run_algorithm(&params, &(), &algo, &CountOnesAnalyzer {}, None);
+        assert_eq!(result.score, 16.0);
+    }
+
+    #[test]
+    fn success_rule_shrinks_mutation_once_converged() {
+        let params = TestParameters {
+            mutation_factor: 0.1,
+            success_rule: Some(SuccessRule::default()),
+            ..parameters()
+        };
+        // Remembers the mutation factor the last offspring was bred with
+        struct Recorded(Mutex<f32>);
+        impl Algorithm<(), Vec<bool>, Vec<bool>, ()> for Recorded {
+            fn output(
+                &self,
+                node: &Node<Vec<bool>>,
+                input: &(),
+                params: &TestParameters<()>,
+            ) -> Vec<bool> {
+                CountOnes {}.output(node, input, params)
+            }
+
+            fn allocate_node(&self, input: &(), params: &TestParameters<()>) -> Node<Vec<bool>> {
+                CountOnes {}.allocate_node(input, params)
+            }
+
+            fn combine_node(
+                &self,
+                left: Node<Vec<bool>>,
+                right: Node<Vec<bool>>,
+                params: &TestParameters<()>,
+            ) -> Node<Vec<bool>> {
+                *self.0.lock().unwrap() = params.current_mutation_factor();
+                CountOnes {}.combine_node(left, right, params)
+            }
+        }
+
+        let algo = Recorded(Mutex::new(0.1));
+        let result = run_algorithm(&params, &(), &algo, &CountOnesAnalyzer {}, None);
+
+        assert!(result.score >= 12.0);
+        assert!(*algo.0.lock().unwrap() < 0.1);
+        // The run adapted its own copy of the parameters
+        assert_eq!(params.state.mutation_scale(), 1.0);
+        assert_eq!(params.state.generation(), 0);
+    }
+
+    #[test]
+    fn multi_parent_recombination_works() {
+        let params = TestParameters {
+            parents: 4,
+            distinct_parents: true,
+            track_lineage: true,
+            ..parameters()
+        };
+        let algo = Configured::default();
+        let result = run_algorithm(&params, &(), &algo, &CountOnesAnalyzer {}, None);
+        assert!(result.score >= 12.0);
+        assert!(result
+            .genealogy
+            .iter()
+            .all(|lineage| lineage.parents.is_empty() || lineage.parents.len() == 4));
+    }
+
+    struct BitFlip {}
+    impl LocalSearch<Vec<bool>> for BitFlip {
+        fn neighbor(&self, solution: &Vec<bool>) -> Vec<bool> {
+            let mut neighbor = solution.clone();
+            let idx = crate::random::rng().gen_range(0..neighbor.len());
+            neighbor[idx] = !neighbor[idx];
+            neighbor
+        }
+    }
+
+    #[test]
+    fn local_search_improves_offspring() {
+        let params = TestParameters {
+            generations: 3,
+            population: 20,
+            local_search: Some(LocalSearchParameters {
+                probability: 1.0,
+                steps: 100,
+                ..Default::default()
+            }),
+            ..parameters()
+        };
+        let algo = Configured {
+            local_search: Some(&BitFlip {}),
+            ..Default::default()
+        };
+        let result = run_algorithm(&params, &(), &algo, &CountOnesAnalyzer {}, None);
+        assert_eq!(result.score, 16.0);
+    }
+
+    #[test]
+    fn baldwinian_learning_keeps_the_genome() {
+        let params = TestParameters {
+            generations: 3,
+            population: 20,
+            mutation_factor: 0.0,
+            local_search: Some(LocalSearchParameters {
+                probability: 1.0,
+                steps: 100,
+                learning: Learning::Baldwinian,
+            }),
+            ..parameters()
+        };
+        let algo = Configured {
+            local_search: Some(&BitFlip {}),
+            ..Default::default()
+        };
+        let result = run_algorithm(&params, &(), &algo, &CountOnesAnalyzer {}, None);
+
+        // The winner earned its score by learning, not through its genes
+        let node = result.node.unwrap();
+        assert_eq!(result.score, 16.0);
+        assert_eq!(node.learned_score, Some(16.0));
+        assert!(node.solution.iter().any(|bit| !*bit));
+    }
+
+    #[test]
+    fn operator_bandit_favours_useful_operators() {
+        // Operator zero only ever copies a parent, so it never improves.
+        // Without any reward, probability matching shares offspring evenly.
+        let algo = Configured {
+            bandit: Some(OperatorBandit::new(
+                2,
+                OperatorSelection::ProbabilityMatching {
+                    min_probability: 0.05,
+                    adaptation_rate: 0.3,
+                },
+            )),
+            ..Default::default()
+        };
+        let params = TestParameters {
+            seed: Some(1),
+            parallelism: models::parallelism::Parallelism::Sequential,
+            generations: 20,
+            mutation_factor: 0.05,
+            ..parameters()
+        };
+        run_algorithm(&params, &(), &algo, &CountOnesAnalyzer {}, None);
+
+        let usage = algo.bandit.unwrap().usage();
+        assert!(usage[1] > 0.8, "{usage:?}");
+    }
+
+    #[test]
+    fn every_runner_rewards_the_operator_bandit() {
+        // The bandit is shared between threads, so the run is kept to
+        // one thread as well as seeded for it to pick the same operators
+        let params = TestParameters {
+            seed: Some(1),
+            parallelism: models::parallelism::Parallelism::Sequential,
+            generations: 20,
+            mutation_factor: 0.05,
+            ..parameters()
+        };
+        let analyzer = &CountOnesAnalyzer {};
+        let descriptor =
+            |output: &Vec<bool>| vec![output.iter().filter(|bit| **bit).count() as f32];
+        let islands = models::islands::IslandParameters::default();
+        let alps = models::alps::AlpsParameters::default();
+        let cellular = models::cellular::CellularParameters::default();
+        let novelty = models::novelty::NoveltyParameters::default();
+        let competitive = models::competitive::CompetitiveParameters::default();
+        /// Each side's payoff is its own number of ones.
+        struct Ones {}
+        impl models::competitive::Game<Vec<bool>, Vec<bool>, ()> for Ones {
+            fn play(
+                &self,
+                host: &Vec<bool>,
+                parasite: &Vec<bool>,
+                params: &TestParameters<()>,
+            ) -> (f32, f32) {
+                let ones = |attempt| CountOnesAnalyzer {}.evaluate(attempt, params);
+                (ones(host), ones(parasite))
+            }
+        }
+        type Run<'a> = &'a dyn Fn(&Configured) -> AlgenResult<Vec<bool>, Vec<bool>>;
+        type Expect = fn(&OperatorBandit) -> bool;
+        // Runners which reward improvements in score move the bandit onto
+        // the operator which improves, where it would otherwise share
+        // offspring evenly. Novelty search rewards gains in novelty
+        // instead, which either operator can come by, so it only has to
+        // credit its offspring.
+        let improves: Expect = |bandit| bandit.usage()[1] > 0.8;
+        let rewarded: Expect = |bandit| bandit.rewards().iter().all(|rewards| *rewards > 0);
+        let runs: [(Run, Expect); 5] = [
+            (
+                &|algo| run_islands(&params, &islands, &(), algo, analyzer, None),
+                improves,
+            ),
+            (
+                &|algo| run_alps(&params, &alps, &(), algo, analyzer, None),
+                improves,
+            ),
+            (
+                &|algo| run_cellular(&params, &cellular, &(), algo, analyzer, None),
+                improves,
+            ),
+            (
+                &|algo| {
+                    run_novelty_search(&params, &novelty, &(), algo, analyzer, &descriptor, None)
+                },
+                rewarded,
+            ),
+            (
+                &|algo| {
+                    let parasites = &CountOnes {};
+                    run_competitive(&params, &competitive, &(), algo, parasites, &Ones {}, None).0
+                },
+                improves,
+            ),
+        ];
+        for (run, expect) in runs {
+            // Operator zero only ever copies a parent, so it never improves
+            let algo = Configured {
+                bandit: Some(OperatorBandit::new(
+                    2,
+                    OperatorSelection::ProbabilityMatching {
+                        min_probability: 0.05,
+                        adaptation_rate: 0.3,
+                    },
+                )),
+                ..Default::default()
+            };
+            run(&algo);
+            let bandit = algo.bandit.unwrap();
+            assert!(expect(&bandit), "{:?}", bandit.usage());
+        }
+    }
+
+    /// Solutions shared behind an `Arc`, which are copied on write.
+    struct SharedCountOnes {}
+    impl Algorithm<(), Vec<bool>, std::sync::Arc<Vec<bool>>, ()> for SharedCountOnes {
+        fn output(
+            &self,
+            node: &Node<std::sync::Arc<Vec<bool>>>,
+            _input: &(),
+            _params: &TestParameters<()>,
+        ) -> Vec<bool> {
+            node.solution.to_vec()
+        }
+
+        fn allocate_node(
+            &self,
+            input: &(),
+            params: &TestParameters<()>,
+        ) -> Node<std::sync::Arc<Vec<bool>>> {
+            Node::new(CountOnes {}.allocate_node(input, params).solution.into())
+        }
+
+        fn combine_node(
+            &self,
+            mut left: Node<std::sync::Arc<Vec<bool>>>,
+            right: Node<std::sync::Arc<Vec<bool>>>,
+            params: &TestParameters<()>,
+        ) -> Node<std::sync::Arc<Vec<bool>>> {
+            let mut rng = crate::random::rng();
+            let bits = std::sync::Arc::make_mut(&mut left.solution);
+            for (bit, other) in bits.iter_mut().zip(right.solution.iter()) {
+                if rng.gen_bool(0.5) {
+                    *bit = *other;
+                }
+                *bit ^= rng.gen_bool(params.current_mutation_factor() as f64);
+            }
+            Node::new(left.solution)
+        }
+    }
+
+    #[test]
+    fn shared_solutions_work() {
+        let result = run_algorithm(
+            &parameters(),
+            &(),
+            &SharedCountOnes {},
+            &CountOnesAnalyzer {},
+            None,
+        );
+        assert!(result.score >= 12.0);
+    }
 }
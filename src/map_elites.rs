@@ -0,0 +1,238 @@
+//! A MAP-Elites runner. Instead of a single population, the runner keeps
+//! a map of cells keyed by what each solution does, and every cell holds
+//! the best solution found with that behavior. The result is a whole
+//! collection of high quality solutions which are each different from
+//! one another, rather than a single winner.
+use crate::parallel::*;
+use crate::{
+    breeding::breed,
+    math::constraints::PenaltyWeight,
+    models::algen_result::AlgenResult,
+    models::algorithm::Algorithm,
+    models::analyzer::Analyzer,
+    models::behavior::BehaviorDescriptor,
+    models::map_elites::{MapElitesParameters, QualityDiversity},
+    models::node::Node,
+    models::statistics::GenerationStatistics,
+    models::test_parameters::TestParameters,
+    random::Seed,
+    scoring::{reward, score},
+    BREEDING, EVALUATION,
+};
+use rand::prelude::*;
+
+#[cfg(feature = "tracing")]
+use tracing::{event, Level};
+
+/// Run MAP-Elites. This accepts the same types and parameters as
+/// `run_algorithm`, plus the `map_elites` parameters which describe the
+/// feature map and a descriptor which maps each output to a behavior.
+///
+/// The map is seeded with `params.population` random nodes. Every
+/// generation after that, `params.population` offspring are bred from
+/// parents picked uniformly from the filled cells. Each node is placed
+/// in the cell for its behavior, replacing the elite there if it scores
+/// better.
+///
+/// Returns every elite in `AlgenResult::archive`, alongside the best
+/// scoring of them, and the coverage and QD-score of the map after each
+/// generation in `AlgenResult::quality_diversity`.
+pub fn run_map_elites<
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    map_elites: &MapElitesParameters,
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+    descriptor: &(impl BehaviorDescriptor<OutputData> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
+) -> AlgenResult<OutputData, Solution> {
+    params.reject_loop_only("run_map_elites", algo.telemetry_sink());
+    let params = &params.for_run();
+    params.install(|| {
+        let seed = Seed::new(params.seed);
+        let _stream = seed.enter();
+        let weight = PenaltyWeight::new(&params.constraint_handling).weight();
+        let mut best_score = f32::MIN;
+        let mut best_node: Option<Node<Solution>> = None;
+        let mut best_output: Option<OutputData> = None;
+
+        let mut map: Vec<Option<Node<Solution>>> = vec![None; map_elites.cells()];
+        let mut filled: Vec<usize> = Vec::new();
+        let mut quality_diversity: Vec<QualityDiversity> = Vec::new();
+        let mut history = Vec::new();
+
+        for generation in 0..params.generations {
+            params.state.set_generation(generation);
+            let streams = seed.derive(generation as u64);
+
+            let mut batch: Vec<Node<Solution>> = if filled.is_empty() {
+                (0..params.population)
+                    .map(|_| algo.allocate_node(input_data, params))
+                    .collect()
+            } else {
+                (0..params.population)
+                    .into_par_iter()
+                    .map(|idx| {
+                        let _stream = streams.derive(BREEDING).derive(idx as u64).enter();
+                        let mut rng = crate::random::rng();
+                        let left = *filled.choose(&mut rng).unwrap();
+                        let right = *filled.choose(&mut rng).unwrap();
+                        breed(
+                            algo,
+                            map[left].clone().unwrap(),
+                            map[right].clone().unwrap(),
+                            params,
+                        )
+                    })
+                    .collect()
+            };
+
+            let outputs = batch
+                .par_iter_mut()
+                .enumerate()
+                .map(|(idx, node)| {
+                    let _stream = streams.derive(EVALUATION).derive(idx as u64).enter();
+                    let output = algo.output(node, input_data, params);
+                    score(algo, analyzer, node, &output, params, weight);
+                    reward(algo, node);
+                    node.behavior = descriptor.describe(&output);
+                    output
+                })
+                .collect::<Vec<OutputData>>();
+            let scores: Vec<f32> = batch.iter().map(|node| node.score).collect();
+            history.push(GenerationStatistics::new(generation, &scores));
+
+            for (node, output) in batch.into_iter().zip(outputs) {
+                if node.score > best_score {
+                    best_score = node.score;
+                    best_node = Some(node.clone());
+                    best_output = Some(output);
+                }
+
+                let cell = map_elites.cell(&node.behavior);
+                match &map[cell] {
+                    Some(elite) if elite.score >= node.score => {}
+                    Some(_) => map[cell] = Some(node),
+                    None => {
+                        map[cell] = Some(node);
+                        filled.push(cell);
+                    }
+                }
+            }
+
+            let metrics = QualityDiversity {
+                coverage: filled.len() as f32 / map.len().max(1) as f32,
+                qd_score: filled
+                    .iter()
+                    .filter_map(|cell| map[*cell].as_ref())
+                    .map(|node| node.score)
+                    .sum(),
+            };
+            quality_diversity.push(metrics);
+
+            #[cfg(feature = "tracing")]
+            event!(
+                Level::INFO,
+                msg = "Generation finished processing",
+                generation = generation,
+                score = best_score,
+                coverage = metrics.coverage,
+                qd_score = metrics.qd_score,
+                diversity = descriptor.diversity(
+                    &filled
+                        .iter()
+                        .filter_map(|cell| map[*cell].as_ref())
+                        .map(|node| node.behavior.as_slice())
+                        .collect::<Vec<&[f32]>>()
+                )
+            );
+
+            if let (Some(func), Some(output), Some(node)) =
+                (on_generation_complete, &best_output, &best_node)
+            {
+                if func(best_score, &node.solution, output) {
+                    break;
+                }
+            }
+        }
+
+        AlgenResult {
+            score: best_score,
+            output: best_output,
+            node: best_node,
+            pareto_front: Vec::new(),
+            archive: map.into_iter().flatten().collect(),
+            quality_diversity,
+            hypervolume: Vec::new(),
+            history,
+            genealogy: Vec::new(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+    use crate::models::map_elites::{FeatureDimension, MapElitesParameters};
+
+    #[test]
+    fn behaviors_map_to_cells() {
+        let map_elites = MapElitesParameters {
+            dimensions: vec![
+                FeatureDimension {
+                    min: 0.0,
+                    max: 1.0,
+                    bins: 4,
+                },
+                FeatureDimension {
+                    min: -1.0,
+                    max: 1.0,
+                    bins: 2,
+                },
+            ],
+        };
+
+        assert_eq!(map_elites.cells(), 8);
+        assert_eq!(map_elites.cell(&[0.0, -1.0]), 0);
+        assert_eq!(map_elites.cell(&[0.3, 0.5]), 3);
+        assert_eq!(map_elites.cell(&[2.0, 2.0]), 7);
+    }
+
+    #[test]
+    fn map_elites_fills_the_map() {
+        let map_elites = MapElitesParameters {
+            dimensions: vec![FeatureDimension {
+                min: 0.0,
+                max: 16.0,
+                bins: 17,
+            }],
+        };
+        let descriptor =
+            |output: &Vec<bool>| vec![output.iter().filter(|bit| **bit).count() as f32];
+        let result = run_map_elites(
+            &parameters(),
+            &map_elites,
+            &(),
+            &CountOnes {},
+            &CountOnesAnalyzer {},
+            &descriptor,
+            None,
+        );
+        assert!(result.archive.len() >= 12);
+        assert!(result.score >= 12.0);
+
+        let metrics = result.quality_diversity.last().unwrap();
+        assert_eq!(metrics.coverage, result.archive.len() as f32 / 17.0);
+        assert_eq!(
+            metrics.qd_score,
+            result.archive.iter().map(|node| node.score).sum::<f32>()
+        );
+    }
+}
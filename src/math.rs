@@ -1,27 +1,29 @@
-use crate::models::{node::Node, test_parameters::TestParameters};
-use rand::prelude::*;
-
-/// A basic implementation of tournament selection.
-pub fn tournament_selection<Solution: Clone, FeatureFlags>(
-    nodes: &[Node<Solution>],
-    params: &TestParameters<FeatureFlags>,
-) -> Option<Node<Solution>> {
-    let mut rng = rand::thread_rng();
-    let mut best_node: Option<Node<Solution>> = None;
-    let mut best_score = f32::MIN;
-
-    for _ in 0..params.tournament_size {
-        let idx = rng.gen_range(0..nodes.len());
-        match nodes.get(idx) {
-            Some(node) => {
-                if node.score > best_score {
-                    best_node = Some(node.clone());
-                    best_score = node.score;
-                }
-            }
-            None => (),
-        }
-    }
-
-    return best_node;
-}
+pub mod accelerator;
+#[cfg(feature = "std")]
+pub mod behavior;
+pub mod columns;
+pub mod constraints;
+#[cfg(feature = "std")]
+pub mod elite_archive;
+#[cfg(feature = "std")]
+pub mod fitness_cache;
+#[cfg(not(feature = "std"))]
+pub mod float;
+#[cfg(feature = "std")]
+pub mod isolation;
+#[cfg(feature = "std")]
+pub mod local_search;
+#[cfg(feature = "std")]
+pub mod niching;
+#[cfg(feature = "std")]
+pub mod pareto;
+#[cfg(feature = "std")]
+pub mod queue;
+#[cfg(feature = "std")]
+pub mod replacement;
+#[cfg(feature = "std")]
+pub mod scaling;
+pub mod selection;
+#[cfg(feature = "std")]
+pub mod speciation;
+pub mod statistics;
@@ -0,0 +1,70 @@
+use crate::models::accelerator::Accelerator;
+use alloc::vec::Vec;
+
+/// Score `solutions` on an accelerator, writing the score of each to the
+/// same position in `scores`. The genomes are sent in as few batches as
+/// the device's capacity allows.
+pub fn offload<Solution>(
+    accelerator: &dyn Accelerator<Solution>,
+    solutions: &[&Solution],
+    scores: &mut [f32],
+) {
+    let capacity = accelerator.capacity();
+    let mut genomes = Vec::new();
+    let mut offsets = Vec::new();
+    let mut first = 0;
+    for (idx, solution) in solutions.iter().enumerate() {
+        let mut start = genomes.len();
+        accelerator.encode(solution, &mut genomes);
+        // A genome which overflows the batch starts the next one
+        if genomes.len() > capacity && !offsets.is_empty() {
+            accelerator.score(&genomes[..start], &offsets, &mut scores[first..idx]);
+            genomes.drain(..start);
+            offsets.clear();
+            first = idx;
+            start = 0;
+        }
+        offsets.push(start);
+    }
+    if !offsets.is_empty() {
+        accelerator.score(&genomes, &offsets, &mut scores[first..]);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    /// Scores each genome by its length, and remembers the batches.
+    struct Lengths(Mutex<Vec<usize>>);
+    impl Accelerator<Vec<u8>> for Lengths {
+        fn encode(&self, solution: &Vec<u8>, out: &mut Vec<u8>) {
+            out.extend_from_slice(solution);
+        }
+
+        fn capacity(&self) -> usize {
+            4
+        }
+
+        fn score(&self, genomes: &[u8], offsets: &[usize], scores: &mut [f32]) {
+            self.0.lock().unwrap().push(genomes.len());
+            let ends = offsets[1..].iter().copied().chain([genomes.len()]);
+            for ((start, end), score) in offsets.iter().zip(ends).zip(scores) {
+                *score = (end - start) as f32;
+            }
+        }
+    }
+
+    #[test]
+    fn generations_are_split_to_fit_the_device() {
+        let solutions = [vec![0; 2], vec![0; 1], vec![0; 3], vec![0; 6], vec![0; 1]];
+        let solutions: Vec<&Vec<u8>> = solutions.iter().collect();
+        let mut scores = vec![0.0; solutions.len()];
+        let accelerator = Lengths(Mutex::new(Vec::new()));
+        offload(&accelerator, &solutions, &mut scores);
+
+        assert_eq!(scores, vec![2.0, 1.0, 3.0, 6.0, 1.0]);
+        assert_eq!(accelerator.0.into_inner().unwrap(), vec![3, 3, 6, 1]);
+    }
+}
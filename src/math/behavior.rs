@@ -0,0 +1,56 @@
+use crate::models::behavior::BehaviorDescriptor;
+use crate::parallel::*;
+
+/// Compute the novelty of each behavior in the population. This is the
+/// mean distance to the `neighbors` nearest behaviors among the rest of
+/// the population and the archive.
+pub fn novelty_scores<OutputData>(
+    behaviors: &[&[f32]],
+    archive: &[Vec<f32>],
+    neighbors: usize,
+    descriptor: &(impl BehaviorDescriptor<OutputData> + Sync),
+) -> Vec<f32> {
+    behaviors
+        .par_iter()
+        .enumerate()
+        .map(|(idx, behavior)| {
+            let mut distances: Vec<f32> = behaviors
+                .iter()
+                .enumerate()
+                .filter(|(other, _)| *other != idx)
+                .map(|(_, other)| *other)
+                .chain(archive.iter().map(Vec::as_slice))
+                .map(|other| descriptor.distance(behavior, other))
+                .collect();
+
+            let k = neighbors.max(1).min(distances.len());
+            if k == 0 {
+                return 0.0;
+            }
+
+            distances.select_nth_unstable_by(k - 1, |left, right| left.total_cmp(right));
+            distances[..k].iter().sum::<f32>() / k as f32
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn isolated_behaviors_are_most_novel() {
+        let behaviors: Vec<&[f32]> = vec![&[0.0], &[1.0], &[10.0]];
+        let scores = novelty_scores(&behaviors, &[], 1, &|output: &Vec<f32>| output.clone());
+        assert_eq!(scores, vec![1.0, 1.0, 9.0]);
+    }
+
+    #[test]
+    fn archive_counts_as_a_neighbor() {
+        let behaviors: Vec<&[f32]> = vec![&[0.0], &[10.0]];
+        let scores = novelty_scores(&behaviors, &[vec![9.0]], 1, &|output: &Vec<f32>| {
+            output.clone()
+        });
+        assert_eq!(scores, vec![9.0, 1.0]);
+    }
+}
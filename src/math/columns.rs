@@ -0,0 +1,133 @@
+use super::constraints::compare;
+use crate::models::{
+    constraint::ConstraintHandling, layout::PopulationLayout, node::Node,
+    test_parameters::TestParameters,
+};
+use crate::parallel::*;
+use alloc::vec::Vec;
+use core::cmp::Ordering;
+
+/// The scores and violations of a population, one column each, in the
+/// same order as the nodes they were taken from.
+pub struct ScoreColumns {
+    scores: Vec<f32>,
+    violations: Vec<f32>,
+    feasibility_first: bool,
+}
+
+impl ScoreColumns {
+    pub fn new<Solution>(nodes: &[Node<Solution>], handling: &ConstraintHandling) -> Self {
+        ScoreColumns {
+            scores: nodes.iter().map(|node| node.score).collect(),
+            violations: nodes.iter().map(|node| node.violation.max(0.0)).collect(),
+            feasibility_first: matches!(handling, ConstraintHandling::FeasibilityFirst),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.scores.len()
+    }
+
+    /// Compare two rows the same way `constraints::compare` compares
+    /// nodes, where `Greater` means the left row is better.
+    pub fn compare(&self, left: usize, right: usize) -> Ordering {
+        let scores = || self.scores[left].total_cmp(&self.scores[right]);
+        if self.feasibility_first {
+            self.violations[right]
+                .total_cmp(&self.violations[left])
+                .then_with(scores)
+        } else {
+            scores()
+        }
+    }
+}
+
+/// Compares nodes by index, where `Greater` means the left node is
+/// better, reading from score columns when the layout calls for them.
+pub fn ranking<'a, Solution: Sync, FeatureFlags>(
+    nodes: &'a [Node<Solution>],
+    params: &'a TestParameters<FeatureFlags>,
+) -> impl Fn(usize, usize) -> Ordering + Sync + 'a {
+    let handling = &params.constraint_handling;
+    let columns =
+        (params.layout == PopulationLayout::Columns).then(|| ScoreColumns::new(nodes, handling));
+    move |left, right| match &columns {
+        Some(columns) => columns.compare(left, right),
+        None => compare(&nodes[left], &nodes[right], handling),
+    }
+}
+
+/// Move the `count` best indices to the front, best first, and leave the
+/// rest in no particular order. This partitions rather than sorts, so it
+/// is much cheaper than ranking everything when `count` is small, and
+/// the best are sorted in parallel for when it isn't.
+pub fn select_best(
+    indices: &mut [usize],
+    count: usize,
+    better: impl Fn(usize, usize) -> Ordering + Sync,
+) {
+    let count = count.min(indices.len());
+    if count == 0 {
+        return;
+    }
+
+    let best_first = |left: &usize, right: &usize| better(*right, *left);
+    if count < indices.len() {
+        indices.select_nth_unstable_by(count - 1, best_first);
+    }
+    indices[..count].par_sort_by(best_first);
+}
+
+/// Move the items into the given order, where `order[i]` is the index
+/// of the item which ends up at `i`. Nothing is cloned.
+pub fn permute<T>(items: &mut Vec<T>, order: &[usize]) {
+    let mut slots: Vec<Option<T>> = items.drain(..).map(Some).collect();
+    items.extend(order.iter().filter_map(|idx| slots[*idx].take()));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn columns_rank_like_nodes() {
+        let nodes: Vec<Node<()>> = [(1.0, 0.0), (5.0, 2.0), (3.0, 0.0)]
+            .into_iter()
+            .map(|(score, violation)| Node {
+                score,
+                violation,
+                ..Node::new(())
+            })
+            .collect();
+
+        let rank = |handling| {
+            let columns = ScoreColumns::new(&nodes, &handling);
+            let mut order: Vec<usize> = (0..columns.len()).collect();
+            select_best(&mut order, columns.len(), |left, right| {
+                columns.compare(left, right)
+            });
+            order
+        };
+        assert_eq!(rank(ConstraintHandling::None), vec![1, 2, 0]);
+
+        let order = rank(ConstraintHandling::FeasibilityFirst);
+        assert_eq!(order, vec![2, 0, 1]);
+
+        let mut names = vec!["a", "b", "c"];
+        permute(&mut names, &order);
+        assert_eq!(names, vec!["c", "a", "b"]);
+    }
+
+    #[test]
+    fn only_the_best_are_ordered() {
+        let scores = [4.0f32, 9.0, 1.0, 7.0, 3.0, 8.0];
+        let better = |left: usize, right: usize| scores[left].total_cmp(&scores[right]);
+        let mut indices: Vec<usize> = (0..scores.len()).collect();
+        select_best(&mut indices, 3, better);
+        assert_eq!(indices[..3], [1, 5, 3]);
+
+        // The worst are the best by the opposite measure
+        select_best(&mut indices[3..], 2, |left, right| better(right, left));
+        assert_eq!(indices[3..5], [2, 4]);
+    }
+}
@@ -0,0 +1,164 @@
+use crate::models::{constraint::ConstraintHandling, node::Node};
+use alloc::collections::VecDeque;
+use core::cmp::Ordering;
+
+/// Compare two nodes, where `Greater` means the left node is better.
+/// This is a plain score comparison unless the feasibility first rules
+/// are in use.
+pub fn compare<Solution>(
+    left: &Node<Solution>,
+    right: &Node<Solution>,
+    handling: &ConstraintHandling,
+) -> Ordering {
+    match handling {
+        ConstraintHandling::FeasibilityFirst => right
+            .violation
+            .max(0.0)
+            .total_cmp(&left.violation.max(0.0))
+            .then_with(|| left.score.total_cmp(&right.score)),
+        _ => left.score.total_cmp(&right.score),
+    }
+}
+
+/// Whichever of two nodes is better, along with whatever came with it.
+/// Ties go to the left node.
+pub fn better<'a, T, Solution>(
+    left: (T, &'a Node<Solution>),
+    right: (T, &'a Node<Solution>),
+    handling: &ConstraintHandling,
+) -> (T, &'a Node<Solution>) {
+    if compare(right.1, left.1, handling).is_gt() {
+        right
+    } else {
+        left
+    }
+}
+
+/// Whether `node` should take over from the best node found so far,
+/// which is `best` with a score of `best_score`.
+pub fn improves<Solution>(
+    node: &Node<Solution>,
+    best: &Option<Node<Solution>>,
+    best_score: f32,
+    handling: &ConstraintHandling,
+) -> bool {
+    match best {
+        Some(best) => compare(node, best, handling).is_gt(),
+        None => node.score > best_score,
+    }
+}
+
+/// Apply the penalty for the node's violation to its score.
+pub fn penalize<Solution>(node: &mut Node<Solution>, handling: &ConstraintHandling, weight: f32) {
+    if node.violation <= 0.0 {
+        return;
+    }
+
+    match handling {
+        ConstraintHandling::StaticPenalty { .. } | ConstraintHandling::AdaptivePenalty { .. } => {
+            node.score -= weight * node.violation;
+        }
+        ConstraintHandling::DeathPenalty => node.score = f32::MIN,
+        ConstraintHandling::None | ConstraintHandling::FeasibilityFirst => {}
+    }
+}
+
+/// The state of the penalty weight across generations.
+pub struct PenaltyWeight {
+    weight: f32,
+    history: VecDeque<bool>,
+}
+
+impl PenaltyWeight {
+    pub fn new(handling: &ConstraintHandling) -> Self {
+        let weight = match handling {
+            ConstraintHandling::StaticPenalty { weight } => *weight,
+            ConstraintHandling::AdaptivePenalty { initial_weight, .. } => *initial_weight,
+            _ => 0.0,
+        };
+
+        PenaltyWeight {
+            weight,
+            history: VecDeque::new(),
+        }
+    }
+
+    /// The weight to use for the current generation.
+    pub fn weight(&self) -> f32 {
+        self.weight
+    }
+
+    /// Record whether the best node of a generation was feasible, and
+    /// adapt the weight if the strategy calls for it.
+    pub fn record(&mut self, handling: &ConstraintHandling, best_feasible: bool) {
+        if let ConstraintHandling::AdaptivePenalty {
+            increase,
+            decrease,
+            window,
+            ..
+        } = handling
+        {
+            self.history.push_back(best_feasible);
+            while self.history.len() > *window {
+                self.history.pop_front();
+            }
+
+            if self.history.len() == *window {
+                if self.history.iter().all(|feasible| *feasible) {
+                    self.weight /= decrease;
+                } else if self.history.iter().all(|feasible| !*feasible) {
+                    self.weight *= increase;
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(score: f32, violation: f32) -> Node<()> {
+        Node {
+            score,
+            violation,
+            ..Node::new(())
+        }
+    }
+
+    #[test]
+    fn feasibility_first_prefers_feasible_nodes() {
+        let handling = ConstraintHandling::FeasibilityFirst;
+
+        assert_eq!(
+            compare(&node(1.0, 0.0), &node(9.0, 1.0), &handling),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare(&node(1.0, 1.0), &node(9.0, 2.0), &handling),
+            Ordering::Greater
+        );
+        assert_eq!(
+            compare(&node(1.0, 0.0), &node(9.0, 0.0), &handling),
+            Ordering::Less
+        );
+    }
+
+    #[test]
+    fn adaptive_weight_grows_while_infeasible() {
+        let handling = ConstraintHandling::AdaptivePenalty {
+            initial_weight: 1.0,
+            increase: 2.0,
+            decrease: 2.0,
+            window: 2,
+        };
+        let mut weight = PenaltyWeight::new(&handling);
+
+        weight.record(&handling, false);
+        assert_eq!(weight.weight(), 1.0);
+        weight.record(&handling, false);
+        assert_eq!(weight.weight(), 2.0);
+        weight.record(&handling, true);
+        assert_eq!(weight.weight(), 2.0);
+    }
+}
@@ -0,0 +1,64 @@
+use crate::{
+    math::constraints::compare,
+    models::{constraint::ConstraintHandling, node::Node},
+};
+
+/// Offer scored nodes to an archive of the best distinct nodes seen so
+/// far, which is kept sorted best first and no longer than `size`.
+pub fn archive_elites<'a, Solution: Clone + 'a>(
+    archive: &mut Vec<Node<Solution>>,
+    nodes: impl IntoIterator<Item = &'a Node<Solution>>,
+    size: usize,
+    handling: &ConstraintHandling,
+    fingerprint: impl Fn(&Solution) -> Option<u64>,
+) {
+    for node in nodes {
+        let worst = archive.last();
+        if archive.len() >= size && worst.is_none_or(|worst| compare(node, worst, handling).is_le())
+        {
+            continue;
+        }
+
+        let print = fingerprint(&node.solution);
+        let duplicate = archive.iter().any(|elite| match print {
+            Some(print) => fingerprint(&elite.solution) == Some(print),
+            None => elite.score == node.score,
+        });
+        if duplicate {
+            continue;
+        }
+
+        let at = archive.partition_point(|elite| compare(elite, node, handling).is_ge());
+        archive.insert(at, node.clone());
+        archive.truncate(size);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn archive_keeps_the_best_distinct_nodes() {
+        let nodes: Vec<Node<u8>> = [3, 1, 4, 1, 5, 9, 2, 6]
+            .into_iter()
+            .map(|value| {
+                let mut node = Node::new(value);
+                node.score = value as f32;
+                node
+            })
+            .collect();
+
+        let mut archive = Vec::new();
+        let handling = ConstraintHandling::default();
+        archive_elites(&mut archive, &nodes, 3, &handling, |value| {
+            Some(*value as u64)
+        });
+        archive_elites(&mut archive, &nodes, 3, &handling, |value| {
+            Some(*value as u64)
+        });
+
+        let solutions: Vec<u8> = archive.iter().map(|node| node.solution).collect();
+        assert_eq!(solutions, vec![9, 6, 5]);
+    }
+}
@@ -0,0 +1,94 @@
+use std::collections::{BTreeMap, HashMap};
+
+/// What the analyzer had to say about a genome.
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedFitness {
+    pub score: f32,
+    pub case_scores: Vec<f32>,
+    pub violation: f32,
+}
+
+/// A least recently used cache of evaluations, keyed by the fingerprint
+/// of the genome which was evaluated.
+pub struct FitnessCache {
+    capacity: usize,
+    tick: u64,
+    entries: HashMap<u64, (CachedFitness, u64)>,
+    /// Every fingerprint by the tick it was last used, oldest first.
+    recency: BTreeMap<u64, u64>,
+}
+
+impl FitnessCache {
+    pub fn new(capacity: usize) -> Self {
+        FitnessCache {
+            capacity,
+            tick: 0,
+            entries: HashMap::new(),
+            recency: BTreeMap::new(),
+        }
+    }
+
+    /// The evaluation of the genome with this fingerprint, if it is
+    /// remembered.
+    pub fn get(&mut self, print: u64) -> Option<CachedFitness> {
+        self.tick += 1;
+        let (fitness, used) = self.entries.get_mut(&print)?;
+        self.recency.remove(used);
+        *used = self.tick;
+        self.recency.insert(self.tick, print);
+        Some(fitness.clone())
+    }
+
+    /// Forget every evaluation, such as when the input they were made on
+    /// has changed.
+    pub fn clear(&mut self) {
+        self.entries.clear();
+        self.recency.clear();
+    }
+
+    /// Remember an evaluation, forgetting the least recently used one if
+    /// the cache is full.
+    pub fn insert(&mut self, print: u64, fitness: CachedFitness) {
+        if self.capacity == 0 {
+            return;
+        }
+        self.tick += 1;
+        if let Some((_, used)) = self.entries.insert(print, (fitness, self.tick)) {
+            self.recency.remove(&used);
+        }
+        self.recency.insert(self.tick, print);
+
+        while self.entries.len() > self.capacity {
+            let Some((_, oldest)) = self.recency.pop_first() else {
+                break;
+            };
+            self.entries.remove(&oldest);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn fitness(score: f32) -> CachedFitness {
+        CachedFitness {
+            score,
+            case_scores: Vec::new(),
+            violation: 0.0,
+        }
+    }
+
+    #[test]
+    fn least_recently_used_is_forgotten() {
+        let mut cache = FitnessCache::new(2);
+        cache.insert(1, fitness(1.0));
+        cache.insert(2, fitness(2.0));
+        assert_eq!(cache.get(1), Some(fitness(1.0)));
+
+        cache.insert(3, fitness(3.0));
+        assert_eq!(cache.get(2), None);
+        assert_eq!(cache.get(1), Some(fitness(1.0)));
+        assert_eq!(cache.get(3), Some(fitness(3.0)));
+    }
+}
@@ -0,0 +1,204 @@
+//! The float functions which std provides and core does not. Without the
+//! `std` feature there is no libm to call, so these are computed here, in
+//! `f64`, to well within the precision a fitness score needs.
+
+use core::f64::consts::LN_2;
+
+/// The methods of `f32` and `f64` which only std provides. Modules which
+/// call them import this when built without `std`, and keep calling them
+/// as methods. Tests always link std, whose methods take precedence, so
+/// the import is left out of test builds.
+pub trait Float: Copy {
+    fn floor(self) -> Self;
+    fn ceil(self) -> Self;
+    fn round(self) -> Self;
+    fn powi(self, n: i32) -> Self;
+    fn sqrt(self) -> Self;
+    fn exp(self) -> Self;
+    fn ln(self) -> Self;
+    fn log2(self) -> Self;
+}
+
+impl Float for f64 {
+    fn floor(self) -> Self {
+        // Beyond 2^52 every float is already a whole number
+        if self.is_nan() || self.abs() >= 4_503_599_627_370_496.0 {
+            return self;
+        }
+        let truncated = self as i64 as f64;
+        if truncated > self {
+            truncated - 1.0
+        } else {
+            truncated
+        }
+    }
+
+    fn ceil(self) -> Self {
+        -(-self).floor()
+    }
+
+    fn round(self) -> Self {
+        if self < 0.0 {
+            -(-self).round()
+        } else {
+            let floor = self.floor();
+            if self - floor >= 0.5 {
+                floor + 1.0
+            } else {
+                floor
+            }
+        }
+    }
+
+    fn powi(self, n: i32) -> Self {
+        let mut base = self;
+        let mut exponent = n.unsigned_abs();
+        let mut result = 1.0;
+        while exponent > 0 {
+            if exponent & 1 == 1 {
+                result *= base;
+            }
+            base *= base;
+            exponent >>= 1;
+        }
+        if n < 0 {
+            1.0 / result
+        } else {
+            result
+        }
+    }
+
+    fn sqrt(self) -> Self {
+        if self.is_nan() || self < 0.0 {
+            return f64::NAN;
+        }
+        if self == 0.0 || self.is_infinite() {
+            return self;
+        }
+        // Halving the exponent gets within a factor of two, and each
+        // Newton step then doubles the number of correct bits
+        let mut root = f64::from_bits((self.to_bits() >> 1) + (1023 << 51));
+        for _ in 0..8 {
+            root = 0.5 * (root + self / root);
+        }
+        root
+    }
+
+    fn exp(self) -> Self {
+        if self.is_nan() {
+            return self;
+        }
+        if self > 709.8 {
+            return f64::INFINITY;
+        }
+        if self < -745.2 {
+            return 0.0;
+        }
+        // e^x = 2^k * e^r with |r| <= ln(2) / 2, where the series for e^r
+        // converges quickly
+        let k = (self / LN_2).round();
+        let r = self - k * LN_2;
+        let mut term = 1.0;
+        let mut sum = 1.0;
+        for n in 1..16 {
+            term *= r / n as f64;
+            sum += term;
+        }
+        sum * 2.0f64.powi(k as i32)
+    }
+
+    fn ln(self) -> Self {
+        if self.is_nan() || self < 0.0 {
+            return f64::NAN;
+        }
+        if self == 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        if self.is_infinite() {
+            return self;
+        }
+        // ln(x) = ln(m) + e * ln(2) with m in [1, 2), and ln(m) from the
+        // series for 2 * atanh((m - 1) / (m + 1))
+        let (value, offset) = if self < f64::MIN_POSITIVE {
+            (self * 2.0f64.powi(54), -54)
+        } else {
+            (self, 0)
+        };
+        let bits = value.to_bits();
+        let exponent = ((bits >> 52) & 0x7ff) as i32 - 1023 + offset;
+        let mantissa = f64::from_bits((bits & 0x000f_ffff_ffff_ffff) | (1023 << 52));
+        let s = (mantissa - 1.0) / (mantissa + 1.0);
+        let mut term = s;
+        let mut sum = 0.0;
+        for n in 0..20 {
+            sum += term / (2 * n + 1) as f64;
+            term *= s * s;
+        }
+        2.0 * sum + exponent as f64 * LN_2
+    }
+
+    fn log2(self) -> Self {
+        self.ln() / LN_2
+    }
+}
+
+impl Float for f32 {
+    fn floor(self) -> Self {
+        (self as f64).floor() as f32
+    }
+
+    fn ceil(self) -> Self {
+        (self as f64).ceil() as f32
+    }
+
+    fn round(self) -> Self {
+        (self as f64).round() as f32
+    }
+
+    fn powi(self, n: i32) -> Self {
+        (self as f64).powi(n) as f32
+    }
+
+    fn sqrt(self) -> Self {
+        (self as f64).sqrt() as f32
+    }
+
+    fn exp(self) -> Self {
+        (self as f64).exp() as f32
+    }
+
+    fn ln(self) -> Self {
+        (self as f64).ln() as f32
+    }
+
+    fn log2(self) -> Self {
+        (self as f64).log2() as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn functions_match_std() {
+        let close = |actual: f64, expected: f64| {
+            assert!(
+                (actual - expected).abs() <= 1e-12 * expected.abs().max(1.0),
+                "{actual} != {expected}"
+            );
+        };
+        for value in [1e-310, 1e-5, 0.3, 0.5, 1.0, 2.5, 7.0, 1234.5678, 1e300] {
+            close(Float::sqrt(value), value.sqrt());
+            close(Float::ln(value), value.ln());
+            close(Float::log2(value), value.log2());
+        }
+        for value in [-40.0, -2.5, -0.5, 0.0, 0.4999, 0.5, 1.5, 3.7, 80.0] {
+            close(Float::floor(value), value.floor());
+            close(Float::ceil(value), value.ceil());
+            close(Float::round(value), value.round());
+            close(Float::exp(value), value.exp());
+            close(Float::powi(value, 3), value.powi(3));
+        }
+    }
+}
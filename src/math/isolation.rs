@@ -0,0 +1,33 @@
+use crate::{clock::Instant, deadline, models::test_parameters::TestParameters};
+use std::panic::{catch_unwind, AssertUnwindSafe};
+
+/// Run user code, and when `enabled`, turn a panic into `None` rather
+/// than letting it unwind through the runner. Whatever the code was
+/// working on is thrown away with the panic, so nothing it leaves half
+/// done is looked at again.
+pub fn isolate<R>(enabled: bool, op: impl FnOnce() -> R) -> Option<R> {
+    if !enabled {
+        return Some(op());
+    }
+    catch_unwind(AssertUnwindSafe(op)).ok()
+}
+
+/// Run part of a node's evaluation within the run's time limit, if it
+/// has one, and isolated when panics are penalized. An evaluation which
+/// panics or runs over time gives back the score the node is penalized
+/// with instead of its result.
+pub fn guard<R, FeatureFlags>(
+    params: &TestParameters<FeatureFlags>,
+    op: impl FnOnce() -> R,
+) -> Result<R, f32> {
+    let started = Instant::now();
+    let _deadline = params
+        .evaluation_timeout
+        .map(|timeout| deadline::start(timeout.limit));
+    let result = isolate(params.panic_penalty.is_some(), op)
+        .ok_or(params.panic_penalty.unwrap_or(f32::MIN))?;
+    match params.evaluation_timeout {
+        Some(timeout) if started.elapsed() > timeout.limit => Err(timeout.penalty),
+        _ => Ok(result),
+    }
+}
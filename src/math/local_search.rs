@@ -0,0 +1,36 @@
+use crate::models::local_search::LocalSearch;
+
+/// First-improvement hill climbing. A neighbor replaces the current
+/// solution whenever it scores better, and the best solution found is
+/// returned along with its score.
+pub fn hill_climb<Solution>(
+    solution: Solution,
+    score: f32,
+    steps: usize,
+    search: &(dyn LocalSearch<Solution> + Sync),
+    evaluate: impl Fn(&Solution) -> f32,
+) -> (Solution, f32) {
+    let (mut best, mut best_score) = (solution, score);
+    for _ in 0..steps {
+        let neighbor = search.neighbor(&best);
+        let neighbor_score = evaluate(&neighbor);
+        if neighbor_score > best_score {
+            best = neighbor;
+            best_score = neighbor_score;
+        }
+    }
+
+    (best, best_score)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hill_climbing_only_accepts_improvements() {
+        let step = |x: &i32| x + 1;
+        let (best, score) = hill_climb(0, -4.0, 10, &step, |x| -((x - 4) as f32).abs());
+        assert_eq!((best, score), (4, 0.0));
+    }
+}
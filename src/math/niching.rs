@@ -0,0 +1,54 @@
+use crate::models::{distance::Distance, niching::FitnessSharing, node::Node};
+use crate::parallel::*;
+
+/// Derate every node's score by its niche count.
+pub fn apply_fitness_sharing<Solution: Send + Sync>(
+    nodes: &mut [Node<Solution>],
+    distance: &(dyn Distance<Solution> + Sync),
+    sharing: &FitnessSharing,
+) {
+    let niche_counts = nodes
+        .par_iter()
+        .map(|node| {
+            nodes
+                .iter()
+                .map(|other| {
+                    let d = distance.distance(&node.solution, &other.solution);
+                    if d < sharing.radius {
+                        1.0 - (d / sharing.radius).powf(sharing.alpha)
+                    } else {
+                        0.0
+                    }
+                })
+                .sum::<f32>()
+        })
+        .collect::<Vec<f32>>();
+
+    for (node, niche_count) in nodes.iter_mut().zip(niche_counts) {
+        // A node is always in its own niche, so the count is at least 1.
+        node.score /= niche_count.max(1.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crowded_nodes_share_fitness() {
+        let mut nodes: Vec<Node<f32>> = [0.0, 0.1, 5.0]
+            .iter()
+            .map(|x| Node {
+                score: 1.0,
+                ..Node::new(*x)
+            })
+            .collect();
+        let distance = |l: &f32, r: &f32| (l - r).abs();
+
+        apply_fitness_sharing(&mut nodes, &distance, &FitnessSharing::default());
+
+        assert!(nodes[0].score < 1.0);
+        assert!(nodes[1].score < 1.0);
+        assert_eq!(nodes[2].score, 1.0);
+    }
+}
@@ -0,0 +1,545 @@
+use crate::models::node::Node;
+use std::cmp::Ordering;
+
+/// Constrained domination. A feasible node always dominates an
+/// infeasible one, between two infeasible nodes the one with the smaller
+/// violation dominates, and between two feasible nodes plain Pareto
+/// dominance is used.
+pub fn constrained_dominates<Solution>(left: &Node<Solution>, right: &Node<Solution>) -> bool {
+    match (left.violation > 0.0, right.violation > 0.0) {
+        (false, true) => true,
+        (true, false) => false,
+        (true, true) => left.violation < right.violation,
+        (false, false) => left.dominates(right),
+    }
+}
+
+/// Compare two nodes lexicographically, where `Greater` means the left
+/// node is better. Feasibility comes first, then the objectives are
+/// compared in priority order.
+pub fn lexicographic_cmp<Solution>(left: &Node<Solution>, right: &Node<Solution>) -> Ordering {
+    right
+        .violation
+        .max(0.0)
+        .total_cmp(&left.violation.max(0.0))
+        .then_with(|| {
+            left.objectives
+                .iter()
+                .zip(right.objectives.iter())
+                .map(|(l, r)| l.total_cmp(r))
+                .find(|ordering| ordering.is_ne())
+                .unwrap_or(Ordering::Equal)
+        })
+}
+
+/// Fast non-dominated sorting. Returns the indices of the nodes grouped
+/// into fronts, where the first front is the non-dominated set, the
+/// second front is only dominated by the first, and so on.
+pub fn non_dominated_sort<Solution>(nodes: &[Node<Solution>]) -> Vec<Vec<usize>> {
+    let mut dominated_by: Vec<usize> = vec![0; nodes.len()];
+    let mut dominates: Vec<Vec<usize>> = vec![Vec::new(); nodes.len()];
+    let mut fronts: Vec<Vec<usize>> = vec![Vec::new()];
+
+    for left in 0..nodes.len() {
+        for right in (left + 1)..nodes.len() {
+            if constrained_dominates(&nodes[left], &nodes[right]) {
+                dominates[left].push(right);
+                dominated_by[right] += 1;
+            } else if constrained_dominates(&nodes[right], &nodes[left]) {
+                dominates[right].push(left);
+                dominated_by[left] += 1;
+            }
+        }
+    }
+
+    for (idx, count) in dominated_by.iter().enumerate() {
+        if *count == 0 {
+            fronts[0].push(idx);
+        }
+    }
+
+    let mut current = 0;
+    while !fronts[current].is_empty() {
+        let mut next = Vec::new();
+        for idx in fronts[current].iter() {
+            for dominated in dominates[*idx].iter() {
+                dominated_by[*dominated] -= 1;
+                if dominated_by[*dominated] == 0 {
+                    next.push(*dominated);
+                }
+            }
+        }
+
+        fronts.push(next);
+        current += 1;
+    }
+
+    fronts.pop();
+    fronts
+}
+
+/// Compute the crowding distance of every node in a front. The result is
+/// aligned with the `front` slice. Boundary nodes get an infinite
+/// distance so they are always preferred.
+pub fn crowding_distance<Solution>(nodes: &[Node<Solution>], front: &[usize]) -> Vec<f32> {
+    let mut distances = vec![0.0; front.len()];
+    if front.len() <= 2 {
+        return vec![f32::INFINITY; front.len()];
+    }
+
+    let objectives = nodes[front[0]].objectives.len();
+    let mut order: Vec<usize> = (0..front.len()).collect();
+    for objective in 0..objectives {
+        let value = |position: usize| nodes[front[position]].objectives[objective];
+        order.sort_by(|left, right| value(*left).total_cmp(&value(*right)));
+
+        let (first, last) = (order[0], order[order.len() - 1]);
+        distances[first] = f32::INFINITY;
+        distances[last] = f32::INFINITY;
+
+        let range = value(last) - value(first);
+        if range <= 0.0 {
+            continue;
+        }
+
+        for window in order.windows(3) {
+            distances[window[1]] += (value(window[2]) - value(window[0])) / range;
+        }
+    }
+
+    distances
+}
+
+/// The Euclidean distance between two nodes in objective space.
+pub fn objective_distance<Solution>(left: &Node<Solution>, right: &Node<Solution>) -> f32 {
+    left.objectives
+        .iter()
+        .zip(right.objectives.iter())
+        .map(|(l, r)| (l - r) * (l - r))
+        .sum::<f32>()
+        .sqrt()
+}
+
+/// SPEA2 fitness assignment, where lower is better. Each node's raw
+/// fitness is the sum of the strengths (how many nodes they dominate) of
+/// every node which dominates it, so non-dominated nodes have a raw
+/// fitness of zero. A density term based on the distance to the k-th
+/// nearest neighbor, which is always below 1, breaks ties.
+pub fn strength_fitness<Solution>(nodes: &[Node<Solution>]) -> Vec<f32> {
+    let count = nodes.len();
+    let mut strength = vec![0.0; count];
+    let mut dominators: Vec<Vec<usize>> = vec![Vec::new(); count];
+    for left in 0..count {
+        for right in 0..count {
+            if left != right && constrained_dominates(&nodes[left], &nodes[right]) {
+                strength[left] += 1.0;
+                dominators[right].push(left);
+            }
+        }
+    }
+
+    let k = (count as f32).sqrt() as usize;
+    (0..count)
+        .map(|idx| {
+            let raw: f32 = dominators[idx].iter().map(|other| strength[*other]).sum();
+            let mut distances: Vec<f32> = (0..count)
+                .filter(|other| *other != idx)
+                .map(|other| objective_distance(&nodes[idx], &nodes[other]))
+                .collect();
+            distances.sort_by(|left, right| left.total_cmp(right));
+            // The node itself is left out, so the k-th nearest is at k - 1
+            let sigma = distances
+                .get(k.saturating_sub(1).min(distances.len().saturating_sub(1)))
+                .copied()
+                .unwrap_or(0.0);
+            raw + 1.0 / (sigma + 2.0)
+        })
+        .collect()
+}
+
+/// SPEA2 archive truncation. Repeatedly removes the node which is
+/// closest to its neighbors (comparing the distance to the nearest
+/// neighbor, then the second nearest, and so on) until only `size`
+/// of the given indices remain.
+pub fn truncate_archive<Solution>(
+    nodes: &[Node<Solution>],
+    mut members: Vec<usize>,
+    size: usize,
+) -> Vec<usize> {
+    let matrix: Vec<Vec<f32>> = members
+        .iter()
+        .map(|left| {
+            members
+                .iter()
+                .map(|right| objective_distance(&nodes[*left], &nodes[*right]))
+                .collect()
+        })
+        .collect();
+    let mut alive: Vec<usize> = (0..members.len()).collect();
+
+    while alive.len() > size {
+        let sorted_distances = |left: usize| {
+            let mut distances: Vec<f32> = alive
+                .iter()
+                .filter(|right| **right != left)
+                .map(|right| matrix[left][*right])
+                .collect();
+            distances.sort_by(|left, right| left.total_cmp(right));
+            distances
+        };
+
+        // Only nodes tied on their nearest neighbor need the full comparison
+        let nearest: Vec<f32> = alive
+            .iter()
+            .map(|left| {
+                alive
+                    .iter()
+                    .filter(|right| *right != left)
+                    .map(|right| matrix[*left][*right])
+                    .fold(f32::INFINITY, f32::min)
+            })
+            .collect();
+        let closest = nearest.iter().copied().fold(f32::INFINITY, f32::min);
+        let tied: Vec<usize> = (0..alive.len())
+            .filter(|position| nearest[*position] == closest)
+            .collect();
+
+        let most_crowded = if tied.len() == 1 {
+            tied[0]
+        } else {
+            let distances: Vec<Vec<f32>> = tied
+                .iter()
+                .map(|position| sorted_distances(alive[*position]))
+                .collect();
+            let winner = (0..tied.len())
+                .min_by(|left, right| {
+                    let (left, right) = (&distances[*left], &distances[*right]);
+                    left.iter()
+                        .zip(right.iter())
+                        .map(|(l, r)| l.total_cmp(r))
+                        .find(|ordering| ordering.is_ne())
+                        .unwrap_or(Ordering::Equal)
+                })
+                .unwrap();
+            tied[winner]
+        };
+        alive.remove(most_crowded);
+    }
+
+    let mut position = 0;
+    members.retain(|_| {
+        let keep = alive.contains(&position);
+        position += 1;
+        keep
+    });
+    members
+}
+
+/// Offer a node to an epsilon-dominance archive. Objective space is
+/// divided into boxes of size `epsilon`, and the archive holds at most
+/// one node per box, only for boxes that no other box dominates. When
+/// two nodes share a box, the dominating one is kept, or failing that,
+/// the one closest to the best corner of the box. Infeasible nodes are
+/// never archived.
+pub fn epsilon_archive_insert<Solution>(
+    archive: &mut Vec<Node<Solution>>,
+    node: Node<Solution>,
+    epsilon: f32,
+) {
+    if node.violation > 0.0 || epsilon <= 0.0 {
+        return;
+    }
+
+    let boxes = |node: &Node<Solution>| -> Vec<f32> {
+        node.objectives
+            .iter()
+            .map(|value| (value / epsilon).floor())
+            .collect()
+    };
+    let box_dominates =
+        |left: &[f32], right: &[f32]| left.iter().zip(right).all(|(l, r)| l >= r) && left != right;
+    let corner_distance = |node: &Node<Solution>, cell: &[f32]| {
+        node.objectives
+            .iter()
+            .zip(cell)
+            .map(|(value, cell)| {
+                let gap = (cell + 1.0) * epsilon - value;
+                gap * gap
+            })
+            .sum::<f32>()
+    };
+
+    let cell = boxes(&node);
+    let mut replaces = None;
+    for (idx, member) in archive.iter().enumerate() {
+        let member_cell = boxes(member);
+        if box_dominates(&member_cell, &cell) {
+            return;
+        }
+        if member_cell == cell {
+            let better = node.dominates(member)
+                || (!member.dominates(&node)
+                    && corner_distance(&node, &cell) < corner_distance(member, &cell));
+            if !better {
+                return;
+            }
+            replaces = Some(idx);
+        }
+    }
+
+    match replaces {
+        Some(idx) => archive[idx] = node,
+        None => {
+            archive.retain(|member| !box_dominates(&cell, &boxes(member)));
+            archive.push(node);
+        }
+    }
+}
+
+/// The hypervolume of a set of objective vectors, which is the volume
+/// of objective space they dominate, bounded by a reference point that
+/// every objective should improve upon. Points which do not beat the
+/// reference point on every objective contribute nothing. This slices
+/// along the last objective and recurses, so it is exact but best kept
+/// to a handful of objectives.
+pub fn hypervolume(points: &[Vec<f32>], reference: &[f32]) -> f32 {
+    let dimensions = reference.len();
+    let mut points: Vec<&[f32]> = points
+        .iter()
+        .map(Vec::as_slice)
+        .filter(|point| {
+            point.len() >= dimensions && point.iter().zip(reference).all(|(p, r)| p > r)
+        })
+        .collect();
+    if points.is_empty() || dimensions == 0 {
+        return 0.0;
+    }
+
+    slice_volume(&mut points, reference)
+}
+
+fn slice_volume(points: &mut [&[f32]], reference: &[f32]) -> f32 {
+    let last = reference.len() - 1;
+    if last == 0 {
+        return points
+            .iter()
+            .map(|point| point[0])
+            .fold(reference[0], f32::max)
+            - reference[0];
+    }
+
+    points.sort_by(|left, right| right[last].total_cmp(&left[last]));
+    let mut volume = 0.0;
+    for idx in 0..points.len() {
+        let floor = points
+            .get(idx + 1)
+            .map_or(reference[last], |next| next[last]);
+        let height = points[idx][last] - floor;
+        if height > 0.0 {
+            let mut projected: Vec<&[f32]> =
+                points[..=idx].iter().map(|point| &point[..last]).collect();
+            volume += slice_volume(&mut projected, &reference[..last]) * height;
+        }
+    }
+
+    volume
+}
+
+/// The Das and Dennis reference directions used by NSGA-III. These are
+/// every point on the unit simplex whose coordinates are multiples of
+/// `1 / divisions`, so there are `C(objectives + divisions - 1,
+/// divisions)` of them.
+pub fn reference_directions(objectives: usize, divisions: usize) -> Vec<Vec<f32>> {
+    fn fill(remaining: usize, left: usize, current: &mut Vec<usize>, out: &mut Vec<Vec<usize>>) {
+        if left == 1 {
+            current.push(remaining);
+            out.push(current.clone());
+            current.pop();
+            return;
+        }
+
+        for value in 0..=remaining {
+            current.push(value);
+            fill(remaining - value, left - 1, current, out);
+            current.pop();
+        }
+    }
+
+    if objectives == 0 {
+        return Vec::new();
+    }
+
+    let divisions = divisions.max(1);
+    let mut points = Vec::new();
+    fill(divisions, objectives, &mut Vec::new(), &mut points);
+    points
+        .into_iter()
+        .map(|point| {
+            point
+                .into_iter()
+                .map(|value| value as f32 / divisions as f32)
+                .collect()
+        })
+        .collect()
+}
+
+/// The distance between a point and the line through the origin along
+/// a reference direction.
+pub fn perpendicular_distance(point: &[f32], direction: &[f32]) -> f32 {
+    let length = direction.iter().map(|d| d * d).sum::<f32>();
+    if length <= 0.0 {
+        return point.iter().map(|p| p * p).sum::<f32>().sqrt();
+    }
+
+    let projection = point.iter().zip(direction).map(|(p, d)| p * d).sum::<f32>() / length;
+    point
+        .iter()
+        .zip(direction)
+        .map(|(p, d)| {
+            let gap = p - projection * d;
+            gap * gap
+        })
+        .sum::<f32>()
+        .sqrt()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes(objectives: &[[f32; 2]]) -> Vec<Node<()>> {
+        objectives
+            .iter()
+            .map(|values| Node {
+                objectives: values.to_vec(),
+                ..Node::new(())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sorting_groups_nodes_into_fronts() {
+        let population = nodes(&[[1.0, 3.0], [3.0, 1.0], [2.0, 2.0], [1.0, 1.0], [0.0, 0.0]]);
+        let fronts = non_dominated_sort(&population);
+
+        assert_eq!(fronts, vec![vec![0, 1, 2], vec![3], vec![4]]);
+    }
+
+    #[test]
+    fn feasible_nodes_dominate_infeasible_ones() {
+        let mut population = nodes(&[[5.0, 5.0], [1.0, 1.0]]);
+        population[0].violation = 1.0;
+
+        assert!(constrained_dominates(&population[1], &population[0]));
+    }
+
+    #[test]
+    fn strength_fitness_is_below_one_on_the_front() {
+        let population = nodes(&[[1.0, 3.0], [3.0, 1.0], [0.0, 0.0]]);
+        let fitness = strength_fitness(&population);
+
+        assert!(fitness[0] < 1.0);
+        assert!(fitness[1] < 1.0);
+        assert!(fitness[2] >= 1.0);
+    }
+
+    #[test]
+    fn strength_density_uses_the_kth_nearest_neighbor() {
+        let population = nodes(&[[0.0, 3.0], [1.0, 2.0], [2.0, 1.0], [3.0, 0.0]]);
+        let fitness = strength_fitness(&population);
+
+        // Four nodes give k = 2, and the second nearest to the first is two steps away
+        let sigma = 2.0 * core::f32::consts::SQRT_2;
+        assert!((fitness[0] - 1.0 / (sigma + 2.0)).abs() < 1e-6);
+    }
+
+    #[test]
+    fn truncation_removes_the_most_crowded_node() {
+        let population = nodes(&[[0.0, 4.0], [2.0, 2.0], [2.1, 1.9], [4.0, 0.0]]);
+        let kept = truncate_archive(&population, vec![0, 1, 2, 3], 3);
+
+        assert!(kept.contains(&0) && kept.contains(&3));
+        assert_eq!(kept.len(), 3);
+    }
+
+    #[test]
+    fn lexicographic_ordering_breaks_ties_with_later_objectives() {
+        let population = nodes(&[[1.0, 0.0], [1.0, 5.0], [0.0, 9.0]]);
+
+        assert_eq!(
+            lexicographic_cmp(&population[1], &population[0]),
+            Ordering::Greater
+        );
+        assert_eq!(
+            lexicographic_cmp(&population[0], &population[2]),
+            Ordering::Greater
+        );
+    }
+
+    #[test]
+    fn crowding_prefers_the_boundaries() {
+        let population = nodes(&[[0.0, 4.0], [1.0, 3.0], [3.0, 1.0], [4.0, 0.0]]);
+        let distances = crowding_distance(&population, &[0, 1, 2, 3]);
+
+        assert!(distances[0].is_infinite());
+        assert!(distances[3].is_infinite());
+        assert!(distances[1].is_finite() && distances[1] > 0.0);
+    }
+
+    #[test]
+    fn epsilon_archive_keeps_one_node_per_box() {
+        let mut archive = Vec::new();
+        for node in nodes(&[
+            [0.1, 0.9],
+            [0.15, 0.95],
+            [0.9, 0.1],
+            [0.5, 0.5],
+            [0.05, 0.05],
+        ]) {
+            epsilon_archive_insert(&mut archive, node, 0.5);
+        }
+
+        assert_eq!(archive.len(), 1);
+        assert_eq!(archive[0].objectives, vec![0.5, 0.5]);
+    }
+
+    #[test]
+    fn epsilon_archive_spreads_over_the_front() {
+        let mut archive = Vec::new();
+        let front: Vec<[f32; 2]> = (0..=100)
+            .map(|step| {
+                let x = step as f32 / 100.0;
+                [x, 1.0 - x]
+            })
+            .collect();
+        for node in nodes(&front) {
+            epsilon_archive_insert(&mut archive, node, 0.1);
+        }
+
+        assert!(archive.len() <= 11);
+        assert!(archive.len() >= 5);
+    }
+
+    #[test]
+    fn hypervolume_of_a_staircase() {
+        let points = vec![
+            vec![1.0, 3.0],
+            vec![2.0, 2.0],
+            vec![3.0, 1.0],
+            vec![1.0, 1.0],
+        ];
+        assert_eq!(hypervolume(&points, &[0.0, 0.0]), 6.0);
+        assert_eq!(hypervolume(&[vec![2.0, 2.0, 2.0]], &[0.0, 0.0, 0.0]), 8.0);
+        assert_eq!(hypervolume(&[vec![-1.0, 5.0]], &[0.0, 0.0]), 0.0);
+    }
+
+    #[test]
+    fn reference_directions_cover_the_simplex() {
+        let directions = reference_directions(3, 4);
+        assert_eq!(directions.len(), 15);
+        assert!(directions
+            .iter()
+            .all(|direction| (direction.iter().sum::<f32>() - 1.0).abs() < 1e-6));
+        assert_eq!(perpendicular_distance(&[1.0, 1.0], &[1.0, 0.0]), 1.0);
+    }
+}
@@ -0,0 +1,53 @@
+use std::{sync::Mutex, thread};
+
+/// Apply `map` to every item on `workers` threads, each of which takes
+/// the next item from a shared queue as soon as it is free. Items are
+/// taken in the order given, and the results are returned in that same
+/// order. WebAssembly can't spawn threads, so there the items are simply
+/// mapped in order on the calling thread.
+pub fn queue_map<T: Send, R: Send>(
+    items: Vec<T>,
+    workers: usize,
+    map: impl Fn(T) -> R + Sync,
+) -> Vec<R> {
+    if cfg!(target_family = "wasm") {
+        return items.into_iter().map(map).collect();
+    }
+
+    let count = items.len();
+    let queue = Mutex::new(items.into_iter().enumerate());
+    let results = Mutex::new(Vec::with_capacity(count));
+
+    thread::scope(|scope| {
+        for _ in 0..workers.clamp(1, count.max(1)) {
+            scope.spawn(|| loop {
+                let next = queue.lock().unwrap().next();
+                let Some((idx, item)) = next else {
+                    break;
+                };
+                let result = map(item);
+                results.lock().unwrap().push((idx, result));
+            });
+        }
+    });
+
+    let mut results = results.into_inner().unwrap();
+    results.sort_unstable_by_key(|(idx, _)| *idx);
+    results.into_iter().map(|(_, result)| result).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn results_keep_their_order() {
+        let results = queue_map((0..20).collect(), 4, |item: u64| {
+            thread::sleep(Duration::from_millis(20 - item));
+            item * 2
+        });
+        assert_eq!(results, (0..20).map(|item| item * 2).collect::<Vec<_>>());
+        assert!(queue_map(Vec::<u8>::new(), 0, |item| item).is_empty());
+    }
+}
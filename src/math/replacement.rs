@@ -0,0 +1,156 @@
+use crate::models::{distance::Distance, node::Node};
+use crate::parallel::*;
+use crate::random::Seed;
+use rand::prelude::*;
+
+/// Deterministic crowding replacement. The parents are shuffled into
+/// pairs, each pair produces two children via `breed`, the children are
+/// scored via `evaluate`, and then each child competes against its most
+/// similar parent. The winners make up the next generation. Each pair
+/// breeds from its own stream of `seed`.
+pub fn deterministic_crowding<Solution: Clone + Send + Sync>(
+    parents: &[Node<Solution>],
+    distance: Option<&(dyn Distance<Solution> + Sync)>,
+    breed: impl Fn(&Node<Solution>, &Node<Solution>) -> Node<Solution> + Sync,
+    evaluate: impl Fn(&mut Node<Solution>) + Sync,
+    seed: Seed,
+) -> Vec<Node<Solution>> {
+    let mut order: Vec<usize> = (0..parents.len()).collect();
+    order.shuffle(&mut crate::random::rng());
+
+    let mut next_population = order
+        .par_chunks(2)
+        .enumerate()
+        .flat_map_iter(|(idx, pair)| {
+            let _stream = seed.derive(idx as u64).enter();
+            if pair.len() < 2 {
+                // An odd node out survives untouched.
+                return vec![parents[pair[0]].clone()];
+            }
+
+            let (left, right) = (&parents[pair[0]], &parents[pair[1]]);
+            let mut first = breed(left, right);
+            let mut second = breed(right, left);
+            evaluate(&mut first);
+            evaluate(&mut second);
+
+            let crossed = match distance {
+                Some(distance) => {
+                    let straight = distance.distance(&left.solution, &first.solution)
+                        + distance.distance(&right.solution, &second.solution);
+                    let swapped = distance.distance(&left.solution, &second.solution)
+                        + distance.distance(&right.solution, &first.solution);
+                    swapped < straight
+                }
+                None => false,
+            };
+
+            let (first_rival, second_rival) = if crossed {
+                (right, left)
+            } else {
+                (left, right)
+            };
+
+            vec![compete(first, first_rival), compete(second, second_rival)]
+        })
+        .collect::<Vec<Node<Solution>>>();
+
+    next_population.truncate(parents.len());
+    next_population
+}
+
+/// Restricted tournament replacement. Each offspring is compared with
+/// `window` random members of the population and competes against the
+/// one which is closest to it.
+pub fn restricted_tournament<Solution: Clone>(
+    mut population: Vec<Node<Solution>>,
+    offspring: Vec<Node<Solution>>,
+    window: usize,
+    distance: Option<&(dyn Distance<Solution> + Sync)>,
+) -> Vec<Node<Solution>> {
+    if population.is_empty() {
+        return population;
+    }
+
+    let mut rng = crate::random::rng();
+    let window = window.clamp(1, population.len());
+    for child in offspring {
+        let candidates = rand::seq::index::sample(&mut rng, population.len(), window);
+        let rival = match distance {
+            Some(distance) => candidates
+                .iter()
+                .map(|idx| {
+                    let d = distance.distance(&child.solution, &population[idx].solution);
+                    (idx, d)
+                })
+                .min_by(|left, right| left.1.total_cmp(&right.1))
+                .map(|(idx, _)| idx)
+                .unwrap(),
+            None => candidates.index(0),
+        };
+
+        if child.score >= population[rival].score {
+            population[rival] = child;
+        }
+    }
+
+    population
+}
+
+/// The child replaces the parent only if it scores at least as well.
+fn compete<Solution: Clone>(child: Node<Solution>, parent: &Node<Solution>) -> Node<Solution> {
+    if child.score >= parent.score {
+        child
+    } else {
+        parent.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn restricted_tournament_replaces_the_closest_node() {
+        let population: Vec<Node<f32>> = [0.0, 10.0]
+            .iter()
+            .map(|x| Node {
+                score: 0.0,
+                ..Node::new(*x)
+            })
+            .collect();
+        let offspring = vec![Node {
+            score: 1.0,
+            ..Node::new(9.0)
+        }];
+        let distance = |l: &f32, r: &f32| (l - r).abs();
+
+        let next = restricted_tournament(population, offspring, 2, Some(&distance));
+
+        assert_eq!(next[0].solution, 0.0);
+        assert_eq!(next[1].solution, 9.0);
+    }
+
+    #[test]
+    fn crowding_never_loses_the_best_parent() {
+        let parents: Vec<Node<f32>> = (0..10)
+            .map(|x| Node {
+                score: x as f32,
+                ..Node::new(x as f32)
+            })
+            .collect();
+        let distance = |l: &f32, r: &f32| (l - r).abs();
+
+        let next = deterministic_crowding(
+            &parents,
+            Some(&distance),
+            |left, right| Node::new((left.solution + right.solution) * 0.5),
+            |node| node.score = node.solution,
+            Seed::new(None),
+        );
+
+        assert_eq!(next.len(), parents.len());
+        let best = next.iter().map(|node| node.score).fold(f32::MIN, f32::max);
+        assert_eq!(best, 9.0);
+    }
+}
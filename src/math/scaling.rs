@@ -0,0 +1,107 @@
+use super::statistics::{mean, standard_deviation};
+use crate::models::{node::Node, scaling::Scaling};
+
+/// The smallest score sigma scaling will produce, so that below average
+/// nodes still have a small chance of being picked.
+const SIGMA_FLOOR: f32 = 0.1;
+
+/// Rescale the score of every node according to the scaling strategy.
+pub fn apply_scaling<Solution>(nodes: &mut [Node<Solution>], scaling: &Scaling) {
+    match scaling {
+        Scaling::None => {}
+        Scaling::Sigma => {
+            let scores: Vec<f32> = nodes.iter().map(|node| node.score).collect();
+            let center = mean(&scores);
+            let sigma = standard_deviation(&scores);
+
+            for node in nodes.iter_mut() {
+                node.score = if sigma > 0.0 {
+                    (1.0 + (node.score - center) / (2.0 * sigma)).max(SIGMA_FLOOR)
+                } else {
+                    1.0
+                };
+            }
+        }
+        Scaling::Linear { pressure } => {
+            let scores: Vec<f32> = nodes.iter().map(|node| node.score).collect();
+            let (a, b) = linear_coefficients(&scores, *pressure);
+
+            for node in nodes.iter_mut() {
+                node.score = a * node.score + b;
+            }
+        }
+    }
+}
+
+/// Compute the `a` and `b` coefficients for linear scaling.
+fn linear_coefficients(scores: &[f32], pressure: f32) -> (f32, f32) {
+    let average = mean(scores);
+    let max = scores.iter().copied().fold(f32::MIN, f32::max);
+    let min = scores.iter().copied().fold(f32::MAX, f32::min);
+
+    if pressure <= 1.0 || max <= average {
+        return (1.0, 0.0);
+    }
+
+    if min > (pressure * average - max) / (pressure - 1.0) {
+        let delta = max - average;
+        (
+            (pressure - 1.0) * average / delta,
+            average * (max - pressure * average) / delta,
+        )
+    } else {
+        let delta = average - min;
+        (average / delta, -min * average / delta)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes(scores: &[f32]) -> Vec<Node<()>> {
+        scores
+            .iter()
+            .map(|score| Node {
+                score: *score,
+                ..Node::new(())
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sigma_scaling_centers_on_one() {
+        let mut population = nodes(&[100.0, 101.0, 102.0]);
+        apply_scaling(&mut population, &Scaling::Sigma);
+
+        assert_eq!(population[1].score, 1.0);
+        assert!(population[0].score < 1.0);
+        assert!(population[2].score > 1.0);
+    }
+
+    #[test]
+    fn linear_scaling_preserves_the_average() {
+        let mut population = nodes(&[10.0, 11.0, 12.0]);
+        apply_scaling(&mut population, &Scaling::Linear { pressure: 2.0 });
+
+        let scores: Vec<f32> = population.iter().map(|node| node.score).collect();
+        assert!((mean(&scores) - 11.0).abs() < 1e-4);
+        assert!(scores.iter().all(|score| *score >= -1e-4));
+    }
+
+    #[test]
+    fn linear_scaling_hits_the_target_pressure() {
+        let mut population = nodes(&[4.0, 5.0, 6.0]);
+        apply_scaling(&mut population, &Scaling::Linear { pressure: 1.5 });
+
+        assert!((population[2].score - 7.5).abs() < 1e-4);
+    }
+
+    #[test]
+    fn sigma_scaling_flattens_identical_scores() {
+        let mut population = nodes(&[5.0, 5.0]);
+        apply_scaling(&mut population, &Scaling::Sigma);
+
+        assert!(population.iter().all(|node| node.score == 1.0));
+    }
+}
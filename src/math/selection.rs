@@ -0,0 +1,657 @@
+use super::columns::ScoreColumns;
+use super::constraints::compare;
+use super::statistics::median_absolute_deviation;
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::float::Float;
+use crate::models::{
+    distance::Distance, layout::PopulationLayout, node::Node, selection::Selection,
+    test_parameters::TestParameters,
+};
+use alloc::{vec, vec::Vec};
+use core::cmp::Ordering;
+use rand::distributions::WeightedIndex;
+use rand::prelude::*;
+
+/// How many times the runner will re-roll a parent which is already
+/// taken, or not compatible with the first, before falling back to an
+/// exhaustive search.
+const MATING_ATTEMPTS: usize = 16;
+
+/// A basic implementation of tournament selection. Returns the index
+/// of the winning node.
+pub fn tournament_selection<Solution, FeatureFlags>(
+    nodes: &[Node<Solution>],
+    params: &TestParameters<FeatureFlags>,
+) -> Option<usize> {
+    tournament(nodes.len(), params, |left, right| {
+        compare(&nodes[left], &nodes[right], &params.constraint_handling)
+    })
+}
+
+/// Run a tournament between `len` contestants, comparing them by index,
+/// where `Greater` means the left contestant is better.
+pub fn tournament<FeatureFlags>(
+    len: usize,
+    params: &TestParameters<FeatureFlags>,
+    compare: impl Fn(usize, usize) -> Ordering,
+) -> Option<usize> {
+    if len == 0 {
+        return None;
+    }
+
+    let mut rng = crate::random::rng();
+    let winner = |best: usize, idx: usize| {
+        if compare(idx, best).is_gt() {
+            idx
+        } else {
+            best
+        }
+    };
+
+    // The usual tournament needs no bookkeeping, so contestants are
+    // drawn and compared on the fly without allocating
+    let deterministic = params.tournament_win_probability >= 1.0;
+    if deterministic && !params.tournament_without_replacement {
+        return (0..params.current_tournament_size())
+            .map(|_| rng.gen_range(0..len))
+            .reduce(winner);
+    }
+
+    let contestants: Vec<usize> = if params.tournament_without_replacement {
+        let size = params.current_tournament_size().min(len);
+        rand::seq::index::sample(&mut rng, len, size).into_vec()
+    } else {
+        (0..params.current_tournament_size())
+            .map(|_| rng.gen_range(0..len))
+            .collect()
+    };
+
+    if deterministic {
+        return contestants.into_iter().reduce(winner);
+    }
+
+    // The best contestant wins with probability p, the runner up with
+    // p * (1 - p), and so on. The last contestant takes what is left.
+    let mut ranked = contestants;
+    ranked.sort_by(|left, right| compare(*right, *left));
+    let probability = params.tournament_win_probability.max(0.0) as f64;
+    for idx in ranked.iter() {
+        if rng.gen_bool(probability) {
+            return Some(*idx);
+        }
+    }
+
+    ranked.last().copied()
+}
+
+/// Whether two nodes are allowed to mate under the mating restriction
+/// in the parameters. Always true when there is no restriction or no
+/// distance to measure it with.
+pub fn compatible<Solution, FeatureFlags>(
+    params: &TestParameters<FeatureFlags>,
+    distance: Option<&(dyn Distance<Solution> + Sync)>,
+    left: &Node<Solution>,
+    right: &Node<Solution>,
+) -> bool {
+    match (params.mating_restriction, distance) {
+        (Some(minimum), Some(distance)) => {
+            distance.distance(&left.solution, &right.solution) >= minimum
+        }
+        _ => true,
+    }
+}
+
+/// Lexicase selection. Shuffle the test cases and, one case at a time,
+/// discard every candidate which is not within epsilon of the best score
+/// on that case. Whatever survives all cases is chosen from at random.
+/// Passing an epsilon of zero for every case gives plain lexicase.
+///
+/// A node without a score on a case counts as the worst on it. A case
+/// which would discard every candidate, as NaN scores can, discards
+/// none, so only an empty population gives nothing back.
+pub fn lexicase_selection<Solution>(nodes: &[Node<Solution>], epsilons: &[f32]) -> Option<usize> {
+    let mut rng = crate::random::rng();
+    let mut order: Vec<usize> = (0..epsilons.len()).collect();
+    order.shuffle(&mut rng);
+
+    let mut candidates: Vec<usize> = (0..nodes.len()).collect();
+    for case in order {
+        if candidates.len() <= 1 {
+            break;
+        }
+
+        let score = |idx: &usize| {
+            nodes[*idx]
+                .case_scores
+                .get(case)
+                .copied()
+                .unwrap_or(f32::MIN)
+        };
+        let best = candidates.iter().map(score).fold(f32::MIN, f32::max);
+
+        let threshold = best - epsilons[case];
+        let survivors: Vec<usize> = candidates
+            .iter()
+            .copied()
+            .filter(|idx| score(idx) >= threshold)
+            .collect();
+        if !survivors.is_empty() {
+            candidates = survivors;
+        }
+    }
+
+    candidates.choose(&mut rng).copied()
+}
+
+/// Compute the epsilon for each test case as the median absolute
+/// deviation of the population's scores on that case.
+pub fn case_epsilons<Solution>(nodes: &[Node<Solution>], cases: usize) -> Vec<f32> {
+    (0..cases)
+        .map(|case| {
+            let scores: Vec<f32> = nodes
+                .iter()
+                .filter_map(|node| node.case_scores.get(case).copied())
+                .collect();
+            median_absolute_deviation(&scores)
+        })
+        .collect()
+}
+
+/// The most test cases any node was scored on. Nodes whose evaluation
+/// was forfeited, for one, have no case scores at all.
+fn case_count<Solution>(nodes: &[Node<Solution>]) -> usize {
+    nodes
+        .iter()
+        .map(|node| node.case_scores.len())
+        .max()
+        .unwrap_or(0)
+}
+
+/// Stochastic ranking (Runarsson & Yao). Returns the indices of the
+/// nodes from best to worst. Sweeps a bubble sort over the population,
+/// comparing adjacent nodes by score when both are feasible or with the
+/// given probability, and by constraint violation otherwise. Stops early
+/// once a sweep makes no swaps.
+pub fn stochastic_ranking<Solution>(nodes: &[Node<Solution>], probability: f32) -> Vec<usize> {
+    let mut rng = crate::random::rng();
+    let mut ranking: Vec<usize> = (0..nodes.len()).collect();
+
+    for _ in 0..nodes.len() {
+        let mut swapped = false;
+        for j in 0..nodes.len().saturating_sub(1) {
+            let (upper, lower) = (&nodes[ranking[j]], &nodes[ranking[j + 1]]);
+            let feasible = upper.violation <= 0.0 && lower.violation <= 0.0;
+            let swap = if feasible || rng.gen::<f32>() < probability {
+                upper.score < lower.score
+            } else {
+                upper.violation > lower.violation
+            };
+
+            if swap {
+                ranking.swap(j, j + 1);
+                swapped = true;
+            }
+        }
+
+        if !swapped {
+            break;
+        }
+    }
+
+    ranking
+}
+
+/// Compute the Boltzmann weight of each node for a given temperature.
+/// Scores are shifted by the maximum so the exponent never overflows.
+pub fn boltzmann_weights<Solution>(nodes: &[Node<Solution>], temperature: f32) -> Vec<f64> {
+    let max_score = nodes
+        .iter()
+        .map(|node| node.score)
+        .filter(|score| score.is_finite())
+        .fold(f32::MIN, f32::max);
+    let temperature = temperature.max(f32::EPSILON) as f64;
+
+    nodes
+        .iter()
+        .map(|node| {
+            if node.score.is_finite() {
+                (((node.score - max_score) as f64) / temperature).exp()
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+/// How many of the best nodes the selection strategy expects to find
+/// at the front of the population, best first. Only truncation selection
+/// cares, so the runner doesn't need to sort anything else.
+pub fn front<FeatureFlags>(params: &TestParameters<FeatureFlags>, nodes: usize) -> usize {
+    match params.selection {
+        Selection::Truncation { fraction } => {
+            ((fraction.clamp(0.0, 1.0) * nodes as f32).ceil() as usize).max(1)
+        }
+        _ => 0,
+    }
+}
+
+/// The per-generation state required to pick parents. Some selection
+/// strategies need to look at the whole population before they can
+/// pick anything, so this is computed once after scoring.
+pub enum Selector {
+    Tournament,
+    /// A tournament which only looks at the score columns.
+    ColumnTournament(ScoreColumns),
+    Weighted(WeightedIndex<f64>),
+    Truncated(usize),
+    Lexicase(Vec<f32>),
+    Ranked(Vec<usize>),
+}
+
+impl Selector {
+    pub fn new<Solution, FeatureFlags>(
+        nodes: &[Node<Solution>],
+        params: &TestParameters<FeatureFlags>,
+        generation: usize,
+    ) -> Selector {
+        match params.selection {
+            Selection::Tournament if params.layout == PopulationLayout::Columns => {
+                Selector::ColumnTournament(ScoreColumns::new(nodes, &params.constraint_handling))
+            }
+            Selection::Tournament => Selector::Tournament,
+            Selection::Boltzmann { temperature } => {
+                let temperature = temperature.value(generation, params.generations);
+                match WeightedIndex::new(boltzmann_weights(nodes, temperature)) {
+                    Ok(index) => Selector::Weighted(index),
+                    Err(_) => Selector::Tournament,
+                }
+            }
+            Selection::Roulette => {
+                let weights = nodes.iter().map(|node| {
+                    if node.score.is_finite() {
+                        node.score.max(0.0) as f64
+                    } else {
+                        0.0
+                    }
+                });
+                match WeightedIndex::new(weights) {
+                    Ok(index) => Selector::Weighted(index),
+                    Err(_) => Selector::Tournament,
+                }
+            }
+            Selection::Truncation { .. } => Selector::Truncated(front(params, nodes.len())),
+            Selection::StochasticRanking {
+                probability,
+                fraction,
+            } => {
+                let mut ranking = stochastic_ranking(nodes, probability);
+                let count = (fraction.clamp(0.0, 1.0) * nodes.len() as f32).ceil() as usize;
+                ranking.truncate(count.max(1));
+                Selector::Ranked(ranking)
+            }
+            Selection::Lexicase => match case_count(nodes) {
+                0 => Selector::Tournament,
+                cases => Selector::Lexicase(vec![0.0; cases]),
+            },
+            Selection::EpsilonLexicase => match case_count(nodes) {
+                0 => Selector::Tournament,
+                cases => Selector::Lexicase(case_epsilons(nodes, cases)),
+            },
+        }
+    }
+
+    /// Pick a single parent from the population, returning its index.
+    pub fn select<Solution, FeatureFlags>(
+        &self,
+        nodes: &[Node<Solution>],
+        params: &TestParameters<FeatureFlags>,
+    ) -> Option<usize> {
+        match self {
+            Selector::Tournament => tournament_selection(nodes, params),
+            Selector::ColumnTournament(columns) => {
+                tournament(columns.len(), params, |left, right| {
+                    columns.compare(left, right)
+                })
+            }
+            Selector::Weighted(index) => {
+                let mut rng = crate::random::rng();
+                Some(index.sample(&mut rng))
+            }
+            Selector::Truncated(count) => {
+                // The runner moves the best nodes to the front, best
+                // first, before selection.
+                let mut rng = crate::random::rng();
+                let count = (*count).min(nodes.len());
+                if count == 0 {
+                    return None;
+                }
+                Some(rng.gen_range(0..count))
+            }
+            Selector::Lexicase(epsilons) => lexicase_selection(nodes, epsilons),
+            Selector::Ranked(ranking) => {
+                let mut rng = crate::random::rng();
+                ranking.choose(&mut rng).copied()
+            }
+        }
+    }
+
+    /// Pick two parents from the population, returning their indices.
+    /// If the parameters require distinct parents, the second parent is
+    /// re-rolled until it differs from the first. It is also re-rolled
+    /// while the pair is not `compatible`, see `select_mate`.
+    pub fn select_pair<Solution, FeatureFlags>(
+        &self,
+        nodes: &[Node<Solution>],
+        params: &TestParameters<FeatureFlags>,
+        compatible: impl Fn(&Node<Solution>, &Node<Solution>) -> bool,
+    ) -> Option<(usize, usize)> {
+        let left = self.select(nodes, params)?;
+        let distinct = params.distinct_parents && nodes.len() > 1;
+        let right = self.select_mate(nodes, params, &[left], distinct, &compatible)?;
+        Some((left, right))
+    }
+
+    /// Pick `count` parents for multi-parent recombination. Every parent
+    /// after the first must be compatible with the first, and parents
+    /// are distinct when `distinct_parents` is set and there are enough
+    /// nodes to go around.
+    pub fn select_parents<Solution, FeatureFlags>(
+        &self,
+        nodes: &[Node<Solution>],
+        params: &TestParameters<FeatureFlags>,
+        count: usize,
+        compatible: impl Fn(&Node<Solution>, &Node<Solution>) -> bool,
+    ) -> Option<Vec<usize>> {
+        let first = self.select(nodes, params)?;
+        let mut parents = vec![first];
+        let distinct = params.distinct_parents && nodes.len() >= count;
+
+        while parents.len() < count {
+            let parent = self.select_mate(nodes, params, &parents, distinct, &compatible)?;
+            parents.push(parent);
+        }
+
+        Some(parents)
+    }
+
+    /// Pick a mate for `parents[0]`, re-rolling while it is incompatible
+    /// with the first parent or, when `distinct`, already one of the
+    /// `parents`. If no roll succeeds, one of the nodes which satisfy both
+    /// is chosen at random, then one which is at least unused, and only
+    /// when neither exists is the last roll kept.
+    fn select_mate<Solution, FeatureFlags>(
+        &self,
+        nodes: &[Node<Solution>],
+        params: &TestParameters<FeatureFlags>,
+        parents: &[usize],
+        distinct: bool,
+        compatible: &impl Fn(&Node<Solution>, &Node<Solution>) -> bool,
+    ) -> Option<usize> {
+        let first = &nodes[parents[0]];
+        let unused = |index: &usize| !distinct || !parents.contains(index);
+        let suitable = |index: &usize| unused(index) && compatible(first, &nodes[*index]);
+
+        let mut mate = self.select(nodes, params)?;
+        for _ in 0..MATING_ATTEMPTS {
+            if suitable(&mate) {
+                return Some(mate);
+            }
+            mate = self.select(nodes, params)?;
+        }
+        if suitable(&mate) {
+            return Some(mate);
+        }
+
+        let mut rng = crate::random::rng();
+        let suitable: Vec<usize> = (0..nodes.len()).filter(suitable).collect();
+        if let Some(mate) = suitable.choose(&mut rng) {
+            return Some(*mate);
+        }
+        let unused: Vec<usize> = (0..nodes.len()).filter(unused).collect();
+        Some(unused.choose(&mut rng).copied().unwrap_or(mate))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(score: f32) -> Node<()> {
+        Node {
+            score,
+            ..Node::new(())
+        }
+    }
+
+    #[test]
+    fn boltzmann_weights_favor_higher_scores() {
+        let nodes = vec![node(1.0), node(2.0), node(f32::NAN)];
+        let weights = boltzmann_weights(&nodes, 1.0);
+
+        assert_eq!(weights[1], 1.0);
+        assert!(weights[0] < weights[1]);
+        assert_eq!(weights[2], 0.0);
+    }
+
+    #[test]
+    fn truncation_only_picks_from_the_top() {
+        let nodes = vec![node(4.0), node(3.0), node(2.0), node(1.0)];
+        let params = TestParameters::<()> {
+            selection: Selection::Truncation { fraction: 0.5 },
+            ..Default::default()
+        };
+        let selector = Selector::new(&nodes, &params, 0);
+
+        for _ in 0..100 {
+            let picked = selector.select(&nodes, &params).unwrap();
+            assert!(picked < 2);
+        }
+    }
+
+    #[test]
+    fn lexicase_keeps_specialists_alive() {
+        let nodes = vec![
+            Node {
+                score: 1.0,
+                case_scores: vec![1.0, 0.0],
+                ..Node::new(())
+            },
+            Node {
+                score: 1.0,
+                case_scores: vec![0.0, 1.0],
+                ..Node::new(())
+            },
+            Node {
+                score: 0.8,
+                case_scores: vec![0.4, 0.4],
+                ..Node::new(())
+            },
+        ];
+
+        for _ in 0..100 {
+            let picked = lexicase_selection(&nodes, &[0.0, 0.0]).unwrap();
+            assert!(picked < 2);
+        }
+    }
+
+    #[test]
+    fn epsilon_lexicase_tolerates_small_differences() {
+        let nodes = vec![
+            Node {
+                case_scores: vec![1.0],
+                ..Node::new(())
+            },
+            Node {
+                case_scores: vec![0.95],
+                ..Node::new(())
+            },
+        ];
+
+        let mut seen_runner_up = false;
+        for _ in 0..100 {
+            let picked = lexicase_selection(&nodes, &[0.1]).unwrap();
+            seen_runner_up |= picked == 1;
+        }
+        assert!(seen_runner_up);
+    }
+
+    #[test]
+    fn lexicase_always_picks_someone() {
+        let nodes = vec![
+            node(0.0),
+            Node {
+                case_scores: vec![f32::NAN, 1.0],
+                ..Node::new(())
+            },
+        ];
+
+        for _ in 0..100 {
+            assert!(lexicase_selection(&nodes, &[0.0, 0.0]).is_some());
+            assert!(lexicase_selection(&nodes[..1], &[0.0, 0.0]).is_some());
+        }
+    }
+
+    #[test]
+    fn lexicase_looks_past_nodes_without_case_scores() {
+        let nodes = vec![
+            node(0.0),
+            Node {
+                case_scores: vec![1.0, 0.0],
+                ..Node::new(())
+            },
+        ];
+        let params = TestParameters::<()> {
+            selection: Selection::Lexicase,
+            ..Default::default()
+        };
+
+        let selector = Selector::new(&nodes, &params, 0);
+        assert!(matches!(&selector, Selector::Lexicase(epsilons) if epsilons.len() == 2));
+    }
+
+    #[test]
+    fn distinct_parents_never_self_mate() {
+        let nodes = vec![node(1.0), node(0.0)];
+        let params = TestParameters::<()> {
+            tournament_size: 2,
+            tournament_without_replacement: true,
+            distinct_parents: true,
+            ..Default::default()
+        };
+        let selector = Selector::new(&nodes, &params, 0);
+
+        for _ in 0..100 {
+            let (left, right) = selector.select_pair(&nodes, &params, |_, _| true).unwrap();
+            assert_ne!(left, right);
+        }
+    }
+
+    #[test]
+    fn incompatible_mates_are_rerolled() {
+        let nodes = vec![node(0.0), node(1.0), node(2.0)];
+        let params = TestParameters::<()> {
+            tournament_size: 1,
+            ..Default::default()
+        };
+        let selector = Selector::new(&nodes, &params, 0);
+
+        for _ in 0..100 {
+            let (left, right) = selector
+                .select_pair(&nodes, &params, |l, r| (l.score - r.score).abs() >= 1.0)
+                .unwrap();
+            assert_ne!(left, right);
+        }
+    }
+
+    #[test]
+    fn fallback_mates_stay_compatible_and_distinct() {
+        // The first tournament always picks the best node, so re-rolls never
+        // find its only compatible mate and the fallback has to.
+        let nodes = vec![node(0.0), node(1.0), node(2.0), node(3.0)];
+        let params = TestParameters::<()> {
+            tournament_size: 4,
+            tournament_without_replacement: true,
+            distinct_parents: true,
+            ..Default::default()
+        };
+        let selector = Selector::new(&nodes, &params, 0);
+        let compatible = |l: &Node<()>, r: &Node<()>| l.score + r.score <= 4.0;
+
+        for _ in 0..20 {
+            let (left, right) = selector.select_pair(&nodes, &params, compatible).unwrap();
+            assert_eq!(left, 3);
+            assert!(right < 2);
+
+            let parents = selector
+                .select_parents(&nodes, &params, 3, compatible)
+                .unwrap();
+            assert_eq!(parents[0], 3);
+            assert!(parents[1] < 2 && parents[2] < 2);
+            assert_ne!(parents[1], parents[2]);
+        }
+    }
+
+    #[test]
+    fn probabilistic_tournaments_let_weaker_nodes_win() {
+        let nodes = vec![node(1.0), node(0.0)];
+        let params = TestParameters::<()> {
+            tournament_size: 2,
+            tournament_without_replacement: true,
+            tournament_win_probability: 0.5,
+            ..Default::default()
+        };
+
+        let wins = (0..200)
+            .filter(|_| tournament_selection(&nodes, &params) == Some(1))
+            .count();
+        assert!(wins > 0 && wins < 200);
+    }
+
+    #[test]
+    fn stochastic_ranking_prefers_feasible_nodes() {
+        let nodes = vec![
+            Node {
+                score: 10.0,
+                violation: 5.0,
+                ..Node::new(())
+            },
+            Node {
+                score: 1.0,
+                ..Node::new(())
+            },
+            Node {
+                score: 2.0,
+                ..Node::new(())
+            },
+        ];
+
+        let ranking = stochastic_ranking(&nodes, 0.0);
+        assert_eq!(ranking, vec![2, 1, 0]);
+    }
+
+    #[test]
+    fn boltzmann_weights_sharpen_as_temperature_drops() {
+        let nodes = vec![node(1.0), node(2.0)];
+        let hot = boltzmann_weights(&nodes, 10.0);
+        let cold = boltzmann_weights(&nodes, 0.1);
+
+        assert!(cold[0] < hot[0]);
+    }
+
+    #[test]
+    fn distinct_parents_are_all_different() {
+        let nodes = vec![node(3.0), node(2.0), node(1.0), node(0.0)];
+        let params = TestParameters::<()> {
+            tournament_size: 4,
+            distinct_parents: true,
+            ..Default::default()
+        };
+        let selector = Selector::new(&nodes, &params, 0);
+
+        let mut parents = selector
+            .select_parents(&nodes, &params, 4, |_, _| true)
+            .unwrap();
+        parents.sort();
+        assert_eq!(parents, vec![0, 1, 2, 3]);
+    }
+}
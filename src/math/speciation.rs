@@ -0,0 +1,188 @@
+use crate::models::{
+    distance::Distance, node::Node, speciation::Speciation, statistics::SpeciesStatistics,
+};
+use rand::prelude::*;
+
+/// A group of similar nodes which persists across generations.
+struct Species<Solution> {
+    id: usize,
+    representative: Solution,
+    best: f32,
+    current: f32,
+    stagnant: usize,
+    age: usize,
+    members: Vec<usize>,
+}
+
+/// Tracks the species of a run from one generation to the next.
+pub struct Speciator<Solution> {
+    species: Vec<Species<Solution>>,
+    last_id: usize,
+}
+
+impl<Solution: Clone> Speciator<Solution> {
+    pub fn new() -> Self {
+        Speciator {
+            species: Vec::new(),
+            last_id: 0,
+        }
+    }
+
+    /// How many species survived the last generation.
+    #[cfg(test)]
+    pub fn len(&self) -> usize {
+        self.species.len()
+    }
+
+    /// Sort the scored nodes into species, divide every score by the size
+    /// of its species, and drop the scores of stagnant species to zero.
+    pub fn apply(
+        &mut self,
+        nodes: &mut [Node<Solution>],
+        distance: &(dyn Distance<Solution> + Sync),
+        speciation: &Speciation,
+    ) {
+        self.assign(nodes, distance, speciation.threshold);
+
+        let champion = self
+            .species
+            .iter()
+            .enumerate()
+            .max_by(|(_, left), (_, right)| left.current.total_cmp(&right.current))
+            .map(|(idx, _)| idx)
+            .unwrap_or(0);
+
+        let mut rng = crate::random::rng();
+        for (idx, species) in self.species.iter_mut().enumerate() {
+            let culled = idx != champion && species.stagnant > speciation.stagnation;
+            for member in &species.members {
+                let node = &mut nodes[*member];
+                node.score = if culled {
+                    0.0
+                } else {
+                    node.score / species.members.len() as f32
+                };
+            }
+
+            let representative = *species.members.choose(&mut rng).unwrap();
+            species.representative = nodes[representative].solution.clone();
+        }
+    }
+
+    /// Sort the scored nodes into species without touching their scores,
+    /// so that niches can be followed across generations and reported.
+    pub fn assign(
+        &mut self,
+        nodes: &[Node<Solution>],
+        distance: &(dyn Distance<Solution> + Sync),
+        threshold: f32,
+    ) {
+        for species in self.species.iter_mut() {
+            species.members.clear();
+        }
+        for (idx, node) in nodes.iter().enumerate() {
+            let existing = self.species.iter().position(|species| {
+                distance.distance(&species.representative, &node.solution) <= threshold
+            });
+            match existing {
+                Some(species) => self.species[species].members.push(idx),
+                None => {
+                    self.last_id += 1;
+                    self.species.push(Species {
+                        id: self.last_id,
+                        representative: node.solution.clone(),
+                        best: f32::MIN,
+                        current: f32::MIN,
+                        stagnant: 0,
+                        age: 0,
+                        members: vec![idx],
+                    });
+                }
+            }
+        }
+
+        // Species which attracted no members this generation die out
+        self.species.retain(|species| !species.members.is_empty());
+
+        for species in self.species.iter_mut() {
+            let best = species
+                .members
+                .iter()
+                .map(|member| nodes[*member].score)
+                .fold(f32::MIN, f32::max);
+            species.current = best;
+            if species.best == f32::MIN {
+                species.best = best;
+            } else {
+                species.age += 1;
+                if best > species.best {
+                    species.best = best;
+                    species.stagnant = 0;
+                } else {
+                    species.stagnant += 1;
+                }
+            }
+        }
+    }
+
+    /// The size, best score, and age of every species, as of the last
+    /// generation which was sorted.
+    pub fn report(&self) -> Vec<SpeciesStatistics> {
+        self.species
+            .iter()
+            .map(|species| SpeciesStatistics {
+                id: species.id,
+                size: species.members.len(),
+                best: species.current,
+                age: species.age,
+                stagnant: species.stagnant,
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn nodes(values: &[(f32, f32)]) -> Vec<Node<f32>> {
+        values
+            .iter()
+            .map(|(solution, score)| Node {
+                score: *score,
+                ..Node::new(*solution)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn scores_are_shared_within_species() {
+        let distance = |left: &f32, right: &f32| (left - right).abs();
+        let mut speciator = Speciator::new();
+        let mut population = nodes(&[(0.0, 4.0), (0.5, 2.0), (10.0, 3.0)]);
+        speciator.apply(&mut population, &distance, &Speciation::default());
+
+        assert_eq!(speciator.len(), 2);
+        let scores: Vec<f32> = population.iter().map(|node| node.score).collect();
+        assert_eq!(scores, vec![2.0, 1.0, 3.0]);
+    }
+
+    #[test]
+    fn stagnant_species_are_culled() {
+        let distance = |left: &f32, right: &f32| (left - right).abs();
+        let speciation = Speciation {
+            threshold: 1.0,
+            stagnation: 2,
+        };
+        let mut speciator = Speciator::new();
+        let mut scores = Vec::new();
+        for _ in 0..5 {
+            let mut population = nodes(&[(0.0, 4.0), (10.0, 3.0)]);
+            speciator.apply(&mut population, &distance, &speciation);
+            scores = population.iter().map(|node| node.score).collect();
+        }
+
+        // The champion species survives even though it has stagnated too
+        assert_eq!(scores, vec![4.0, 0.0]);
+    }
+}
@@ -0,0 +1,118 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::float::Float;
+use alloc::collections::BTreeMap;
+use alloc::vec::Vec;
+
+/// Compute the mean of a set of values. Returns 0 for an empty set.
+pub fn mean(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    values.iter().sum::<f32>() / values.len() as f32
+}
+
+/// Compute the population standard deviation of a set of values.
+pub fn standard_deviation(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    let center = mean(values);
+    let variance = values
+        .iter()
+        .map(|value| (value - center) * (value - center))
+        .sum::<f32>()
+        / values.len() as f32;
+    variance.sqrt()
+}
+
+/// Compute the median of a set of values. Returns 0 for an empty set.
+pub fn median(values: &[f32]) -> f32 {
+    if values.is_empty() {
+        return 0.0;
+    }
+
+    let mut sorted = values.to_vec();
+    sorted.sort_by(|left, right| left.total_cmp(right));
+
+    let middle = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        (sorted[middle - 1] + sorted[middle]) * 0.5
+    } else {
+        sorted[middle]
+    }
+}
+
+/// Compute the `q` quantile of a set of values which is already sorted
+/// in ascending order, interpolating linearly between the closest
+/// values. Returns 0 for an empty set.
+pub fn quantile(sorted: &[f32], q: f32) -> f32 {
+    if sorted.is_empty() {
+        return 0.0;
+    }
+
+    let position = q.clamp(0.0, 1.0) * (sorted.len() - 1) as f32;
+    let (lower, upper) = (position.floor() as usize, position.ceil() as usize);
+    let weight = position - lower as f32;
+    sorted[lower] * (1.0 - weight) + sorted[upper] * weight
+}
+
+/// Compute the Shannon entropy, in bits, of the distribution of a set
+/// of labels. This is zero when every label is the same, and
+/// `log2(n)` when all `n` labels differ.
+pub fn entropy(labels: impl IntoIterator<Item = u64>) -> f32 {
+    let mut counts: BTreeMap<u64, usize> = BTreeMap::new();
+    let mut total = 0;
+    for label in labels {
+        *counts.entry(label).or_default() += 1;
+        total += 1;
+    }
+
+    counts
+        .values()
+        .map(|count| {
+            let share = *count as f32 / total as f32;
+            -share * share.log2()
+        })
+        .sum()
+}
+
+/// Compute the median absolute deviation of a set of values, which is
+/// the median of the distance between each value and the median.
+pub fn median_absolute_deviation(values: &[f32]) -> f32 {
+    let center = median(values);
+    let deviations: Vec<f32> = values.iter().map(|value| (value - center).abs()).collect();
+    median(&deviations)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn entropy_counts_distinct_labels() {
+        assert_eq!(entropy([7, 7, 7, 7]), 0.0);
+        assert_eq!(entropy([1, 2, 3, 4]), 2.0);
+        assert_eq!(entropy([1, 1, 2, 2]), 1.0);
+    }
+
+    #[test]
+    fn standard_deviation_of_a_known_set() {
+        let values = [2.0, 4.0, 4.0, 4.0, 5.0, 5.0, 7.0, 9.0];
+        assert_eq!(mean(&values), 5.0);
+        assert_eq!(standard_deviation(&values), 2.0);
+    }
+
+    #[test]
+    fn median_handles_even_and_odd_sets() {
+        assert_eq!(median(&[3.0, 1.0, 2.0]), 2.0);
+        assert_eq!(median(&[4.0, 1.0, 2.0, 3.0]), 2.5);
+        assert_eq!(median(&[]), 0.0);
+    }
+
+    #[test]
+    fn mad_ignores_outliers() {
+        assert_eq!(median_absolute_deviation(&[1.0, 2.0, 3.0, 4.0, 100.0]), 1.0);
+    }
+}
@@ -0,0 +1,118 @@
+//! Where a run's memory goes. The telemetry of each generation carries
+//! the population's footprint, and, when `CountingAllocator` is the
+//! program's global allocator, how many allocations the generation made:
+//!
+//! ```ignore
+//! #[global_allocator]
+//! static ALLOCATOR: algen::memory::CountingAllocator = algen::memory::CountingAllocator;
+//! ```
+//!
+//! The counts cover every thread in the process, so anything else running
+//! alongside a run is counted with it.
+use crate::models::node::Node;
+use std::{
+    alloc::{GlobalAlloc, Layout, System},
+    cell::Cell,
+    mem::size_of,
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+static ALLOCATED_BYTES: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    static THREAD_ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+/// The system allocator, counting every allocation it makes. A
+/// reallocation counts as an allocation of its new size.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CountingAllocator;
+
+impl CountingAllocator {
+    fn count(size: usize) {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        ALLOCATED_BYTES.fetch_add(size, Ordering::Relaxed);
+        // A thread being torn down may no longer have its count
+        let _ = THREAD_ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+    }
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        Self::count(layout.size());
+        System.alloc(layout)
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        Self::count(layout.size());
+        System.alloc_zeroed(layout)
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        Self::count(new_size);
+        System.realloc(ptr, layout, new_size)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+/// How many allocations, and how many bytes, the counting allocator has
+/// made since the program started. Both are zero when it isn't installed.
+pub fn allocations() -> (usize, usize) {
+    (
+        ALLOCATIONS.load(Ordering::Relaxed),
+        ALLOCATED_BYTES.load(Ordering::Relaxed),
+    )
+}
+
+/// How many allocations the counting allocator has made on the calling
+/// thread, which unlike `allocations` is not disturbed by other threads.
+pub fn thread_allocations() -> usize {
+    THREAD_ALLOCATIONS.with(Cell::get)
+}
+
+/// The bytes held by a population: the nodes themselves, the vectors each
+/// node carries, and whatever `solution_bytes` says each solution holds
+/// on the heap.
+pub fn population_bytes<Solution>(
+    nodes: &[Node<Solution>],
+    solution_bytes: impl Fn(&Solution) -> usize,
+) -> usize {
+    nodes
+        .iter()
+        .map(|node| {
+            size_of::<Node<Solution>>()
+                + (node.case_scores.capacity()
+                    + node.objectives.capacity()
+                    + node.behavior.capacity())
+                    * size_of::<f32>()
+                + node.parents.capacity() * size_of::<usize>()
+                + solution_bytes(&node.solution)
+        })
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[global_allocator]
+    static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+    #[test]
+    fn allocations_are_counted() {
+        let (count, bytes) = allocations();
+        let data = std::hint::black_box(vec![0u8; 1000]);
+        let (after_count, after_bytes) = allocations();
+        assert!(after_count > count && after_bytes >= bytes + 1000);
+
+        let nodes = vec![Node::new(data)];
+        assert_eq!(
+            population_bytes(&nodes, |solution| solution.capacity()),
+            size_of::<Node<Vec<u8>>>() + 1000
+        );
+    }
+}
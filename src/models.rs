@@ -1,5 +1,54 @@
+pub mod accelerator;
 pub mod algen_result;
 pub mod algorithm;
+#[cfg(feature = "std")]
+pub mod alps;
 pub mod analyzer;
+#[cfg(feature = "std")]
+pub mod behavior;
+#[cfg(feature = "std")]
+pub mod cellular;
+#[cfg(feature = "std")]
+pub mod competitive;
+pub mod constraint;
+#[cfg(feature = "std")]
+pub mod cooperative;
+pub mod distance;
+pub mod elite_archive;
+pub mod evaluation;
+pub mod genome;
+pub mod input_provider;
+#[cfg(feature = "std")]
+pub mod islands;
+pub mod layout;
+pub mod lineage;
+pub mod local_search;
+pub mod map_elites;
+#[cfg(feature = "std")]
+pub mod multi_objective;
+#[cfg(feature = "std")]
+pub mod multi_objective_analyzer;
+#[cfg(feature = "network")]
+pub mod network;
+pub mod niching;
 pub mod node;
+#[cfg(feature = "std")]
+pub mod novelty;
+pub mod operator_selection;
+pub mod parallelism;
+#[cfg(feature = "std")]
+pub mod population_store;
+pub mod replacement;
+#[cfg(feature = "std")]
+pub mod scalarization;
+pub mod scaling;
+pub mod schedule;
+pub mod selection;
+pub mod snapshot;
+pub mod speciation;
+pub mod statistics;
+pub mod success_rule;
+pub mod telemetry;
 pub mod test_parameters;
+#[cfg(feature = "std")]
+pub mod wire;
@@ -0,0 +1,32 @@
+use alloc::vec::Vec;
+
+/// A device, such as a GPU, which scores whole generations at once. When
+/// an algorithm provides one, `run_algorithm` encodes the genome of every
+/// node which needs scoring into one buffer and hands it over, rather
+/// than scoring nodes one at a time with the analyzer. The device is fed
+/// through whatever CUDA, wgpu, or OpenCL bindings the implementation
+/// likes.
+///
+/// The device's scores stand in for the analyzer's `evaluate`, but each
+/// node's output is still worked out, within the time limit and the
+/// panic isolation as usual, so that its case scores and constraint
+/// violation come from it the same as any other node's. The device's
+/// time isn't held against any node, and if it panics, every node in
+/// the batch is given the panic penalty.
+pub trait Accelerator<Solution> {
+    /// Append a genome to the buffer sent to the device. Solutions which
+    /// implement `Wire` can simply encode themselves.
+    fn encode(&self, solution: &Solution, out: &mut Vec<u8>);
+
+    /// The most bytes of genomes the device takes at once. Generations
+    /// which don't fit are sent in several batches, each as full as it
+    /// can be, and a genome larger than this is sent on its own.
+    fn capacity(&self) -> usize {
+        usize::MAX
+    }
+
+    /// Score a batch of genomes. `genomes` holds the encoded genomes back
+    /// to back, the genome at `offsets[i]` runs up to the next offset or
+    /// the end of the buffer, and its score goes in `scores[i]`.
+    fn score(&self, genomes: &[u8], offsets: &[usize], scores: &mut [f32]);
+}
@@ -1,8 +1,42 @@
-use super::node::Node;
+use super::{
+    lineage::Lineage, map_elites::QualityDiversity, node::Node, statistics::GenerationStatistics,
+};
+use alloc::vec::Vec;
 
-#[derive(Clone, Copy)]
+#[derive(Clone)]
 pub struct AlgenResult<OutputData, Solution> {
     pub score: f32,
     pub output: Option<OutputData>,
     pub node: Option<Node<Solution>>,
+    /// The final non-dominated set of a multi-objective run. Each node
+    /// carries its solution and objective vector. This is empty for
+    /// single-objective runs, and multi-objective runs leave `score`,
+    /// `output`, and `node` unset because there is no single winner.
+    pub pareto_front: Vec<Node<Solution>>,
+    /// Every elite in the map of a quality-diversity run, one per filled
+    /// cell. Each node carries its behavior alongside its score. For
+    /// competitive coevolution, the hall of fame of past champions, and
+    /// for runs with an elite archive, the archived elites, best first.
+    /// This is empty otherwise.
+    pub archive: Vec<Node<Solution>>,
+    /// The coverage and QD-score of the map after every generation of a
+    /// quality-diversity run, oldest first. This is empty for every
+    /// other runner.
+    pub quality_diversity: Vec<QualityDiversity>,
+    /// The hypervolume of the front after every generation of a
+    /// multi-objective run with a reference point, oldest first. This is
+    /// empty otherwise.
+    pub hypervolume: Vec<f32>,
+    /// Summary statistics of the scores of every generation, oldest
+    /// first. Only `run_algorithm` and its variants measure diversity and
+    /// species; the other single-score runners summarize the scores
+    /// alone, across every island or layer and, for MAP-Elites, across
+    /// the batch of the generation. This is empty for threaded islands,
+    /// whose generations are not in step, and for the multi-objective,
+    /// cooperative, competitive, and preallocated runners.
+    pub history: Vec<GenerationStatistics>,
+    /// The lineage of every node created by a run which tracks lineage,
+    /// oldest first. This is filled by `run_algorithm` and empty
+    /// otherwise.
+    pub genealogy: Vec<Lineage>,
 }
@@ -1,9 +1,27 @@
-use super::{node::Node, test_parameters::TestParameters};
+#[cfg(feature = "std")]
+use std::{
+    collections::hash_map::DefaultHasher,
+    hash::{Hash, Hasher},
+};
+
+use super::{
+    accelerator::Accelerator, constraint::Constraint, distance::Distance,
+    local_search::LocalSearch, node::Node, operator_selection::OperatorBandit,
+    snapshot::SnapshotSink, telemetry::TelemetrySink, test_parameters::TestParameters,
+};
+use alloc::boxed::Box;
 
 /// An algorithm is a structure that represents the problem you are trying
 /// to solve. It has methods for generating a random solution and evaluating
 /// the solution in order to produce an output. Furthermore, it should know
 /// how to recombine two solutions to produce the next generation.
+///
+/// Runners clone solutions whenever a node is kept, such as for elites
+/// and the best node, but otherwise only ever lend them out. For large
+/// solutions like trees or long vectors, use an `Arc` as the solution
+/// so that these copies are cheap, and `Arc::make_mut` to change an
+/// offspring's solution in place, which copies it only when a parent
+/// still shares it.
 pub trait Algorithm<
     InputData: Send + Sync,
     OutputData: Send + Sync,
@@ -48,4 +66,113 @@ pub trait Algorithm<
         right: Node<Solution>,
         params: &TestParameters<FeatureFlags>,
     ) -> Node<Solution>;
+
+    /// Create an offspring from more than two parents, for techniques
+    /// such as diagonal crossover and gene pooling. The runner only
+    /// calls this when `TestParameters::parents` is greater than two. By
+    /// default the first two parents are combined with `combine_node`.
+    fn combine_nodes(
+        &self,
+        parents: &[Node<Solution>],
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<Solution> {
+        self.combine_node(parents[0].clone(), parents[1].clone(), params)
+    }
+
+    /// An optional bandit which picks one of several operators for every
+    /// offspring. When this returns a bandit, offspring are created with
+    /// `combine_with_operator` instead of `combine_node`, and the runner
+    /// rewards each operator with how much its offspring improved on
+    /// their parents. Multi-objective runs have no single score to
+    /// measure that by, so there the bandit picks operators without ever
+    /// learning which are useful.
+    fn operator_selection(&self) -> Option<&OperatorBandit> {
+        None
+    }
+
+    /// Create an offspring using the operator picked by the operator
+    /// bandit, numbered from zero. This only needs to be implemented
+    /// alongside `operator_selection`.
+    fn combine_with_operator(
+        &self,
+        _operator: usize,
+        left: Node<Solution>,
+        right: Node<Solution>,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Node<Solution> {
+        self.combine_node(left, right, params)
+    }
+
+    /// An optional hook which the runner invokes on every new offspring,
+    /// after crossover and mutation but before it is scored. Problems
+    /// with hard structural constraints (permutations, budgets) can use
+    /// this to turn an invalid child into a valid one rather than
+    /// wasting an evaluation on it.
+    fn repair(&self, _solution: &mut Solution, _params: &TestParameters<FeatureFlags>) {}
+
+    /// An optional measure of how different two solutions are. Features
+    /// which need to compare genomes, such as mating restriction, are
+    /// only active when this returns a distance.
+    fn distance(&self) -> Option<&(dyn Distance<Solution> + Sync)> {
+        None
+    }
+
+    /// An optional source of neighbors for local search. Memetic local
+    /// search is only active when this returns a search, and only in the
+    /// runners which use `TestParameters::local_search`.
+    fn local_search(&self) -> Option<&(dyn LocalSearch<Solution> + Sync)> {
+        None
+    }
+
+    /// An optional device which scores whole generations at once. When
+    /// this returns one, `run_algorithm` scores nodes on it rather than
+    /// with the analyzer's `evaluate`.
+    fn accelerator(&self) -> Option<&dyn Accelerator<Solution>> {
+        None
+    }
+
+    /// An optional destination for population snapshots, which
+    /// `run_algorithm`, `run_with_store`, and `run_dynamic` fill every
+    /// `snapshot_interval` generations.
+    fn snapshot_sink(&self) -> Option<&dyn SnapshotSink<Solution>> {
+        None
+    }
+
+    /// An optional destination for the timings of each generation, which
+    /// `run_algorithm`, `run_with_store`, and `run_dynamic` record at the
+    /// end of every generation. The other runners panic when there is one.
+    fn telemetry_sink(&self) -> Option<&dyn TelemetrySink> {
+        None
+    }
+
+    /// How many bytes a solution holds on the heap, which is counted in
+    /// the population's footprint in each generation's telemetry.
+    #[cfg(feature = "memory")]
+    fn solution_bytes(&self, _solution: &Solution) -> usize {
+        0
+    }
+
+    /// An optional hash of a solution, where identical solutions must
+    /// produce the same value. Duplicate detection is only active when
+    /// this returns a value. Solutions which implement `Hash` can simply
+    /// return `Some(fingerprint(solution))`.
+    fn fingerprint(&self, _solution: &Solution) -> Option<u64> {
+        None
+    }
+
+    /// The constraints of the problem. Every runner which scores nodes
+    /// with an analyzer adds the violation of each constraint to the node
+    /// after it has been scored, and penalizes it according to the
+    /// constraint handling in the parameters.
+    fn constraints(&self) -> &[Box<dyn Constraint<Solution, OutputData> + Send + Sync>] {
+        &[]
+    }
+}
+
+/// Hash any value into a fingerprint, for use by `Algorithm::fingerprint`.
+#[cfg(feature = "std")]
+pub fn fingerprint<T: Hash + ?Sized>(value: &T) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    value.hash(&mut hasher);
+    hasher.finish()
 }
@@ -0,0 +1,79 @@
+/// How the maximum age of each ALPS layer grows as you move up the
+/// layers. The limit of a layer is `age_gap` multiplied by the value
+/// of the scheme for that layer.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum AgingScheme {
+    /// 1, 2, 3, 4, 5, ...
+    Linear,
+    /// 1, 2, 4, 9, 16, 25, ...
+    #[default]
+    Polynomial,
+    /// 1, 2, 4, 8, 16, ...
+    Exponential,
+    /// 1, 2, 3, 5, 8, 13, ...
+    Fibonacci,
+}
+
+impl AgingScheme {
+    /// The multiplier for a given layer, starting at layer 0.
+    pub fn multiplier(&self, layer: usize) -> usize {
+        match self {
+            AgingScheme::Linear => layer + 1,
+            AgingScheme::Polynomial => match layer {
+                0 => 1,
+                1 => 2,
+                _ => layer * layer,
+            },
+            AgingScheme::Exponential => 1 << layer.min(usize::BITS as usize - 1),
+            AgingScheme::Fibonacci => {
+                let (mut previous, mut current) = (1usize, 1usize);
+                for _ in 0..layer {
+                    let next = previous.saturating_add(current);
+                    previous = current;
+                    current = next;
+                }
+                current
+            }
+        }
+    }
+}
+
+/// The parameters which control an Age-Layered Population Structure
+/// run. The population is split into layers which each hold
+/// `population / layers` nodes. A node's age counts how many
+/// generations its genetic material has been evolving, and each layer
+/// only holds nodes younger than its age limit. Nodes which grow too old
+/// move up a layer, and every `age_gap` generations the bottom layer is
+/// replaced with freshly allocated nodes.
+#[derive(Clone, Copy, Debug)]
+pub struct AlpsParameters {
+    /// How many age layers to create.
+    pub layers: usize,
+    /// How often, in generations, the bottom layer is replaced.
+    pub age_gap: usize,
+    /// How quickly the age limit of each layer grows.
+    pub aging_scheme: AgingScheme,
+}
+
+impl AlpsParameters {
+    /// The oldest a node may be while living in the given layer. The top
+    /// layer has no limit.
+    pub fn max_age(&self, layer: usize) -> usize {
+        if layer + 1 >= self.layers {
+            return usize::MAX;
+        }
+
+        self.age_gap
+            .saturating_mul(self.aging_scheme.multiplier(layer))
+    }
+}
+
+impl Default for AlpsParameters {
+    fn default() -> Self {
+        AlpsParameters {
+            layers: 5,
+            age_gap: 10,
+            aging_scheme: AgingScheme::default(),
+        }
+    }
+}
@@ -1,8 +1,44 @@
 use super::test_parameters::TestParameters;
+use alloc::vec::Vec;
 
 /// The Analyzer Trait is responsible for taking a set of test data,
 /// the output of an algorithm, and then returning the score of how
 /// well the algorithm did.
 pub trait Analyzer<InputData, OutputData, FeatureFlags> {
     fn evaluate(&self, attempt: &OutputData, params: &TestParameters<FeatureFlags>) -> f32;
+
+    /// Score many outputs at once, writing the score of each attempt to
+    /// the same position in `scores`. This is only used by the batch
+    /// evaluation strategy, which hands over the outputs of a whole
+    /// generation in one contiguous slice. By default every attempt is
+    /// scored with `evaluate`.
+    fn evaluate_batch(
+        &self,
+        attempts: &[OutputData],
+        scores: &mut [f32],
+        params: &TestParameters<FeatureFlags>,
+    ) {
+        for (attempt, score) in attempts.iter().zip(scores.iter_mut()) {
+            *score = self.evaluate(attempt, params);
+        }
+    }
+
+    /// Optionally score each test case individually, where a higher
+    /// value is better. This is required by lexicase selection, which
+    /// picks parents by filtering the population one case at a time.
+    fn evaluate_cases(
+        &self,
+        _attempt: &OutputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Option<Vec<f32>> {
+        None
+    }
+
+    /// Optionally measure how badly an output violates the problem's
+    /// constraints. Zero means the output is feasible, and larger values
+    /// are worse. This is used by constraint aware selection strategies
+    /// such as stochastic ranking.
+    fn violation(&self, _attempt: &OutputData, _params: &TestParameters<FeatureFlags>) -> f32 {
+        0.0
+    }
 }
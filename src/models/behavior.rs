@@ -0,0 +1,61 @@
+/// Describes what a solution does rather than how well it does it. The
+/// output of a solution is mapped to a short vector of numbers, and two
+/// solutions with similar vectors are considered to behave alike.
+///
+/// This is the one integration point for every behavior aware part of
+/// the crate. Novelty search rewards nodes whose behavior is far from
+/// anything seen before, MAP-Elites keeps the best node for each region
+/// of behavior space, and both report how spread out the behaviors of
+/// the population are.
+pub trait BehaviorDescriptor<OutputData> {
+    fn describe(&self, output: &OutputData) -> Vec<f32>;
+
+    /// How far apart two behaviors are. Defaults to the euclidean
+    /// distance, which assumes each dimension is on a similar scale.
+    fn distance(&self, left: &[f32], right: &[f32]) -> f32 {
+        left.iter()
+            .zip(right)
+            .map(|(l, r)| (l - r) * (l - r))
+            .sum::<f32>()
+            .sqrt()
+    }
+
+    /// The mean distance between every pair of behaviors, as a measure
+    /// of how spread out a population is in behavior space.
+    fn diversity(&self, behaviors: &[&[f32]]) -> f32 {
+        let pairs = behaviors.len() * behaviors.len().saturating_sub(1) / 2;
+        if pairs == 0 {
+            return 0.0;
+        }
+
+        let total = behaviors
+            .iter()
+            .enumerate()
+            .flat_map(|(idx, left)| {
+                behaviors[idx + 1..]
+                    .iter()
+                    .map(move |right| self.distance(left, right))
+            })
+            .sum::<f32>();
+
+        total / pairs as f32
+    }
+}
+
+impl<OutputData, F: Fn(&OutputData) -> Vec<f32>> BehaviorDescriptor<OutputData> for F {
+    fn describe(&self, output: &OutputData) -> Vec<f32> {
+        self(output)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn diversity_is_the_mean_pairwise_distance() {
+        let descriptor = |output: &Vec<f32>| output.clone();
+        let behaviors: Vec<&[f32]> = vec![&[0.0, 0.0], &[3.0, 4.0], &[0.0, 0.0]];
+        assert_eq!(descriptor.diversity(&behaviors), 10.0 / 3.0);
+    }
+}
@@ -0,0 +1,108 @@
+/// The shape of the neighborhood around each cell of a cellular
+/// population. The grid wraps around at the edges.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Neighborhood {
+    /// The cell plus its north, south, east, and west neighbors.
+    #[default]
+    VonNeumann,
+    /// The cell plus all eight surrounding neighbors.
+    Moore,
+    /// The cell plus the cells up to `radius` steps away along its row
+    /// and column. A radius of one is the von Neumann neighborhood, and
+    /// a radius of two is the neighborhood often called L9.
+    Linear { radius: usize },
+    /// Every cell in the square of side `2 * radius + 1` centered on the
+    /// cell. A radius of one is the Moore neighborhood, and a radius of
+    /// two is the neighborhood often called C25.
+    Compact { radius: usize },
+    /// Every cell within `radius` steps, moving along rows and columns.
+    /// A radius of two is the neighborhood often called C13.
+    Diamond { radius: usize },
+}
+
+impl Neighborhood {
+    /// The offsets, as `(column, row)`, of every cell in the neighborhood
+    /// relative to the center cell.
+    pub fn offsets(&self) -> Vec<(isize, isize)> {
+        match self {
+            Neighborhood::VonNeumann => vec![(0, 0), (0, -1), (0, 1), (-1, 0), (1, 0)],
+            Neighborhood::Moore => Neighborhood::Compact { radius: 1 }.offsets(),
+            Neighborhood::Linear { radius } => {
+                let radius = *radius as isize;
+                let mut offsets = vec![(0, 0)];
+                for step in 1..=radius {
+                    offsets.extend([(0, -step), (0, step), (-step, 0), (step, 0)]);
+                }
+                offsets
+            }
+            Neighborhood::Compact { radius } => {
+                let radius = *radius as isize;
+                (-radius..=radius)
+                    .flat_map(|row| (-radius..=radius).map(move |column| (column, row)))
+                    .collect()
+            }
+            Neighborhood::Diamond { radius } => Neighborhood::Compact { radius: *radius }
+                .offsets()
+                .into_iter()
+                .filter(|(column, row)| column.abs() + row.abs() <= *radius as isize)
+                .collect(),
+        }
+    }
+}
+
+/// Which cell an offspring competes for in a cellular population.
+/// Replacement never reaches beyond the neighborhood of the cell which
+/// bred the offspring.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum LocalReplacement {
+    /// The offspring replaces the cell which bred it if it scores at
+    /// least as well.
+    #[default]
+    IfNotWorse,
+    /// The offspring always replaces the cell which bred it.
+    Always,
+    /// The offspring replaces the worst node in the neighborhood if it
+    /// scores at least as well. When several offspring compete for the
+    /// same cell, the best of them wins.
+    WorstNeighbor,
+}
+
+/// The parameters which describe the grid of a cellular run. The
+/// population size is `width * height`, which takes the place of
+/// `population` in the test parameters.
+#[derive(Clone, Copy, Debug)]
+pub struct CellularParameters {
+    pub width: usize,
+    pub height: usize,
+    pub neighborhood: Neighborhood,
+    pub replacement: LocalReplacement,
+}
+
+impl CellularParameters {
+    /// The index of every cell in the neighborhood of the given cell.
+    pub fn neighbors(&self, cell: usize) -> Vec<usize> {
+        let (column, row) = ((cell % self.width) as isize, (cell / self.width) as isize);
+        let (width, height) = (self.width as isize, self.height as isize);
+
+        self.neighborhood
+            .offsets()
+            .into_iter()
+            .map(|(dx, dy)| {
+                let x = (column + dx).rem_euclid(width);
+                let y = (row + dy).rem_euclid(height);
+                (y * width + x) as usize
+            })
+            .collect()
+    }
+}
+
+impl Default for CellularParameters {
+    fn default() -> Self {
+        CellularParameters {
+            width: 32,
+            height: 32,
+            neighborhood: Neighborhood::default(),
+            replacement: LocalReplacement::default(),
+        }
+    }
+}
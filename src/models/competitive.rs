@@ -0,0 +1,42 @@
+use super::test_parameters::TestParameters;
+
+/// An interaction between a member of each of two competing
+/// populations, such as a game between two players or a test case
+/// against a program. Populations are called hosts and parasites, but
+/// the game needn't be lopsided.
+pub trait Game<HostOutput, ParasiteOutput, FeatureFlags> {
+    /// Play the host against the parasite and return the payoff of each,
+    /// host first, where higher is better.
+    fn play(
+        &self,
+        host: &HostOutput,
+        parasite: &ParasiteOutput,
+        params: &TestParameters<FeatureFlags>,
+    ) -> (f32, f32);
+}
+
+/// The parameters which control competitive coevolution.
+#[derive(Clone, Copy, Debug)]
+pub struct CompetitiveParameters {
+    /// How many opponents, sampled from the current generation of the
+    /// other population, each node plays.
+    pub opponents: usize,
+    /// How many former champions of the other population each node also
+    /// plays. Playing against the past stops the populations from
+    /// chasing each other in circles.
+    pub archive_opponents: usize,
+    /// How many champions each population's hall of fame holds. The
+    /// champion of every generation is added and, once the archive is
+    /// full, replaces the oldest.
+    pub archive_size: usize,
+}
+
+impl Default for CompetitiveParameters {
+    fn default() -> Self {
+        CompetitiveParameters {
+            opponents: 5,
+            archive_opponents: 2,
+            archive_size: 50,
+        }
+    }
+}
@@ -0,0 +1,37 @@
+/// A constraint on the problem being solved. Constraints are checked by
+/// the runner after each node is scored, and the total violation is
+/// stored on the node and handled according to the constraint handling
+/// strategy in the test parameters.
+pub trait Constraint<Solution, OutputData> {
+    /// How badly the solution violates this constraint. Zero means the
+    /// constraint is satisfied, and larger values are worse.
+    fn violation(&self, solution: &Solution, output: &OutputData) -> f32;
+}
+
+/// How the runner treats nodes which violate constraints.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum ConstraintHandling {
+    /// Violations are recorded on the node but do not affect its score.
+    #[default]
+    None,
+    /// The score is reduced by `weight * violation`.
+    StaticPenalty { weight: f32 },
+    /// The score is reduced by `weight * violation`, where the weight
+    /// adapts over time. If the best node of each of the last `window`
+    /// generations was feasible, the weight is divided by `decrease`. If
+    /// they were all infeasible, the weight is multiplied by `increase`.
+    /// Only `run_algorithm` adapts the weight, and the other runners keep
+    /// penalizing with `initial_weight`.
+    AdaptivePenalty {
+        initial_weight: f32,
+        increase: f32,
+        decrease: f32,
+        window: usize,
+    },
+    /// Infeasible nodes receive the worst possible score.
+    DeathPenalty,
+    /// Deb's feasibility rules. When comparing two nodes, a feasible node
+    /// beats an infeasible one, two infeasible nodes are compared by
+    /// violation, and two feasible nodes are compared by score.
+    FeasibilityFirst,
+}
@@ -0,0 +1,51 @@
+use super::test_parameters::TestParameters;
+
+/// A problem split into components for cooperative coevolution. Each
+/// component is evolved in its own subpopulation, and a complete
+/// solution is assembled from one component of every subpopulation.
+///
+/// Components are usually slices of a long vector, such as a block of
+/// the variables of a high dimensional function, but they can be any
+/// parts which are only meaningful together.
+pub trait Decomposition<InputData, OutputData, Component, FeatureFlags> {
+    /// How many components make up a complete solution.
+    fn components(&self) -> usize;
+
+    /// Allocate a random value for the given component.
+    fn allocate_component(
+        &self,
+        component: usize,
+        input: &InputData,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Component;
+
+    /// Create an offspring of two values of the given component, with
+    /// crossover and mutation.
+    fn combine_components(
+        &self,
+        component: usize,
+        left: &Component,
+        right: &Component,
+        params: &TestParameters<FeatureFlags>,
+    ) -> Component;
+
+    /// Assemble a complete solution, with one value for each component
+    /// in order, and produce its output for the analyzer.
+    fn output(
+        &self,
+        components: &[Component],
+        input: &InputData,
+        params: &TestParameters<FeatureFlags>,
+    ) -> OutputData;
+}
+
+/// The parameters which control cooperative coevolution.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct CooperativeParameters {
+    /// How many random collaborators from each other subpopulation a
+    /// node is also evaluated with, on top of their best members. A node
+    /// keeps the best score of all its collaborations. More collaborators
+    /// give a fairer view of components which interact strongly, at the
+    /// cost of more evaluations.
+    pub random_collaborators: usize,
+}
@@ -0,0 +1,155 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::float::Float;
+use alloc::{vec, vec::Vec};
+
+/// A measure of how different two solutions are. Zero means the two
+/// solutions are identical and larger values mean they are further
+/// apart in genotype space. This is used by the diversity preserving
+/// parts of the runner, such as niching, crowding, and mating
+/// restriction, and by its diversity telemetry.
+///
+/// Common metrics are provided: `Hamming`, `Euclidean`, `Manhattan`,
+/// and `KendallTau` for permutations.
+pub trait Distance<Solution> {
+    fn distance(&self, left: &Solution, right: &Solution) -> f32;
+
+    /// The mean distance between every pair of solutions, which is a
+    /// measure of how diverse a population is.
+    fn diversity(&self, solutions: &[&Solution]) -> f32 {
+        let mut total = 0.0;
+        let mut pairs = 0;
+        for (idx, left) in solutions.iter().enumerate() {
+            for right in &solutions[idx + 1..] {
+                total += self.distance(left, right);
+                pairs += 1;
+            }
+        }
+
+        if pairs == 0 {
+            0.0
+        } else {
+            total / pairs as f32
+        }
+    }
+}
+
+impl<Solution, F: Fn(&Solution, &Solution) -> f32> Distance<Solution> for F {
+    fn distance(&self, left: &Solution, right: &Solution) -> f32 {
+        self(left, right)
+    }
+}
+
+/// The number of positions at which two sequences differ.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Hamming;
+
+impl<Gene: PartialEq> Distance<Vec<Gene>> for Hamming {
+    fn distance(&self, left: &Vec<Gene>, right: &Vec<Gene>) -> f32 {
+        let differences = left.iter().zip(right).filter(|(l, r)| l != r).count();
+        (differences + left.len().abs_diff(right.len())) as f32
+    }
+}
+
+/// The straight line distance between two vectors.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Euclidean;
+
+impl Distance<Vec<f64>> for Euclidean {
+    fn distance(&self, left: &Vec<f64>, right: &Vec<f64>) -> f32 {
+        left.iter()
+            .zip(right)
+            .map(|(l, r)| (l - r) * (l - r))
+            .sum::<f64>()
+            .sqrt() as f32
+    }
+}
+
+impl Distance<Vec<f32>> for Euclidean {
+    fn distance(&self, left: &Vec<f32>, right: &Vec<f32>) -> f32 {
+        left.iter()
+            .zip(right)
+            .map(|(l, r)| (l - r) * (l - r))
+            .sum::<f32>()
+            .sqrt()
+    }
+}
+
+/// The sum of the absolute differences between two vectors.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Manhattan;
+
+impl Distance<Vec<i64>> for Manhattan {
+    fn distance(&self, left: &Vec<i64>, right: &Vec<i64>) -> f32 {
+        left.iter()
+            .zip(right)
+            .map(|(l, r)| l.abs_diff(*r))
+            .sum::<u64>() as f32
+    }
+}
+
+impl Distance<Vec<f64>> for Manhattan {
+    fn distance(&self, left: &Vec<f64>, right: &Vec<f64>) -> f32 {
+        left.iter()
+            .zip(right)
+            .map(|(l, r)| (l - r).abs())
+            .sum::<f64>() as f32
+    }
+}
+
+/// The Kendall tau distance between two permutations of `0..n`, which
+/// is the number of pairs of values the two permutations put in a
+/// different order. Equivalently, the number of adjacent swaps needed
+/// to turn one into the other.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct KendallTau;
+
+impl Distance<Vec<usize>> for KendallTau {
+    fn distance(&self, left: &Vec<usize>, right: &Vec<usize>) -> f32 {
+        // Where each value sits in the right permutation
+        let mut position = vec![0; right.len()];
+        for (idx, value) in right.iter().enumerate() {
+            if let Some(slot) = position.get_mut(*value) {
+                *slot = idx;
+            }
+        }
+
+        let ranks: Vec<usize> = left
+            .iter()
+            .map(|value| position.get(*value).copied().unwrap_or(0))
+            .collect();
+        let mut discordant = 0;
+        for (idx, rank) in ranks.iter().enumerate() {
+            discordant += ranks[idx + 1..]
+                .iter()
+                .filter(|other| *other < rank)
+                .count();
+        }
+        discordant as f32
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn built_in_metrics() {
+        assert_eq!(Hamming.distance(&vec![1, 2, 3], &vec![1, 0, 3, 4]), 2.0);
+        assert_eq!(Euclidean.distance(&vec![0.0, 0.0], &vec![3.0, 4.0]), 5.0);
+        assert_eq!(Manhattan.distance(&vec![0i64, 5], &vec![3, 1]), 7.0);
+        assert_eq!(KendallTau.distance(&vec![0, 1, 2], &vec![0, 1, 2]), 0.0);
+        assert_eq!(KendallTau.distance(&vec![0, 1, 2], &vec![2, 1, 0]), 3.0);
+        assert_eq!(
+            KendallTau.distance(&vec![0, 1, 2, 3], &vec![1, 0, 2, 3]),
+            1.0
+        );
+    }
+
+    #[test]
+    fn diversity_is_the_mean_pairwise_distance() {
+        let solutions = [vec![0.0], vec![1.0], vec![3.0]];
+        let refs: Vec<&Vec<f64>> = solutions.iter().collect();
+        assert_eq!(Euclidean.diversity(&refs), 2.0);
+        assert_eq!(Euclidean.diversity(&refs[..1]), 0.0);
+    }
+}
@@ -0,0 +1,31 @@
+/// Configuration for an elite archive kept apart from the breeding
+/// population. The best distinct nodes ever seen are archived, so good
+/// solutions are never lost, but they only rejoin the population every
+/// `reinsertion_interval` generations rather than being copied into it
+/// every generation. This keeps strong elitism from dominating
+/// selection.
+///
+/// Nodes are told apart by the algorithm's fingerprint. Without one, a
+/// node which ties the score of an archived node is taken to be the
+/// same node.
+#[derive(Clone, Copy, Debug)]
+pub struct EliteArchive {
+    /// How many elites the archive holds.
+    pub size: usize,
+    /// How many generations pass between reinsertions, or zero to never
+    /// reinsert and only keep the archive for reference.
+    pub reinsertion_interval: usize,
+    /// How many of the best archived elites replace random nodes of the
+    /// population at each reinsertion.
+    pub reinsertions: usize,
+}
+
+impl Default for EliteArchive {
+    fn default() -> Self {
+        EliteArchive {
+            size: 10,
+            reinsertion_interval: 10,
+            reinsertions: 1,
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use core::time::Duration;
+
+/// How the runner spreads evaluations across threads.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Evaluation {
+    /// Evaluate with rayon's parallel iterators. This has the least
+    /// overhead, and works well when every evaluation costs about the
+    /// same.
+    #[default]
+    Parallel,
+    /// Evaluate with rayon's parallel iterators, but hand each task a
+    /// batch of nodes rather than one. When evaluations are very cheap,
+    /// this keeps the cost of scheduling tasks from outweighing the
+    /// evaluations themselves.
+    Chunked {
+        /// How many nodes each task evaluates, or zero to pick a size
+        /// which gives every thread a few batches.
+        chunk_size: usize,
+    },
+    /// Evaluate from a shared queue, where each worker takes the next
+    /// node as soon as it is free. This balances the load when some
+    /// evaluations take far longer than others.
+    Queue {
+        /// How many threads evaluate at once, or zero for one per rayon
+        /// thread.
+        workers: usize,
+        /// When true, each node records how long its evaluation took,
+        /// and the nodes which took longest last time are queued first
+        /// so that no slow evaluation is left to run alone at the end.
+        /// Nodes which haven't been timed yet are queued before all
+        /// others.
+        record_durations: bool,
+    },
+    /// Work out every node's outputs in parallel, then hand all of the
+    /// outputs which need scoring to the analyzer's `evaluate_batch` in
+    /// one contiguous slice. This suits analyzers over numeric outputs
+    /// which score a whole generation at once with SIMD or ndarray.
+    ///
+    /// Each node's outputs are worked out within the time limit and the
+    /// panic isolation as usual, but the analyzer scores the batch as a
+    /// whole. Its time isn't held against any node, and if it panics,
+    /// every node in the batch is given the panic penalty.
+    Batch,
+}
+
+/// A time limit on scoring each node.
+#[derive(Clone, Copy, Debug)]
+pub struct EvaluationTimeout {
+    /// How long a node's output and score may take.
+    pub limit: Duration,
+    /// The score given to a node which took longer, in place of whatever
+    /// it scored.
+    pub penalty: f32,
+}
+
+/// How many batches each thread gets when the chunk size is picked
+/// automatically, so that threads which finish early can take more.
+const BATCHES_PER_THREAD: usize = 4;
+
+impl Evaluation {
+    /// The fewest nodes a parallel task evaluates, out of `nodes` nodes
+    /// spread across `threads` threads.
+    pub fn chunk_size(&self, nodes: usize, threads: usize) -> usize {
+        match self {
+            Evaluation::Chunked { chunk_size: 0 } => {
+                (nodes / (threads.max(1) * BATCHES_PER_THREAD)).max(1)
+            }
+            Evaluation::Chunked { chunk_size } => *chunk_size,
+            _ => 1,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn chunks_are_sized_for_the_threads() {
+        assert_eq!(Evaluation::Parallel.chunk_size(1000, 8), 1);
+        assert_eq!(
+            Evaluation::Chunked { chunk_size: 50 }.chunk_size(1000, 8),
+            50
+        );
+        assert_eq!(
+            Evaluation::Chunked { chunk_size: 0 }.chunk_size(1000, 8),
+            31
+        );
+        assert_eq!(Evaluation::Chunked { chunk_size: 0 }.chunk_size(10, 8), 1);
+    }
+}
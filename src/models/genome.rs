@@ -0,0 +1,40 @@
+use rand::Rng;
+
+#[cfg(feature = "derive")]
+pub use algen_derive::Genome;
+
+/// A chromosome which knows how to create, recombine, and mutate itself.
+/// This is usually derived with `#[derive(Genome)]` (behind the `derive`
+/// feature) and then run with `genomes::derived::GenomeAlgorithm`, which
+/// turns it into a complete algorithm.
+pub trait Genome: Clone + Send + Sync {
+    /// Create a random genome.
+    fn random<R: Rng + ?Sized>(rng: &mut R) -> Self;
+
+    /// Create a child which takes each gene from `self` with probability
+    /// `bias`, and from `other` otherwise.
+    fn crossover<R: Rng + ?Sized>(&self, other: &Self, bias: f32, rng: &mut R) -> Self;
+
+    /// Change each gene with probability `rate`.
+    fn mutate<R: Rng + ?Sized>(&mut self, rate: f32, rng: &mut R);
+}
+
+impl Genome for bool {
+    fn random<R: Rng + ?Sized>(rng: &mut R) -> Self {
+        rng.gen()
+    }
+
+    fn crossover<R: Rng + ?Sized>(&self, other: &Self, bias: f32, rng: &mut R) -> Self {
+        if rng.gen::<f32>() < bias {
+            *self
+        } else {
+            *other
+        }
+    }
+
+    fn mutate<R: Rng + ?Sized>(&mut self, rate: f32, rng: &mut R) {
+        if rng.gen::<f32>() < rate {
+            *self = !*self;
+        }
+    }
+}
@@ -0,0 +1,35 @@
+/// A source of input data for problems which change as the run goes on,
+/// such as tracking a moving target or trading on live prices. Any
+/// `Fn(usize) -> Option<InputData>` closure is a provider.
+pub trait InputProvider<InputData> {
+    /// The input for `generation`, or `None` when it is the same as the
+    /// previous generation's. This is asked before every generation after
+    /// the first, which uses the input the run was started with.
+    fn input(&self, generation: usize) -> Option<InputData>;
+}
+
+impl<InputData, F: Fn(usize) -> Option<InputData>> InputProvider<InputData> for F {
+    fn input(&self, generation: usize) -> Option<InputData> {
+        self(generation)
+    }
+}
+
+/// Which nodes are scored again when the input changes. The best node
+/// found so far is always scored again, and the fitness cache and the
+/// elite archive are always forgotten, since none of them mean anything
+/// on the new input.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Rescoring {
+    /// Every node scored on the old input, including offspring which
+    /// won their place under crowding or a restricted tournament.
+    #[default]
+    All,
+    /// Only the nodes which survived from an earlier generation: the
+    /// elites of generational replacement, and every incumbent which held
+    /// its place under crowding or a restricted tournament. Offspring
+    /// which won their place on the old input keep their scores, which
+    /// saves evaluations when the input only drifts a little at a time.
+    /// Generational replacement scores its offspring afresh anyway, so
+    /// there this rescores the same nodes as `All`.
+    Elites,
+}
@@ -0,0 +1,149 @@
+/// Which islands send migrants to which. Every island sends a copy of
+/// its emigrants to each of its neighbors.
+#[derive(Clone, Debug, Default)]
+pub enum Topology {
+    /// Each island sends to the next one, and the last sends to the
+    /// first.
+    #[default]
+    Ring,
+    /// Each island sends to every other island.
+    FullyConnected,
+    /// The islands are laid out in rows of `width` on a wrapping grid,
+    /// and each island sends to the islands above, below, left, and
+    /// right of it.
+    Grid { width: usize },
+    /// The neighbors of each island, by index.
+    Custom(Vec<Vec<usize>>),
+}
+
+impl Topology {
+    /// The islands which receive migrants from the given island, out of
+    /// `islands` islands in total. An island is never its own neighbor.
+    pub fn neighbors(&self, island: usize, islands: usize) -> Vec<usize> {
+        let mut neighbors = match self {
+            Topology::Ring => vec![(island + 1) % islands.max(1)],
+            Topology::FullyConnected => (0..islands).collect(),
+            Topology::Grid { width } => {
+                let width = (*width).clamp(1, islands.max(1));
+                let height = islands.div_ceil(width);
+                let (row, column) = (island / width, island % width);
+                vec![
+                    ((row + height - 1) % height) * width + column,
+                    ((row + 1) % height) * width + column,
+                    row * width + (column + width - 1) % width,
+                    row * width + (column + 1) % width,
+                ]
+            }
+            Topology::Custom(adjacency) => adjacency.get(island).cloned().unwrap_or_default(),
+        };
+
+        neighbors.sort_unstable();
+        neighbors.dedup();
+        neighbors.retain(|neighbor| *neighbor != island && *neighbor < islands);
+        neighbors
+    }
+}
+
+/// How the nodes which leave an island are chosen.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum EmigrantSelection {
+    /// The best nodes of the island.
+    #[default]
+    Best,
+    /// Nodes picked at random.
+    Random,
+    /// The winners of tournaments of the given size.
+    Tournament { size: usize },
+}
+
+/// Which nodes of an island are replaced by arriving migrants.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum ImmigrantReplacement {
+    /// The worst nodes of the island.
+    #[default]
+    Worst,
+    /// Nodes picked at random.
+    Random,
+}
+
+/// How the islands are run.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum IslandExecution {
+    /// Every island advances one generation at a time, together, inside
+    /// the rayon pool. Migration happens between generations, so runs
+    /// are as repeatable as the rest of the crate.
+    #[default]
+    LockStep,
+    /// Every island runs on its own thread and migrants are exchanged
+    /// through channels without waiting, so a slow island never stalls
+    /// the others. Islands may drift apart in generation count, but each
+    /// island keeps its own run state, so parameter schedules follow that
+    /// island's own generation. On WebAssembly, which can't spawn
+    /// threads, this falls back to lock step.
+    Threaded,
+}
+
+/// The parameters which control an island model run. The population is
+/// split into `islands` sub-populations of `population / islands` nodes
+/// which evolve independently. Every `migration_interval` generations,
+/// nodes emigrate from each island to its neighbors in the topology.
+#[derive(Clone, Debug)]
+pub struct IslandParameters {
+    /// How many islands to create.
+    pub islands: usize,
+    /// How often, in generations, nodes migrate between islands.
+    pub migration_interval: usize,
+    /// A number between 0 - 1 which indicates the share of each island
+    /// which emigrates during a migration. At least one node always
+    /// migrates.
+    pub migration_rate: f32,
+    /// Which islands send migrants to which.
+    pub topology: Topology,
+    /// How the nodes which leave an island are chosen.
+    pub emigrants: EmigrantSelection,
+    /// Which nodes are replaced by arriving migrants.
+    pub immigrants: ImmigrantReplacement,
+    /// Whether islands advance together or on their own threads.
+    pub execution: IslandExecution,
+}
+
+impl IslandParameters {
+    /// How many nodes leave an island of the given size per migration.
+    pub fn migrants(&self, island_size: usize) -> usize {
+        ((self.migration_rate.clamp(0.0, 1.0) * island_size as f32).round() as usize)
+            .clamp(1, island_size.max(1))
+    }
+}
+
+impl Default for IslandParameters {
+    fn default() -> Self {
+        IslandParameters {
+            islands: 4,
+            migration_interval: 10,
+            migration_rate: 0.1,
+            topology: Topology::default(),
+            emigrants: EmigrantSelection::default(),
+            immigrants: ImmigrantReplacement::default(),
+            execution: IslandExecution::default(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn topologies_connect_the_right_islands() {
+        assert_eq!(Topology::Ring.neighbors(3, 4), vec![0]);
+        assert_eq!(Topology::FullyConnected.neighbors(1, 4), vec![0, 2, 3]);
+        // 0 1 2
+        // 3 4 5
+        assert_eq!(Topology::Grid { width: 3 }.neighbors(4, 6), vec![1, 3, 5]);
+        assert_eq!(Topology::Grid { width: 3 }.neighbors(0, 6), vec![1, 2, 3]);
+        assert_eq!(
+            Topology::Custom(vec![vec![1, 1, 0, 7], vec![]]).neighbors(0, 2),
+            vec![1]
+        );
+    }
+}
@@ -0,0 +1,13 @@
+/// How the runner lays out the population while it ranks nodes and
+/// picks parents.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum PopulationLayout {
+    /// Nodes are compared in place. This suits most populations.
+    #[default]
+    Nodes,
+    /// The scores and violations are copied into columns of their own,
+    /// and ranking and tournaments only touch those columns. This keeps
+    /// ranking and selection in cache for populations in the hundreds of
+    /// thousands, or with large nodes.
+    Columns,
+}
@@ -0,0 +1,128 @@
+use alloc::{
+    collections::{BTreeMap, BTreeSet},
+    vec,
+    vec::Vec,
+};
+#[cfg(feature = "std")]
+use std::io::{self, Write};
+
+/// Where a node came from. A lineage is recorded for every node a run
+/// creates when lineage tracking is enabled, so the ancestry of any node
+/// can be followed back through its parents' records to the initial
+/// population.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Lineage {
+    pub id: usize,
+    /// The ids of the node's parents, which is empty for nodes which
+    /// were allocated rather than bred.
+    pub parents: Vec<usize>,
+    /// The generation in which the node was first evaluated.
+    pub birth_generation: usize,
+    /// The operator which created the node, when operators are picked by
+    /// an operator bandit.
+    pub operator: Option<usize>,
+    /// The score the node earned when it was first evaluated.
+    pub score: f32,
+}
+
+/// The lineage of a node and of all its ancestors which appear in the
+/// genealogy, oldest first.
+pub fn ancestry(genealogy: &[Lineage], id: usize) -> Vec<&Lineage> {
+    let by_id: BTreeMap<usize, &Lineage> = genealogy
+        .iter()
+        .map(|lineage| (lineage.id, lineage))
+        .collect();
+
+    let mut seen = BTreeSet::new();
+    let mut pending = vec![id];
+    let mut ancestry = Vec::new();
+    while let Some(id) = pending.pop() {
+        if !seen.insert(id) {
+            continue;
+        }
+        if let Some(lineage) = by_id.get(&id) {
+            pending.extend(&lineage.parents);
+            ancestry.push(*lineage);
+        }
+    }
+
+    ancestry.sort_by_key(|lineage| (lineage.birth_generation, lineage.id));
+    ancestry
+}
+
+/// Write lineages as a Graphviz DOT digraph, with an edge from every
+/// parent to its child. Each node is labelled with its id, birth
+/// generation, and score, and edges are labelled with the operator
+/// which bred the child when one is known. Pass `ancestry` of the best
+/// node to draw where it came from, or the whole genealogy to draw the
+/// run.
+#[cfg(feature = "std")]
+pub fn write_dot<'a>(
+    lineages: impl IntoIterator<Item = &'a Lineage>,
+    out: &mut impl Write,
+) -> io::Result<()> {
+    let lineages: Vec<&Lineage> = lineages.into_iter().collect();
+    let ids: BTreeSet<usize> = lineages.iter().map(|lineage| lineage.id).collect();
+
+    writeln!(out, "digraph genealogy {{")?;
+    writeln!(out, "    rankdir=TB;")?;
+    writeln!(out, "    node [shape=box];")?;
+    for lineage in &lineages {
+        writeln!(
+            out,
+            "    n{} [label=\"#{}\\ngeneration {}\\nscore {}\"];",
+            lineage.id, lineage.id, lineage.birth_generation, lineage.score
+        )?;
+    }
+    for lineage in &lineages {
+        for parent in lineage.parents.iter().filter(|parent| ids.contains(parent)) {
+            match lineage.operator {
+                Some(operator) => writeln!(
+                    out,
+                    "    n{} -> n{} [label=\"op {}\"];",
+                    parent, lineage.id, operator
+                )?,
+                None => writeln!(out, "    n{} -> n{};", parent, lineage.id)?,
+            }
+        }
+    }
+    writeln!(out, "}}")
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    fn lineage(id: usize, parents: Vec<usize>, birth_generation: usize) -> Lineage {
+        Lineage {
+            id,
+            parents,
+            birth_generation,
+            operator: None,
+            score: id as f32,
+        }
+    }
+
+    #[test]
+    fn ancestry_is_drawn_as_a_graph() {
+        let genealogy = vec![
+            lineage(1, vec![], 0),
+            lineage(2, vec![], 0),
+            lineage(3, vec![], 0),
+            lineage(4, vec![1, 2], 1),
+            lineage(5, vec![4, 4], 2),
+        ];
+
+        let ancestry = ancestry(&genealogy, 5);
+        let ids: Vec<usize> = ancestry.iter().map(|lineage| lineage.id).collect();
+        assert_eq!(ids, vec![1, 2, 4, 5]);
+
+        let mut dot = Vec::new();
+        write_dot(ancestry, &mut dot).unwrap();
+        let dot = String::from_utf8(dot).unwrap();
+        assert!(dot.starts_with("digraph genealogy {"));
+        assert!(dot.contains("n1 -> n4;"));
+        assert!(dot.contains("n4 -> n5;"));
+        assert!(!dot.contains("n3"));
+    }
+}
@@ -0,0 +1,50 @@
+/// A source of small moves for local search. Given a solution, propose a
+/// neighbor which differs from it only slightly, such as one flipped bit
+/// or one nudged number. The runner keeps the neighbor when it scores
+/// better than the solution it came from.
+pub trait LocalSearch<Solution> {
+    fn neighbor(&self, solution: &Solution) -> Solution;
+}
+
+impl<Solution, F: Fn(&Solution) -> Solution> LocalSearch<Solution> for F {
+    fn neighbor(&self, solution: &Solution) -> Solution {
+        self(solution)
+    }
+}
+
+/// What happens to an offspring once local search has improved it.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Learning {
+    /// The improved genome replaces the offspring's genome, so what was
+    /// learned is inherited by its own offspring.
+    #[default]
+    Lamarckian,
+    /// The offspring keeps its genome but is scored as if it were the
+    /// improved one. Learning guides selection without being inherited,
+    /// which keeps more diversity in the population.
+    Baldwinian,
+}
+
+/// Configuration for turning the runner into a memetic algorithm. A
+/// share of the offspring hill-climb for a few steps before they are
+/// scored. Requires the algorithm to provide a local search.
+#[derive(Clone, Copy, Debug)]
+pub struct LocalSearchParameters {
+    /// A number between 0 - 1 which indicates the probability of an
+    /// offspring being improved by local search.
+    pub probability: f32,
+    /// How many neighbors are tried for each improved offspring.
+    pub steps: usize,
+    /// Whether the improvements are written back into the genome.
+    pub learning: Learning,
+}
+
+impl Default for LocalSearchParameters {
+    fn default() -> Self {
+        LocalSearchParameters {
+            probability: 0.1,
+            steps: 10,
+            learning: Learning::default(),
+        }
+    }
+}
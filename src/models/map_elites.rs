@@ -0,0 +1,76 @@
+use alloc::vec::Vec;
+
+/// One axis of a MAP-Elites feature map. Behaviors are clamped into
+/// `min..=max` and that range is split into `bins` equally sized cells.
+#[derive(Clone, Copy, Debug)]
+pub struct FeatureDimension {
+    pub min: f32,
+    pub max: f32,
+    pub bins: usize,
+}
+
+impl FeatureDimension {
+    /// The bin a single behavior value falls into.
+    pub fn bin(&self, value: f32) -> usize {
+        let bins = self.bins.max(1);
+        let span = self.max - self.min;
+        if span <= 0.0 || value.is_nan() {
+            return 0;
+        }
+
+        let fraction = ((value - self.min) / span).clamp(0.0, 1.0);
+        ((fraction * bins as f32) as usize).min(bins - 1)
+    }
+}
+
+impl Default for FeatureDimension {
+    fn default() -> Self {
+        FeatureDimension {
+            min: 0.0,
+            max: 1.0,
+            bins: 10,
+        }
+    }
+}
+
+/// The parameters which describe the feature map of a MAP-Elites run.
+/// There is one dimension per value returned by the behavior descriptor,
+/// and the map holds one elite for every combination of bins.
+#[derive(Clone, Debug, Default)]
+pub struct MapElitesParameters {
+    pub dimensions: Vec<FeatureDimension>,
+}
+
+impl MapElitesParameters {
+    /// How many cells the map holds in total.
+    pub fn cells(&self) -> usize {
+        self.dimensions
+            .iter()
+            .map(|dimension| dimension.bins.max(1))
+            .product()
+    }
+
+    /// The index of the cell which a behavior maps to. Missing values are
+    /// treated as falling into the first bin, and extra values are
+    /// ignored.
+    pub fn cell(&self, behavior: &[f32]) -> usize {
+        self.dimensions
+            .iter()
+            .enumerate()
+            .fold(0, |cell, (idx, dimension)| {
+                let value = behavior.get(idx).copied().unwrap_or(dimension.min);
+                cell * dimension.bins.max(1) + dimension.bin(value)
+            })
+    }
+}
+
+/// How well a MAP-Elites run has illuminated its feature map.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct QualityDiversity {
+    /// The fraction of cells which hold an elite, from 0 to 1.
+    pub coverage: f32,
+    /// The sum of the scores of every elite. This grows both when new
+    /// cells are filled and when existing elites are improved, so it
+    /// assumes scores are non-negative.
+    pub qd_score: f32,
+}
@@ -0,0 +1,45 @@
+/// The algorithm used by the multi-objective runner to decide which
+/// nodes survive each generation.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum MultiObjectiveStrategy {
+    /// NSGA-II. Survivors are picked by non-domination rank and then by
+    /// crowding distance.
+    #[default]
+    Nsga2,
+    /// NSGA-III. Survivors are picked by non-domination rank and then by
+    /// niching around reference directions spread evenly over the
+    /// objective space, with `divisions` steps along each objective. This
+    /// is intended for problems with four or more objectives, where
+    /// crowding distance stops telling nodes apart.
+    Nsga3 { divisions: usize },
+    /// SPEA2. An external archive of `archive_size` nodes is kept
+    /// alongside the population. Nodes are scored by how many nodes
+    /// dominate them (weighted by strength) plus a density estimate, the
+    /// archive is filled with the non-dominated nodes and truncated by
+    /// removing the most crowded ones, and parents are only ever picked
+    /// from the archive.
+    Spea2 { archive_size: usize },
+    /// Lexicographic ordering. The objectives have a strict priority
+    /// order: nodes are compared on the first objective, ties are broken
+    /// by the second objective, and so on. Feasible nodes always come
+    /// before infeasible ones.
+    Lexicographic,
+}
+
+/// The configuration for a multi-objective run.
+#[derive(Clone, Debug, Default)]
+pub struct MultiObjectiveParameters {
+    pub strategy: MultiObjectiveStrategy,
+    /// When set, every feasible node evaluated during the run is offered
+    /// to an epsilon-dominance archive with this box size, and the
+    /// archive is returned as the front. The archive keeps at most one
+    /// node per box of objective space, so its size stays bounded while
+    /// still spreading over the whole front. The objectives should be on
+    /// similar scales for a single box size to make sense.
+    pub epsilon: Option<f32>,
+    /// When set, the hypervolume of the front is measured against this
+    /// point after every generation. It needs one value per objective,
+    /// and each should be worse than any value you care about, since
+    /// nodes which do not beat it on every objective add no volume.
+    pub reference_point: Option<Vec<f32>>,
+}
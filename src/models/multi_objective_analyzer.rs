@@ -0,0 +1,19 @@
+use super::test_parameters::TestParameters;
+
+/// The MultiObjectiveAnalyzer Trait is the multi-objective counterpart
+/// of the Analyzer. Rather than collapsing how well the algorithm did
+/// into a single number, it returns one score per objective. Higher is
+/// better for every objective, and every call should return a vector of
+/// the same length.
+///
+/// Multi-objective runners keep the full vector on each node in
+/// `Node::objectives`.
+pub trait MultiObjectiveAnalyzer<InputData, OutputData, FeatureFlags> {
+    fn evaluate(&self, attempt: &OutputData, params: &TestParameters<FeatureFlags>) -> Vec<f32>;
+
+    /// Optionally measure how badly an output violates the problem's
+    /// constraints. Zero means the output is feasible.
+    fn violation(&self, _attempt: &OutputData, _params: &TestParameters<FeatureFlags>) -> f32 {
+        0.0
+    }
+}
@@ -0,0 +1,33 @@
+//! The settings for islands which live in separate processes. Migrants
+//! travel between processes as `wire` frames, where each payload is a
+//! `Vec` of migrant nodes, each with its id, age, score, and solution.
+use std::{net::SocketAddr, time::Duration};
+
+/// Where a networked island listens and which islands it sends to.
+#[derive(Clone, Debug)]
+pub struct NetworkParameters {
+    /// The address this island accepts migrants on.
+    pub listen: SocketAddr,
+    /// The islands which receive this island's emigrants. Peers which
+    /// are not up yet, or have already finished, are skipped and tried
+    /// again at the next migration.
+    pub peers: Vec<SocketAddr>,
+    /// How long to wait when connecting to a peer.
+    pub connect_timeout: Duration,
+    /// How long a frame may take to be sent, or to arrive once it has
+    /// started. A peer which stalls for longer is disconnected, and the
+    /// migrants it was sending are dropped.
+    pub frame_timeout: Duration,
+}
+
+impl NetworkParameters {
+    /// Listen on `listen` and send emigrants to every one of `peers`.
+    pub fn new(listen: SocketAddr, peers: Vec<SocketAddr>) -> Self {
+        NetworkParameters {
+            listen,
+            peers,
+            connect_timeout: Duration::from_secs(1),
+            frame_timeout: Duration::from_secs(5),
+        }
+    }
+}
@@ -0,0 +1,27 @@
+/// Configuration for fitness sharing. Each node's score is divided by
+/// its niche count, which is the sum of the sharing function over every
+/// node in the population:
+///
+/// `sh(d) = 1 - (d / radius) ^ alpha` when `d < radius`, otherwise `0`
+///
+/// Nodes which are crowded together in genotype space end up sharing
+/// their fitness, which keeps several peaks of a multimodal problem
+/// alive. Requires the algorithm to provide a distance and assumes
+/// scores are non-negative.
+#[derive(Clone, Copy, Debug)]
+pub struct FitnessSharing {
+    /// The distance within which two nodes are considered to be in the
+    /// same niche.
+    pub radius: f32,
+    /// The shape of the sharing function. 1.0 gives a triangular kernel.
+    pub alpha: f32,
+}
+
+impl Default for FitnessSharing {
+    fn default() -> Self {
+        FitnessSharing {
+            radius: 1.0,
+            alpha: 1.0,
+        }
+    }
+}
@@ -1,9 +1,119 @@
+use alloc::vec::Vec;
+use core::time::Duration;
+
 /// A node is a wrapper that contains one specific
 /// solution for an experiment that was created
 /// during a genetic run.
-#[derive(Copy, Clone)]
+#[derive(Clone)]
 pub struct Node<Solution> {
+    /// A unique number for the node. `run_algorithm` numbers every node
+    /// from one upwards as it first evaluates it, so algorithms should
+    /// leave this at zero; offspring are reset to zero when bred.
     pub id: usize,
     pub solution: Solution,
     pub score: f32,
+    /// Whether `score`, `case_scores`, and `violation` already hold this
+    /// node's evaluation. `run_algorithm` skips evaluating such nodes, so
+    /// elites carried over unchanged are not scored again. Breeding clears
+    /// this; anything else which changes a node's solution should too.
+    pub evaluated: bool,
+    /// The score of each individual test case, if the analyzer
+    /// provides them. This is populated by the runner and used by
+    /// case-based selection strategies such as lexicase.
+    pub case_scores: Vec<f32>,
+    /// The score of each objective, for nodes evaluated by a
+    /// multi-objective analyzer. Higher is better for every objective.
+    pub objectives: Vec<f32>,
+    /// How badly this solution violates the problem's constraints, where
+    /// zero means the solution is feasible. This is populated by the
+    /// runner from the analyzer.
+    pub violation: f32,
+    /// A description of what this solution does, as produced by a
+    /// behavior descriptor. This is only populated by behavior aware
+    /// runners such as novelty search and MAP-Elites.
+    pub behavior: Vec<f32>,
+    /// How many generations this node has been around. `run_algorithm`
+    /// counts the generations the node has survived, starting from zero
+    /// when it is born. ALPS instead counts how long the node's genetic
+    /// material has been evolving, and offspring inherit the age of
+    /// their oldest parent.
+    pub age: usize,
+    /// The better score of this node's parents, or `f32::MIN` for nodes
+    /// which were allocated rather than bred.
+    pub parent_score: f32,
+    /// The operator which created this node, when the algorithm picks
+    /// operators through an operator bandit. This is cleared once the
+    /// operator has been credited with the node's score.
+    pub operator: Option<usize>,
+    /// The score this node earned through Baldwinian local search, which
+    /// stands in for the score of its genome whenever it is evaluated.
+    pub learned_score: Option<f32>,
+    /// How long this node's last evaluation took, when the runner's
+    /// evaluation queue records durations.
+    pub evaluation_time: Option<Duration>,
+    /// The ids of the nodes this node was bred from. This is only
+    /// recorded when the run tracks lineage.
+    pub parents: Vec<usize>,
+    /// The generation in which this node was first evaluated. This is
+    /// only recorded when the run tracks lineage.
+    pub birth_generation: usize,
+}
+
+impl<Solution> Node<Solution> {
+    /// Create a new, unscored node which wraps the given solution.
+    pub fn new(solution: Solution) -> Self {
+        Node {
+            id: 0,
+            solution,
+            score: f32::MIN,
+            evaluated: false,
+            case_scores: Vec::new(),
+            objectives: Vec::new(),
+            violation: 0.0,
+            behavior: Vec::new(),
+            age: 0,
+            parent_score: f32::MIN,
+            operator: None,
+            learned_score: None,
+            evaluation_time: None,
+            parents: Vec::new(),
+            birth_generation: 0,
+        }
+    }
+
+    /// Whether this node Pareto-dominates another node, which means it
+    /// is at least as good on every objective and strictly better on at
+    /// least one of them.
+    pub fn dominates(&self, other: &Node<Solution>) -> bool {
+        let mut strictly_better = false;
+        for (mine, theirs) in self.objectives.iter().zip(other.objectives.iter()) {
+            if mine < theirs {
+                return false;
+            }
+            if mine > theirs {
+                strictly_better = true;
+            }
+        }
+
+        strictly_better
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn node(objectives: &[f32]) -> Node<()> {
+        Node {
+            objectives: objectives.to_vec(),
+            ..Node::new(())
+        }
+    }
+
+    #[test]
+    fn dominance_requires_one_strict_improvement() {
+        assert!(node(&[2.0, 1.0]).dominates(&node(&[1.0, 1.0])));
+        assert!(!node(&[1.0, 1.0]).dominates(&node(&[1.0, 1.0])));
+        assert!(!node(&[2.0, 0.0]).dominates(&node(&[1.0, 1.0])));
+    }
 }
@@ -0,0 +1,24 @@
+/// Configuration for novelty search. A node's novelty is the mean
+/// distance between its behavior and the behaviors of its nearest
+/// neighbors, drawn from the current population and the archive of
+/// behaviors seen in earlier generations.
+#[derive(Clone, Copy, Debug)]
+pub struct NoveltyParameters {
+    /// How many nearest neighbors are averaged to compute novelty.
+    pub neighbors: usize,
+    /// The chance that each node's behavior is added to the archive.
+    pub archive_probability: f32,
+    /// The most behaviors the archive will hold. Once full, the oldest
+    /// behaviors are dropped first.
+    pub archive_size: usize,
+}
+
+impl Default for NoveltyParameters {
+    fn default() -> Self {
+        NoveltyParameters {
+            neighbors: 15,
+            archive_probability: 0.05,
+            archive_size: 1000,
+        }
+    }
+}
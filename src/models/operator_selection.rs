@@ -0,0 +1,196 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::float::Float;
+use crate::sync::Mutex;
+use alloc::{vec, vec::Vec};
+use rand::prelude::*;
+
+/// The rule an operator bandit uses to share offspring between the
+/// operators it manages.
+#[derive(Clone, Copy, Debug)]
+pub enum OperatorSelection {
+    /// Upper confidence bound (UCB1). Every operator is tried once, and
+    /// after that the operator with the best mean reward plus an
+    /// exploration bonus is used. Mean rewards are normalized against
+    /// the best operator so that `exploration` does not depend on the
+    /// scale of the scores.
+    Ucb { exploration: f32 },
+    /// Probability matching. Each operator is picked with a probability
+    /// proportional to its recent reward, but never less than
+    /// `min_probability`. `adaptation_rate` is a number between 0 - 1
+    /// which controls how quickly old rewards are forgotten.
+    ProbabilityMatching {
+        min_probability: f32,
+        adaptation_rate: f32,
+    },
+}
+
+impl Default for OperatorSelection {
+    fn default() -> Self {
+        OperatorSelection::Ucb {
+            exploration: core::f32::consts::SQRT_2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+struct Arm {
+    selections: usize,
+    rewards: usize,
+    quality: f32,
+}
+
+/// A multi-armed bandit which decides which of several recombination
+/// operators creates the next offspring. The runner rewards an operator
+/// with the improvement of each offspring it created over the better of
+/// that offspring's parents, so operators which stop paying off are used
+/// less as the run goes on.
+///
+/// An algorithm opts in by returning a bandit from
+/// `Algorithm::operator_selection` and implementing
+/// `Algorithm::combine_with_operator`.
+pub struct OperatorBandit {
+    strategy: OperatorSelection,
+    arms: Mutex<Vec<Arm>>,
+}
+
+impl OperatorBandit {
+    /// A bandit which chooses between `operators` operators, numbered
+    /// from zero.
+    pub fn new(operators: usize, strategy: OperatorSelection) -> Self {
+        OperatorBandit {
+            strategy,
+            arms: Mutex::new(vec![Arm::default(); operators.max(1)]),
+        }
+    }
+
+    /// How many operators this bandit chooses between.
+    pub fn operators(&self) -> usize {
+        self.arms.lock().unwrap().len()
+    }
+
+    /// Pick the operator which should create the next offspring.
+    pub fn select(&self) -> usize {
+        let mut arms = self.arms.lock().unwrap();
+        let operator = match self.strategy {
+            OperatorSelection::Ucb { exploration } => ucb(&arms, exploration),
+            OperatorSelection::ProbabilityMatching {
+                min_probability, ..
+            } => {
+                let probabilities = matching_probabilities(&arms, min_probability);
+                let mut pick: f32 = crate::random::rng().gen();
+                probabilities
+                    .iter()
+                    .position(|probability| {
+                        pick -= probability;
+                        pick < 0.0
+                    })
+                    .unwrap_or(arms.len() - 1)
+            }
+        };
+
+        arms[operator].selections += 1;
+        operator
+    }
+
+    /// Credit an operator with the improvement of an offspring it
+    /// created. Negative improvements count as no reward at all.
+    pub fn reward(&self, operator: usize, improvement: f32) {
+        let mut arms = self.arms.lock().unwrap();
+        let Some(arm) = arms.get_mut(operator) else {
+            return;
+        };
+
+        let reward = improvement.max(0.0);
+        arm.rewards += 1;
+        arm.quality += match self.strategy {
+            OperatorSelection::Ucb { .. } => (reward - arm.quality) / arm.rewards as f32,
+            OperatorSelection::ProbabilityMatching {
+                adaptation_rate, ..
+            } => adaptation_rate * (reward - arm.quality),
+        };
+    }
+
+    /// The share of offspring each operator has created so far.
+    pub fn usage(&self) -> Vec<f32> {
+        let arms = self.arms.lock().unwrap();
+        let total = arms.iter().map(|arm| arm.selections).sum::<usize>().max(1);
+        arms.iter()
+            .map(|arm| arm.selections as f32 / total as f32)
+            .collect()
+    }
+
+    /// How many offspring each operator has been credited for so far.
+    pub fn rewards(&self) -> Vec<usize> {
+        let arms = self.arms.lock().unwrap();
+        arms.iter().map(|arm| arm.rewards).collect()
+    }
+}
+
+fn ucb(arms: &[Arm], exploration: f32) -> usize {
+    if let Some(untried) = arms.iter().position(|arm| arm.selections == 0) {
+        return untried;
+    }
+
+    let best_quality = arms.iter().map(|arm| arm.quality).fold(0.0, f32::max);
+    let total = arms.iter().map(|arm| arm.selections).sum::<usize>() as f32;
+    let value = |arm: &Arm| {
+        let quality = if best_quality > 0.0 {
+            arm.quality / best_quality
+        } else {
+            0.0
+        };
+        quality + exploration * (total.ln() / arm.selections as f32).sqrt()
+    };
+
+    arms.iter()
+        .enumerate()
+        .max_by(|(_, left), (_, right)| value(left).total_cmp(&value(right)))
+        .map(|(idx, _)| idx)
+        .unwrap_or(0)
+}
+
+fn matching_probabilities(arms: &[Arm], min_probability: f32) -> Vec<f32> {
+    let count = arms.len() as f32;
+    let floor = min_probability.clamp(0.0, 1.0 / count);
+    let total: f32 = arms.iter().map(|arm| arm.quality).sum();
+    arms.iter()
+        .map(|arm| {
+            let share = if total > 0.0 {
+                arm.quality / total
+            } else {
+                1.0 / count
+            };
+            floor + (1.0 - count * floor) * share
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn favours_the_rewarded_operator(strategy: OperatorSelection) {
+        let bandit = OperatorBandit::new(3, strategy);
+        for _ in 0..1000 {
+            let operator = bandit.select();
+            bandit.reward(operator, if operator == 1 { 1.0 } else { -1.0 });
+        }
+
+        let usage = bandit.usage();
+        assert!(usage[1] > usage[0] && usage[1] > usage[2]);
+        assert!(usage[0] > 0.0 && usage[2] > 0.0);
+    }
+
+    #[test]
+    fn ucb_favours_the_rewarded_operator() {
+        favours_the_rewarded_operator(OperatorSelection::default());
+    }
+
+    #[test]
+    fn probability_matching_favours_the_rewarded_operator() {
+        favours_the_rewarded_operator(OperatorSelection::ProbabilityMatching {
+            min_probability: 0.05,
+            adaptation_rate: 0.3,
+        });
+    }
+}
@@ -0,0 +1,15 @@
+/// How many threads a run spreads its work across.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Parallelism {
+    /// Use the run's thread pool if it has one, or rayon's global pool.
+    #[default]
+    Automatic,
+    /// Use a pool of its own with this many threads, built once when
+    /// the run starts.
+    Threads(usize),
+    /// Do everything on a single thread. This avoids the overhead of
+    /// spreading work for small problems, and keeps a run off every
+    /// other thread. Building without the `parallel` feature makes every
+    /// run sequential and drops the rayon dependency altogether.
+    Sequential,
+}
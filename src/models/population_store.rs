@@ -0,0 +1,26 @@
+use super::node::Node;
+use std::io;
+
+/// Where a run keeps a copy of its population. When a run is given a
+/// store, the population is written to it at the end of every
+/// generation, along with the generation it goes on from, so other
+/// processes may inspect it. A run which starts with a non-empty store
+/// resumes from it, at that generation, rather than allocating a new
+/// population. The run itself carries on with its own population and
+/// only reads the store when it starts.
+///
+/// Stores needn't keep every field of a node. Those which encode nodes
+/// with `Wire` keep just the id, age, score, and solution, so a resumed
+/// run evaluates its population again.
+///
+/// Ready made stores live in the `stores` module.
+pub trait PopulationStore<Solution> {
+    /// Replace the stored population with `nodes`, which a resumed run
+    /// goes on from at `generation`.
+    fn save(&self, generation: usize, nodes: &[Node<Solution>]) -> io::Result<()>;
+
+    /// Read the stored population and the generation it goes on from,
+    /// which are generation zero and no nodes if nothing has been saved
+    /// yet.
+    fn load(&self) -> io::Result<(usize, Vec<Node<Solution>>)>;
+}
@@ -0,0 +1,22 @@
+/// The strategy used to decide which nodes make it into the next
+/// generation.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Replacement {
+    /// The elites are copied over and the rest of the next generation is
+    /// filled with offspring of the selected parents.
+    #[default]
+    Generational,
+    /// Deterministic crowding. The population is randomly paired up, each
+    /// pair produces two offspring, and each offspring competes against
+    /// its most similar parent for a place in the next generation. This
+    /// uses the algorithm's distance when provided, otherwise each child
+    /// competes against the parent in the same position.
+    DeterministicCrowding,
+    /// Restricted tournament selection. Offspring are bred from the
+    /// selected parents as usual, then each offspring competes against
+    /// the most similar node out of `window` random members of the
+    /// population, replacing it if the offspring scores at least as well.
+    /// Without a distance the offspring competes against a random member
+    /// of the window instead.
+    RestrictedTournament { window: usize },
+}
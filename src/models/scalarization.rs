@@ -0,0 +1,102 @@
+use super::{
+    analyzer::Analyzer, multi_objective_analyzer::MultiObjectiveAnalyzer,
+    test_parameters::TestParameters,
+};
+
+/// An adapter which turns a multi-objective analyzer into a plain
+/// analyzer by taking the weighted sum of its objectives, so it can be
+/// used with `run_algorithm`. Objectives without a weight are ignored.
+pub struct WeightedSum<A> {
+    pub analyzer: A,
+    pub weights: Vec<f32>,
+}
+
+impl<InputData, OutputData, FeatureFlags, A> Analyzer<InputData, OutputData, FeatureFlags>
+    for WeightedSum<A>
+where
+    A: MultiObjectiveAnalyzer<InputData, OutputData, FeatureFlags>,
+{
+    fn evaluate(&self, attempt: &OutputData, params: &TestParameters<FeatureFlags>) -> f32 {
+        self.analyzer
+            .evaluate(attempt, params)
+            .iter()
+            .zip(self.weights.iter())
+            .map(|(objective, weight)| objective * weight)
+            .sum()
+    }
+
+    fn violation(&self, attempt: &OutputData, params: &TestParameters<FeatureFlags>) -> f32 {
+        self.analyzer.violation(attempt, params)
+    }
+}
+
+/// An adapter which turns a multi-objective analyzer into a plain
+/// analyzer using the weighted Chebyshev (Tchebycheff) distance to an
+/// ideal point. The score is the negated largest weighted shortfall,
+/// `-max(weight * (ideal - objective))`, so the best possible score is
+/// zero. Unlike a weighted sum, this can reach solutions on concave
+/// parts of the Pareto front.
+pub struct WeightedChebyshev<A> {
+    pub analyzer: A,
+    pub weights: Vec<f32>,
+    /// The best value each objective could possibly reach.
+    pub ideal: Vec<f32>,
+}
+
+impl<InputData, OutputData, FeatureFlags, A> Analyzer<InputData, OutputData, FeatureFlags>
+    for WeightedChebyshev<A>
+where
+    A: MultiObjectiveAnalyzer<InputData, OutputData, FeatureFlags>,
+{
+    fn evaluate(&self, attempt: &OutputData, params: &TestParameters<FeatureFlags>) -> f32 {
+        let shortfall = self
+            .analyzer
+            .evaluate(attempt, params)
+            .iter()
+            .zip(self.weights.iter())
+            .zip(self.ideal.iter())
+            .map(|((objective, weight), ideal)| weight * (ideal - objective).abs())
+            .fold(0.0, f32::max);
+
+        -shortfall
+    }
+
+    fn violation(&self, attempt: &OutputData, params: &TestParameters<FeatureFlags>) -> f32 {
+        self.analyzer.violation(attempt, params)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Identity {}
+    impl MultiObjectiveAnalyzer<(), Vec<f32>, ()> for Identity {
+        fn evaluate(&self, attempt: &Vec<f32>, _params: &TestParameters<()>) -> Vec<f32> {
+            attempt.clone()
+        }
+    }
+
+    #[test]
+    fn weighted_sum_combines_objectives() {
+        let analyzer = WeightedSum {
+            analyzer: Identity {},
+            weights: vec![1.0, 0.5],
+        };
+
+        let score = analyzer.evaluate(&vec![2.0, 4.0], &TestParameters::default());
+        assert_eq!(score, 4.0);
+    }
+
+    #[test]
+    fn chebyshev_scores_the_worst_objective() {
+        let analyzer = WeightedChebyshev {
+            analyzer: Identity {},
+            weights: vec![1.0, 1.0],
+            ideal: vec![10.0, 10.0],
+        };
+
+        let score = analyzer.evaluate(&vec![9.0, 6.0], &TestParameters::default());
+        assert_eq!(score, -4.0);
+    }
+}
@@ -0,0 +1,19 @@
+/// A transform applied to every node's score after evaluation and
+/// before selection. Scaling keeps selection pressure reasonable when
+/// raw scores bunch up, which matters most for roulette selection.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Scaling {
+    /// Scores are used exactly as the analyzer returned them.
+    #[default]
+    None,
+    /// Sigma scaling. Each score is replaced with
+    /// `1 + (score - mean) / (2 * std_dev)`, floored at 0.1. When every
+    /// node has the same score they all scale to 1.
+    Sigma,
+    /// Goldberg's linear scaling. Scores are mapped through `a * score + b`
+    /// so that the average score is unchanged and the best score becomes
+    /// `pressure` times the average (1.2 - 2.0 is typical). If that would
+    /// push the worst score below zero, the mapping is instead chosen so
+    /// the worst score becomes zero. Assumes non-negative raw scores.
+    Linear { pressure: f32 },
+}
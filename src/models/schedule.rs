@@ -0,0 +1,204 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::float::Float;
+use core::sync::atomic::{AtomicU32, AtomicUsize, Ordering};
+#[cfg(feature = "parallel")]
+use rayon::ThreadPool;
+#[cfg(feature = "parallel")]
+use std::sync::Arc;
+
+/// A schedule describes how a numeric parameter changes over the
+/// course of a run. It is evaluated once per generation by the runner.
+#[derive(Clone, Copy, Debug)]
+pub enum Schedule {
+    /// The value never changes.
+    Constant(f32),
+    /// The value moves in a straight line from `start` (at the first
+    /// generation) to `end` (at the last generation).
+    Linear { start: f32, end: f32 },
+    /// The value begins at `start` and is multiplied by `rate` every
+    /// generation. A rate between 0 - 1 produces a decay.
+    Exponential { start: f32, rate: f32 },
+    /// The value begins at `start` and is multiplied by `factor` once
+    /// every `every` generations.
+    Step {
+        start: f32,
+        factor: f32,
+        every: usize,
+    },
+}
+
+impl Schedule {
+    /// Compute the value of this schedule for a given generation, out of
+    /// the total amount of generations in the test.
+    pub fn value(&self, generation: usize, generations: usize) -> f32 {
+        match *self {
+            Schedule::Constant(value) => value,
+            Schedule::Linear { start, end } => {
+                if generations <= 1 {
+                    return start;
+                }
+
+                let progress = generation as f32 / (generations - 1) as f32;
+                start + (end - start) * progress.min(1.0)
+            }
+            Schedule::Exponential { start, rate } => start * rate.powi(generation as i32),
+            Schedule::Step {
+                start,
+                factor,
+                every,
+            } => start * factor.powi((generation / every.max(1)) as i32),
+        }
+    }
+}
+
+/// Schedules for the test parameters which may change over a run. Any
+/// parameter without a schedule keeps the value set on the
+/// `TestParameters`. Scheduled values are read through
+/// `TestParameters::current_mutation_factor` and its siblings, which
+/// the built-in genomes already do.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ParameterSchedules {
+    pub mutation_factor: Option<Schedule>,
+    pub crossover_factor: Option<Schedule>,
+    /// The tournament size is rounded to the nearest whole number and is
+    /// never less than one.
+    pub tournament_size: Option<Schedule>,
+}
+
+/// The parts of a run which change as it goes, such as the current
+/// generation, along with the threads the run works on. Runners keep
+/// this up to date so that it can be read from inside `combine_node`,
+/// which only has access to the parameters. Each run has its own, in the
+/// copy of the parameters the runner works on.
+#[derive(Debug)]
+pub struct RunState {
+    generation: AtomicUsize,
+    mutation_scale: AtomicU32,
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    last_id: AtomicUsize,
+    /// The threads the run works on, when it doesn't use the global pool.
+    #[cfg(feature = "parallel")]
+    pool: Option<Arc<ThreadPool>>,
+}
+
+impl Default for RunState {
+    fn default() -> Self {
+        RunState {
+            generation: AtomicUsize::new(0),
+            mutation_scale: AtomicU32::new(1.0f32.to_bits()),
+            last_id: AtomicUsize::new(0),
+            #[cfg(feature = "parallel")]
+            pool: None,
+        }
+    }
+}
+
+impl RunState {
+    /// The generation the runner is currently working on.
+    pub fn generation(&self) -> usize {
+        self.generation.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn set_generation(&self, generation: usize) {
+        self.generation.store(generation, Ordering::Relaxed);
+    }
+
+    /// The multiplier the success rule currently applies to mutation.
+    /// This is always 1 when no success rule is set.
+    pub fn mutation_scale(&self) -> f32 {
+        f32::from_bits(self.mutation_scale.load(Ordering::Relaxed))
+    }
+
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) fn set_mutation_scale(&self, scale: f32) {
+        self.mutation_scale
+            .store(scale.to_bits(), Ordering::Relaxed);
+    }
+
+    /// A node id which hasn't been handed out before. Ids start at one,
+    /// so zero always means a node has no id yet.
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) fn next_id(&self) -> usize {
+        self.last_id.fetch_add(1, Ordering::Relaxed) + 1
+    }
+
+    /// Carry on numbering nodes after `last_id`, for a run which resumes
+    /// with nodes numbered by an earlier one.
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) fn resume_ids(&self, last_id: usize) {
+        self.last_id.fetch_max(last_id, Ordering::Relaxed);
+    }
+
+    /// A fresh state for a run which works on `pool`.
+    #[cfg(feature = "parallel")]
+    pub(crate) fn with_pool(pool: Option<Arc<ThreadPool>>) -> Self {
+        RunState {
+            pool,
+            ..Default::default()
+        }
+    }
+
+    /// A fresh state for another part of the same run, which works on
+    /// the same threads.
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) fn fresh(&self) -> Self {
+        RunState {
+            #[cfg(feature = "parallel")]
+            pool: self.pool.clone(),
+            ..Default::default()
+        }
+    }
+
+    #[cfg(feature = "parallel")]
+    pub(crate) fn pool(&self) -> Option<&Arc<ThreadPool>> {
+        self.pool.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::test_parameters::TestParameters;
+
+    #[test]
+    fn step_schedules_change_every_few_generations() {
+        let schedule = Schedule::Step {
+            start: 1.0,
+            factor: 0.5,
+            every: 10,
+        };
+
+        assert_eq!(schedule.value(0, 100), 1.0);
+        assert_eq!(schedule.value(9, 100), 1.0);
+        assert_eq!(schedule.value(10, 100), 0.5);
+        assert_eq!(schedule.value(25, 100), 0.25);
+    }
+
+    #[test]
+    fn parameters_follow_their_schedules() {
+        let params = TestParameters::<()> {
+            generations: 11,
+            mutation_factor: 0.3,
+            schedules: ParameterSchedules {
+                crossover_factor: Some(Schedule::Linear {
+                    start: 0.5,
+                    end: 0.0,
+                }),
+                tournament_size: Some(Schedule::Linear {
+                    start: 2.0,
+                    end: 12.0,
+                }),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        params.state.set_generation(10);
+        assert_eq!(params.current_mutation_factor(), 0.3);
+        assert_eq!(params.current_crossover_factor(), 0.0);
+        assert_eq!(params.current_tournament_size(), 12);
+
+        params.state.set_generation(5);
+        assert_eq!(params.current_tournament_size(), 7);
+    }
+}
@@ -0,0 +1,38 @@
+use super::schedule::Schedule;
+
+/// The strategy used to pick parents when creating the next generation.
+#[derive(Clone, Copy, Debug, Default)]
+pub enum Selection {
+    /// Pick `tournament_size` random nodes and keep the best one.
+    #[default]
+    Tournament,
+    /// Boltzmann (softmax) selection. Each node is picked with a probability
+    /// proportional to `exp(score / temperature)`. The temperature is
+    /// evaluated every generation, so a decaying schedule will gradually
+    /// move the run from exploration to exploitation.
+    Boltzmann { temperature: Schedule },
+    /// Roulette wheel (fitness proportionate) selection. Each node is
+    /// picked with a probability proportional to its score. Negative
+    /// scores are treated as zero, so this pairs well with scaling.
+    Roulette,
+    /// Truncation selection. Parents are picked uniformly at random from
+    /// the top `fraction` (a number between 0 - 1) of the population.
+    Truncation { fraction: f32 },
+    /// Lexicase selection. The test cases are shuffled and the population
+    /// is filtered down to the nodes which scored best on each case in
+    /// turn, until one node remains. Requires the analyzer to implement
+    /// `evaluate_cases`, otherwise tournament selection is used.
+    Lexicase,
+    /// Epsilon-lexicase selection. This behaves like lexicase selection,
+    /// except a node survives a case if it is within epsilon of the best
+    /// score on that case. Epsilon is computed automatically for every case
+    /// as the median absolute deviation of the population's case scores,
+    /// which makes it suitable for real-valued errors.
+    EpsilonLexicase,
+    /// Stochastic ranking for constrained problems. The population is
+    /// ranked with a bubble sort where adjacent nodes are compared by
+    /// score if both are feasible (or with `probability`, typically 0.45),
+    /// and by constraint violation otherwise. Parents are then picked
+    /// uniformly from the top `fraction` of the ranking.
+    StochasticRanking { probability: f32, fraction: f32 },
+}
@@ -0,0 +1,23 @@
+use super::node::Node;
+
+/// The whole population of one generation, as captured for offline
+/// analysis. Every node carries its solution along with its raw score,
+/// case scores, and violation, before any sharing or scaling.
+#[derive(Clone, Copy)]
+pub struct Snapshot<'a, Solution> {
+    pub generation: usize,
+    pub nodes: &'a [Node<Solution>],
+}
+
+/// Somewhere to send population snapshots, such as a file or a plotting
+/// tool. Any `Fn(&Snapshot<Solution>)` closure is a sink, so snapshots
+/// can also be handed straight to a callback.
+pub trait SnapshotSink<Solution> {
+    fn record(&self, snapshot: &Snapshot<Solution>);
+}
+
+impl<Solution, F: Fn(&Snapshot<Solution>)> SnapshotSink<Solution> for F {
+    fn record(&self, snapshot: &Snapshot<Solution>) {
+        self(snapshot)
+    }
+}
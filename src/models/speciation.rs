@@ -0,0 +1,27 @@
+/// Configuration for NEAT style speciation. Every generation, each node
+/// joins the first species whose representative is within `threshold`
+/// of it, or founds a new species. Scores are then shared within each
+/// species, so that a large species cannot crowd out new ideas, and any
+/// species which has gone `stagnation` generations without improving is
+/// culled. Requires the algorithm to provide a distance, which plays the
+/// part of NEAT's compatibility distance, and assumes scores are
+/// non-negative.
+#[derive(Clone, Copy, Debug)]
+pub struct Speciation {
+    /// The largest compatibility distance at which a node still belongs
+    /// to a species.
+    pub threshold: f32,
+    /// How many generations a species may go without improving its best
+    /// score before it is culled. The species holding the best node is
+    /// never culled.
+    pub stagnation: usize,
+}
+
+impl Default for Speciation {
+    fn default() -> Self {
+        Speciation {
+            threshold: 3.0,
+            stagnation: 15,
+        }
+    }
+}
@@ -0,0 +1,109 @@
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::float::Float;
+use crate::{
+    math::statistics::{mean, quantile, standard_deviation},
+    parallel::*,
+};
+use alloc::vec::Vec;
+
+/// A summary of the raw scores and the diversity of one generation,
+/// taken before any sharing or scaling. Diversity collapse is the most
+/// common reason for a run to stall, so it is worth watching alongside
+/// the scores.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct GenerationStatistics {
+    pub generation: usize,
+    pub best: f32,
+    pub worst: f32,
+    pub mean: f32,
+    pub median: f32,
+    pub standard_deviation: f32,
+    /// The 25th percentile.
+    pub lower_quartile: f32,
+    /// The 75th percentile.
+    pub upper_quartile: f32,
+    /// The population variance of the scores.
+    pub score_variance: f32,
+    /// The Shannon entropy, in bits, of the genotypes in the population,
+    /// when the algorithm provides a fingerprint. This falls towards
+    /// zero as the population fills with copies of a few genomes.
+    pub genotype_entropy: Option<f32>,
+    /// The mean distance between pairs of genomes, when the algorithm
+    /// provides a distance. Large populations are estimated from a
+    /// random sample of `DIVERSITY_SAMPLE` nodes.
+    pub mean_distance: Option<f32>,
+    /// Every species alive this generation when speciation is active, or
+    /// every niche when fitness sharing is. Niches are found the same way
+    /// as species, using the sharing radius as the threshold. A shrinking
+    /// list means niches are collapsing.
+    pub species: Vec<SpeciesStatistics>,
+}
+
+/// The state of one species, or niche, in one generation.
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct SpeciesStatistics {
+    /// Identifies the species across generations.
+    pub id: usize,
+    /// How many nodes belong to the species.
+    pub size: usize,
+    /// The best score in the species, before speciation shares it.
+    pub best: f32,
+    /// How many generations the species has existed.
+    pub age: usize,
+    /// How many generations since the species last improved its best
+    /// score.
+    pub stagnant: usize,
+}
+
+/// The most nodes compared pairwise when measuring `mean_distance`.
+pub const DIVERSITY_SAMPLE: usize = 64;
+
+impl GenerationStatistics {
+    /// Summarize the scores of a generation.
+    pub fn new(generation: usize, scores: &[f32]) -> Self {
+        let mut sorted = scores.to_vec();
+        // Populations can be huge, so the scores are sorted in parallel
+        sorted.par_sort_unstable_by(|left, right| left.total_cmp(right));
+        GenerationStatistics {
+            generation,
+            best: sorted.last().copied().unwrap_or(0.0),
+            worst: sorted.first().copied().unwrap_or(0.0),
+            mean: mean(&sorted),
+            median: quantile(&sorted, 0.5),
+            standard_deviation: standard_deviation(&sorted),
+            lower_quartile: quantile(&sorted, 0.25),
+            upper_quartile: quantile(&sorted, 0.75),
+            score_variance: standard_deviation(&sorted).powi(2),
+            genotype_entropy: None,
+            mean_distance: None,
+            species: Vec::new(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statistics_of_a_known_set() {
+        let statistics = GenerationStatistics::new(3, &[9.0, 2.0, 4.0, 4.0, 5.0, 5.0, 7.0, 4.0]);
+        assert_eq!(
+            statistics,
+            GenerationStatistics {
+                generation: 3,
+                best: 9.0,
+                worst: 2.0,
+                mean: 5.0,
+                median: 4.5,
+                standard_deviation: 2.0,
+                lower_quartile: 4.0,
+                upper_quartile: 5.5,
+                score_variance: 4.0,
+                genotype_entropy: None,
+                mean_distance: None,
+                species: Vec::new(),
+            }
+        );
+    }
+}
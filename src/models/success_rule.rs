@@ -0,0 +1,71 @@
+/// Rechenberg's 1/5th success rule. After every generation the runner
+/// measures the fraction of offspring which scored better than the
+/// better of their parents. When more than `target` of them succeeded
+/// the search is being too timid and the mutation scale grows, and when
+/// fewer succeeded it is being too bold and the scale shrinks.
+///
+/// The scale multiplies `TestParameters::current_mutation_factor`, and
+/// is available from `TestParameters::state` for algorithms which would
+/// rather scale a step size.
+#[derive(Clone, Copy, Debug)]
+pub struct SuccessRule {
+    /// The success rate the rule steers towards.
+    pub target: f32,
+    /// A number between 0 - 1. The scale is multiplied by this when too
+    /// few offspring succeed, and divided by it when too many do.
+    pub factor: f32,
+    /// The smallest the scale is allowed to become.
+    pub min_scale: f32,
+    /// The largest the scale is allowed to become.
+    pub max_scale: f32,
+}
+
+impl Default for SuccessRule {
+    fn default() -> Self {
+        SuccessRule {
+            target: 0.2,
+            factor: 0.85,
+            min_scale: 0.01,
+            max_scale: 100.0,
+        }
+    }
+}
+
+impl SuccessRule {
+    /// The mutation scale for the next generation, given the current
+    /// scale and how many of this generation's offspring succeeded.
+    pub fn adapt(&self, scale: f32, offspring: usize, successes: usize) -> f32 {
+        if offspring == 0 {
+            return scale;
+        }
+
+        let rate = successes as f32 / offspring as f32;
+        let scale = if rate > self.target {
+            scale / self.factor
+        } else if rate < self.target {
+            scale * self.factor
+        } else {
+            scale
+        };
+        scale.clamp(self.min_scale, self.max_scale)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn scale_follows_the_success_rate() {
+        let rule = SuccessRule {
+            factor: 0.5,
+            ..Default::default()
+        };
+
+        assert_eq!(rule.adapt(1.0, 10, 5), 2.0);
+        assert_eq!(rule.adapt(1.0, 10, 1), 0.5);
+        assert_eq!(rule.adapt(1.0, 10, 2), 1.0);
+        assert_eq!(rule.adapt(1.0, 0, 0), 1.0);
+        assert_eq!(rule.adapt(0.01, 10, 0), 0.01);
+    }
+}
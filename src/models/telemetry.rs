@@ -0,0 +1,40 @@
+use core::time::Duration;
+
+/// Where the time went in one generation of `run_algorithm`, and with
+/// the memory feature, where the memory went.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct IterationTelemetry {
+    pub generation: usize,
+    /// How long it took to score the population.
+    pub compute_time: Duration,
+    /// How long it took to select parents and breed the next generation.
+    pub recombination_time: Duration,
+    /// How long the whole generation took, up to the point the telemetry
+    /// was recorded.
+    pub generation_time: Duration,
+    /// The best score seen so far in the run.
+    pub best_score: f32,
+    /// The bytes held by the population bred for the next generation.
+    #[cfg(feature = "memory")]
+    pub population_bytes: usize,
+    /// How many allocations were made during the generation, when the
+    /// counting allocator is installed.
+    #[cfg(feature = "memory")]
+    pub allocations: usize,
+    /// How many bytes were allocated during the generation, when the
+    /// counting allocator is installed.
+    #[cfg(feature = "memory")]
+    pub allocated_bytes: usize,
+}
+
+/// Somewhere to send the telemetry of each generation, such as a log or
+/// a dashboard. Any `Fn(&IterationTelemetry)` closure is a sink.
+pub trait TelemetrySink {
+    fn record(&self, telemetry: &IterationTelemetry);
+}
+
+impl<F: Fn(&IterationTelemetry)> TelemetrySink for F {
+    fn record(&self, telemetry: &IterationTelemetry) {
+        self(telemetry)
+    }
+}
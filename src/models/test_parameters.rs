@@ -1,5 +1,40 @@
+use super::{
+    constraint::ConstraintHandling,
+    elite_archive::EliteArchive,
+    evaluation::{Evaluation, EvaluationTimeout},
+    input_provider::Rescoring,
+    layout::PopulationLayout,
+    local_search::LocalSearchParameters,
+    niching::FitnessSharing,
+    parallelism::Parallelism,
+    replacement::Replacement,
+    scaling::Scaling,
+    schedule::{ParameterSchedules, RunState, Schedule},
+    selection::Selection,
+    speciation::Speciation,
+    success_rule::SuccessRule,
+    telemetry::TelemetrySink,
+};
+#[cfg(not(any(feature = "std", test)))]
+use crate::math::float::Float;
+#[cfg(feature = "parallel")]
+use rayon::{ThreadPool, ThreadPoolBuilder};
+#[cfg(feature = "parallel")]
+use std::sync::Arc;
+
 /// This is a set of common genetic algorithm parameters that
 /// are often used for testing purposes.
+///
+/// Not every runner supports every parameter. Some belong to the
+/// generation loop shared by `run_algorithm`, `run_with_store`, and
+/// `run_dynamic`, and every other runner panics when one of them is
+/// changed from its default rather than quietly run without it. Those are
+/// `evaluation_timeout`, `panic_penalty`, `fitness_cache`,
+/// `local_search`, `success_rule`, `replacement`,
+/// `random_immigrant_rate`, `fitness_sharing`, `speciation`,
+/// `deduplicate`, `snapshot_interval`, `track_lineage`, `evaluation`,
+/// `max_age`, and `elite_archive`, along with the algorithm's telemetry
+/// sink.
 pub struct TestParameters<FeatureFlags> {
     /// The total amount of generations to produce in the test
     pub generations: usize,
@@ -8,6 +43,10 @@ pub struct TestParameters<FeatureFlags> {
     /// A number between 0 - 1 which indicates the percentage of results
     /// will be retained because they are the best (or worst) solutions.
     pub elitism_factor: f32,
+    /// When set, nodes which have survived this many generations retire
+    /// and are no longer retained as elites, so a single old champion
+    /// can't hold its place forever.
+    pub max_age: Option<usize>,
     /// A number between 0 - 1 which indicates the probability of
     /// crossover favoring one solution over the other.
     pub crossover_factor: f32,
@@ -17,6 +56,297 @@ pub struct TestParameters<FeatureFlags> {
     /// How many solutions will be included in the tournament selection
     /// event, per tournament.
     pub tournament_size: usize,
+    /// A number between 0 - 1 which indicates the probability of the best
+    /// contestant winning a tournament. Otherwise the runner up wins with
+    /// the same probability, and so on down the ranking.
+    pub tournament_win_probability: f32,
+    /// When true, a tournament never includes the same node twice.
+    pub tournament_without_replacement: bool,
+    /// How many parents each offspring has. When this is greater than
+    /// two, offspring are created with `Algorithm::combine_nodes`.
+    /// Deterministic crowding always breeds pairs, since it pits each
+    /// offspring against one of its two parents.
+    pub parents: usize,
+    /// When true, the parents of an offspring are guaranteed to be
+    /// different nodes (as long as the population has enough of them).
+    pub distinct_parents: bool,
+    /// When set, parent pairs whose genomes are closer together than
+    /// this distance are rejected and the second parent is re-rolled.
+    /// This is often called incest prevention and requires the
+    /// algorithm to provide a distance.
+    pub mating_restriction: Option<f32>,
+    /// When set, scores are derated by fitness sharing after every node
+    /// has been evaluated. Requires the algorithm to provide a distance.
+    pub fitness_sharing: Option<FitnessSharing>,
+    /// When set, nodes are grouped into species every generation and
+    /// scores are shared within each species. Requires the algorithm to
+    /// provide a distance.
+    pub speciation: Option<Speciation>,
+    /// When set, a share of the offspring are improved by local search
+    /// before they are scored. Requires the algorithm to provide a local
+    /// search.
+    pub local_search: Option<LocalSearchParameters>,
+    /// When true, any genome which appears more than once in the next
+    /// generation is replaced by a mutant of itself (or, failing that, a
+    /// fresh random node) so that clones cannot flood the population.
+    /// Requires the algorithm to provide a fingerprint.
+    pub deduplicate: bool,
+    /// When set, the evaluations of this many recently seen genomes are
+    /// remembered, so a genome which turns up again is not scored again.
+    /// This pays off when evaluation is expensive and the population
+    /// keeps rediscovering the same genomes. Requires the algorithm to
+    /// provide a fingerprint, and assumes the analyzer always gives the
+    /// same genome the same score.
+    pub fitness_cache: Option<usize>,
+    /// A number between 0 - 1 which indicates the fraction of every new
+    /// generation replaced by freshly allocated nodes. A little fresh
+    /// material helps on dynamic or deceptive problems where the
+    /// population would otherwise converge and stay put.
+    pub random_immigrant_rate: f32,
+    /// When set, the best nodes ever seen are kept in an archive outside
+    /// the breeding population and reinserted now and then.
+    pub elite_archive: Option<EliteArchive>,
+    /// How many generations pass between snapshots of the population,
+    /// or zero for none. Snapshots go to the algorithm's snapshot sink.
+    pub snapshot_interval: usize,
+    /// When true, the runner gives every node a unique id, records the
+    /// ids of its parents and its birth generation, and returns the
+    /// lineage of every node it created.
+    pub track_lineage: bool,
+    /// How evaluations are spread across threads.
+    pub evaluation: Evaluation,
+    /// When set, a node which takes longer than the limit to score is
+    /// given the penalty score instead. Nodes scored in batches or on an
+    /// accelerator are only timed while their outputs are worked out,
+    /// and evaluations can see their time running out with
+    /// `algen::deadline`.
+    pub evaluation_timeout: Option<EvaluationTimeout>,
+    /// When set, a node whose output or score panics is given this score
+    /// instead of the panic bringing down the whole run. A panic while
+    /// scoring a batch, or on an accelerator, gives every node in the
+    /// batch this score.
+    pub panic_penalty: Option<f32>,
+    /// How many threads the run spreads its work across.
+    pub parallelism: Parallelism,
+    /// The rayon pool which runs the parallel work of a run. When this is
+    /// `None`, the global pool is used, so set it to keep a run from
+    /// competing with other rayon users in the same process.
+    #[cfg(feature = "parallel")]
+    pub thread_pool: Option<Arc<ThreadPool>>,
+    /// When set, every runner draws all of its randomness from streams
+    /// derived from this seed, so two runs with the same seed and the
+    /// same settings produce the same result, however many threads they
+    /// use. Algorithms must draw from `algen::random::rng()` rather than
+    /// `rand::thread_rng()` for their part to be reproducible too.
+    /// Operator bandits are credited in whatever order evaluations
+    /// finish, so runs which use one only repeat on a single thread.
+    /// Threaded and networked islands take in migrants whenever they
+    /// happen to arrive, so they only repeat without migration, and a
+    /// run which hits `evaluation_timeout` doesn't repeat at all.
+    pub seed: Option<u64>,
+    /// Which nodes are scored again when `run_dynamic` is handed new
+    /// input.
+    pub rescoring: Rescoring,
+    /// How the population is laid out while it is ranked and parents
+    /// are picked.
+    pub layout: PopulationLayout,
+    /// How nodes which violate the problem's constraints are treated.
+    pub constraint_handling: ConstraintHandling,
+    /// A transform applied to every score before selection.
+    pub scaling: Scaling,
+    /// The strategy used to pick parents for recombination.
+    pub selection: Selection,
+    /// The strategy used to decide which nodes survive into the next
+    /// generation.
+    pub replacement: Replacement,
+    /// Optional schedules which vary the mutation factor, crossover
+    /// factor, and tournament size from one generation to the next.
+    pub schedules: ParameterSchedules,
+    /// When set, the mutation factor is scaled up or down every
+    /// generation according to how many offspring beat their parents.
+    pub success_rule: Option<SuccessRule>,
+    /// The current generation and anything else the runner adapts as the
+    /// run goes on. Every run works on its own copy of the parameters, so
+    /// this is only kept up to date on the copy handed to the algorithm
+    /// and is left alone on the parameters passed to the runner.
+    pub state: RunState,
     /// A bucket of strings that you can use however you like.
     pub feature_flag: FeatureFlags,
 }
+
+impl<FeatureFlags: Clone> TestParameters<FeatureFlags> {
+    /// A copy of these parameters with a fresh state, which a runner
+    /// works on so that runs sharing the same parameters don't overwrite
+    /// each other's generation. The run's threads are settled here, once,
+    /// so a run which asks for a thread count builds a single pool.
+    pub(crate) fn for_run(&self) -> Self {
+        #[cfg(feature = "parallel")]
+        let state = {
+            let threads = match self.parallelism {
+                Parallelism::Automatic => None,
+                Parallelism::Threads(threads) => Some(threads.max(1)),
+                Parallelism::Sequential => Some(1),
+            };
+            let pool = threads
+                .and_then(|threads| ThreadPoolBuilder::new().num_threads(threads).build().ok())
+                .map(Arc::new)
+                .or_else(|| self.thread_pool.clone());
+            RunState::with_pool(pool)
+        };
+        #[cfg(not(feature = "parallel"))]
+        let state = RunState::default();
+        self.with_state(state)
+    }
+
+    /// A copy of `island`'s parameters with a fresh state of its own,
+    /// which works on the threads of this run.
+    #[cfg_attr(not(feature = "std"), allow(dead_code))]
+    pub(crate) fn for_island(&self, island: &Self) -> Self {
+        island.with_state(self.state.fresh())
+    }
+
+    fn with_state(&self, state: RunState) -> Self {
+        TestParameters {
+            #[cfg(feature = "parallel")]
+            thread_pool: self.thread_pool.clone(),
+            state,
+            feature_flag: self.feature_flag.clone(),
+            ..*self
+        }
+    }
+}
+
+impl<FeatureFlags> TestParameters<FeatureFlags> {
+    /// The mutation factor for the current generation, which is
+    /// `mutation_factor` unless it has a schedule, multiplied by the
+    /// scale of the success rule.
+    pub fn current_mutation_factor(&self) -> f32 {
+        let factor = self.scheduled(self.schedules.mutation_factor, self.mutation_factor);
+        if self.success_rule.is_some() {
+            (factor * self.state.mutation_scale()).min(1.0)
+        } else {
+            factor
+        }
+    }
+
+    /// The crossover factor for the current generation, which is
+    /// `crossover_factor` unless it has a schedule.
+    pub fn current_crossover_factor(&self) -> f32 {
+        self.scheduled(self.schedules.crossover_factor, self.crossover_factor)
+    }
+
+    /// The tournament size for the current generation, which is
+    /// `tournament_size` unless it has a schedule.
+    pub fn current_tournament_size(&self) -> usize {
+        match self.schedules.tournament_size {
+            Some(schedule) => schedule
+                .value(self.state.generation(), self.generations)
+                .round()
+                .max(1.0) as usize,
+            None => self.tournament_size,
+        }
+    }
+
+    /// Run `op` on the run's threads: the pool `for_run` settled on from
+    /// `parallelism` and `thread_pool`, or the global pool when there is
+    /// none. Every runner does its work through here. Work which is
+    /// already on the run's threads carries on where it is, rather than
+    /// waiting on the pool it is part of.
+    pub fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> R {
+        #[cfg(feature = "parallel")]
+        if let Some(pool) = self.state.pool() {
+            if pool.current_thread_index().is_none() {
+                return pool.install(op);
+            }
+        }
+
+        op()
+    }
+
+    /// Stop `runner` before it starts if any parameter is set which only
+    /// the generation loop of `run_algorithm` and its variants honors, or
+    /// there is a `telemetry` sink, which only that loop records to.
+    pub(crate) fn reject_loop_only(&self, runner: &str, telemetry: Option<&dyn TelemetrySink>) {
+        let loop_only = [
+            ("evaluation_timeout", self.evaluation_timeout.is_some()),
+            ("panic_penalty", self.panic_penalty.is_some()),
+            ("fitness_cache", self.fitness_cache.is_some()),
+            ("telemetry_sink", telemetry.is_some()),
+            ("local_search", self.local_search.is_some()),
+            ("success_rule", self.success_rule.is_some()),
+            (
+                "replacement",
+                !matches!(self.replacement, Replacement::Generational),
+            ),
+            ("random_immigrant_rate", self.random_immigrant_rate > 0.0),
+            ("fitness_sharing", self.fitness_sharing.is_some()),
+            ("speciation", self.speciation.is_some()),
+            ("deduplicate", self.deduplicate),
+            ("snapshot_interval", self.snapshot_interval > 0),
+            ("track_lineage", self.track_lineage),
+            (
+                "evaluation",
+                !matches!(self.evaluation, Evaluation::Parallel),
+            ),
+            ("max_age", self.max_age.is_some()),
+            ("elite_archive", self.elite_archive.is_some()),
+        ];
+        if let Some((parameter, _)) = loop_only.iter().find(|(_, set)| *set) {
+            panic!(
+                "`{runner}` does not support `{parameter}`, which only `run_algorithm`, \
+                 `run_with_store`, and `run_dynamic` honor"
+            );
+        }
+    }
+
+    fn scheduled(&self, schedule: Option<Schedule>, value: f32) -> f32 {
+        schedule
+            .map(|schedule| schedule.value(self.state.generation(), self.generations))
+            .unwrap_or(value)
+    }
+}
+
+impl<FeatureFlags: Default> Default for TestParameters<FeatureFlags> {
+    fn default() -> Self {
+        TestParameters {
+            generations: 100,
+            population: 1000,
+            elitism_factor: 0.05,
+            max_age: None,
+            crossover_factor: 0.5,
+            mutation_factor: 0.01,
+            tournament_size: 10,
+            tournament_win_probability: 1.0,
+            tournament_without_replacement: false,
+            parents: 2,
+            distinct_parents: false,
+            mating_restriction: None,
+            fitness_sharing: None,
+            speciation: None,
+            local_search: None,
+            deduplicate: false,
+            fitness_cache: None,
+            random_immigrant_rate: 0.0,
+            elite_archive: None,
+            snapshot_interval: 0,
+            track_lineage: false,
+            evaluation: Evaluation::default(),
+            evaluation_timeout: None,
+            panic_penalty: None,
+            parallelism: Parallelism::default(),
+            #[cfg(feature = "parallel")]
+            thread_pool: None,
+            seed: None,
+            rescoring: Rescoring::default(),
+            layout: PopulationLayout::default(),
+            constraint_handling: ConstraintHandling::default(),
+            scaling: Scaling::default(),
+            selection: Selection::default(),
+            replacement: Replacement::default(),
+            schedules: ParameterSchedules::default(),
+            success_rule: None,
+            state: RunState::default(),
+            feature_flag: FeatureFlags::default(),
+        }
+    }
+}
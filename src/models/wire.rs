@@ -0,0 +1,271 @@
+//! A small binary encoding for sending solutions to other processes or
+//! writing them to disk. Values are written with `Wire`, and messages
+//! travel as frames: a four byte, big endian payload length followed by
+//! the payload.
+//!
+//! This is deliberately not serde, gRPC, or HTTP. The crate otherwise
+//! depends on nothing beyond `rand` and, optionally, `rayon` and
+//! `tracing`, and a serialization framework would put its bounds on
+//! every solution type which crosses a process. Solutions implement
+//! `Wire` by writing their fields in turn, the format is fixed and easy
+//! to reimplement in another language, and frames are bounded by
+//! `MAX_FRAME`, so a worker or peer only needs a TCP socket.
+use super::node::Node;
+use std::{
+    io::{self, Read, Write},
+    sync::Arc,
+};
+
+/// The largest frame a peer will accept, which protects a process from
+/// allocating whatever a corrupt length prefix asks for.
+pub const MAX_FRAME: usize = 64 * 1024 * 1024;
+
+/// A value which can be sent to another process. Solutions of networked
+/// islands implement this, usually by writing each of their fields in
+/// turn.
+pub trait Wire: Sized {
+    /// Append the value to `out`.
+    fn encode(&self, out: &mut Vec<u8>);
+    /// Read a value from the front of `input`, advancing past it.
+    fn decode(input: &mut &[u8]) -> io::Result<Self>;
+}
+
+fn take<const N: usize>(input: &mut &[u8]) -> io::Result<[u8; N]> {
+    if input.len() < N {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "truncated payload",
+        ));
+    }
+    let (head, rest) = input.split_at(N);
+    *input = rest;
+    Ok(head.try_into().unwrap())
+}
+
+macro_rules! wire_numbers {
+    ($($ty:ty),*) => {
+        $(
+            impl Wire for $ty {
+                fn encode(&self, out: &mut Vec<u8>) {
+                    out.extend_from_slice(&self.to_be_bytes());
+                }
+
+                fn decode(input: &mut &[u8]) -> io::Result<Self> {
+                    take(input).map(<$ty>::from_be_bytes)
+                }
+            }
+        )*
+    };
+}
+
+wire_numbers!(u8, u16, u32, u64, i8, i16, i32, i64, f32, f64);
+
+impl Wire for usize {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (*self as u64).encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> io::Result<Self> {
+        usize::try_from(u64::decode(input)?)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl Wire for bool {
+    fn encode(&self, out: &mut Vec<u8>) {
+        out.push(*self as u8);
+    }
+
+    fn decode(input: &mut &[u8]) -> io::Result<Self> {
+        Ok(u8::decode(input)? != 0)
+    }
+}
+
+impl<T: Wire> Wire for Vec<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.len().encode(out);
+        for item in self {
+            item.encode(out);
+        }
+    }
+
+    fn decode(input: &mut &[u8]) -> io::Result<Self> {
+        let length = usize::decode(input)?;
+        // Every item takes at least a byte, which bounds a bogus length
+        let mut items = Vec::with_capacity(length.min(input.len()));
+        for _ in 0..length {
+            items.push(T::decode(input)?);
+        }
+        Ok(items)
+    }
+}
+
+impl Wire for String {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.as_bytes().to_vec().encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> io::Result<Self> {
+        String::from_utf8(Vec::decode(input)?)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+}
+
+impl<A: Wire, B: Wire> Wire for (A, B) {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.0.encode(out);
+        self.1.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> io::Result<Self> {
+        Ok((A::decode(input)?, B::decode(input)?))
+    }
+}
+
+impl<T: Wire> Wire for Arc<T> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        (**self).encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> io::Result<Self> {
+        Ok(Arc::new(T::decode(input)?))
+    }
+}
+
+/// A node's solution along with its id, age, and score.
+impl<Solution: Wire> Wire for Node<Solution> {
+    fn encode(&self, out: &mut Vec<u8>) {
+        self.id.encode(out);
+        self.age.encode(out);
+        self.score.encode(out);
+        self.solution.encode(out);
+    }
+
+    fn decode(input: &mut &[u8]) -> io::Result<Self> {
+        let id = usize::decode(input)?;
+        let age = usize::decode(input)?;
+        let score = f32::decode(input)?;
+        let mut node = Node::new(Solution::decode(input)?);
+        node.id = id;
+        node.age = age;
+        node.score = score;
+        Ok(node)
+    }
+}
+
+/// Write a payload as a single length prefixed frame.
+pub fn write_frame(stream: &mut impl Write, payload: &[u8]) -> io::Result<()> {
+    if payload.len() > MAX_FRAME {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "frame is too large",
+        ));
+    }
+    stream.write_all(&(payload.len() as u32).to_be_bytes())?;
+    stream.write_all(payload)?;
+    stream.flush()
+}
+
+/// Read the payload of a single length prefixed frame.
+pub fn read_frame(stream: &mut impl Read) -> io::Result<Vec<u8>> {
+    let mut length = [0; 4];
+    stream.read_exact(&mut length)?;
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_FRAME {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "frame is too large",
+        ));
+    }
+    let mut payload = vec![0; length];
+    stream.read_exact(&mut payload)?;
+    Ok(payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn frames_round_trip() {
+        let migrants = vec![(1.5f32, vec![true, false]), (-2.0, vec![])];
+        let mut payload = Vec::new();
+        migrants.encode(&mut payload);
+
+        let mut stream = Vec::new();
+        write_frame(&mut stream, &payload).unwrap();
+        let payload = read_frame(&mut stream.as_slice()).unwrap();
+        let decoded = Vec::<(f32, Vec<bool>)>::decode(&mut payload.as_slice()).unwrap();
+        assert_eq!(decoded, migrants);
+
+        assert!(Vec::<u64>::decode(&mut [0, 0, 0, 0, 0, 0, 0, 9].as_slice()).is_err());
+
+        let shared = Arc::new(String::from("shared"));
+        let mut payload = Vec::new();
+        shared.encode(&mut payload);
+        assert_eq!(
+            Arc::<String>::decode(&mut payload.as_slice()).unwrap(),
+            shared
+        );
+    }
+
+    /// Encode a value and decode it again, checking that nothing is left
+    /// over.
+    fn round_trip<T: Wire>(value: &T) -> T {
+        let mut payload = Vec::new();
+        value.encode(&mut payload);
+        let mut input = payload.as_slice();
+        let decoded = T::decode(&mut input).unwrap();
+        assert!(input.is_empty());
+        decoded
+    }
+
+    #[test]
+    fn every_value_round_trips() {
+        assert_eq!(round_trip(&u8::MAX), u8::MAX);
+        assert_eq!(round_trip(&u16::MAX), u16::MAX);
+        assert_eq!(round_trip(&u32::MAX), u32::MAX);
+        assert_eq!(round_trip(&u64::MAX), u64::MAX);
+        assert_eq!(round_trip(&i8::MIN), i8::MIN);
+        assert_eq!(round_trip(&i16::MIN), i16::MIN);
+        assert_eq!(round_trip(&i32::MIN), i32::MIN);
+        assert_eq!(round_trip(&i64::MIN), i64::MIN);
+        assert_eq!(round_trip(&-1.25f32), -1.25);
+        assert_eq!(round_trip(&f64::MAX), f64::MAX);
+        assert!(round_trip(&f32::NAN).is_nan());
+        assert_eq!(round_trip(&usize::MAX), usize::MAX);
+        assert!(round_trip(&true));
+        assert!(!round_trip(&false));
+        assert_eq!(
+            round_trip(&vec![vec![1u16], vec![]]),
+            vec![vec![1u16], vec![]]
+        );
+        assert_eq!(round_trip(&String::from("héllo")), "héllo");
+        assert_eq!(round_trip(&(7i64, String::new())), (7, String::new()));
+        assert_eq!(*round_trip(&Arc::new(vec![3u8])), vec![3]);
+
+        let mut node = Node::new(vec![true, false, true]);
+        node.id = 42;
+        node.age = 3;
+        node.score = 9.5;
+        let decoded = round_trip(&node);
+        assert_eq!(
+            (decoded.id, decoded.age, decoded.score, decoded.solution),
+            (42, 3, 9.5, vec![true, false, true])
+        );
+    }
+
+    #[test]
+    fn malformed_values_are_errors() {
+        assert!(u32::decode(&mut [0, 0, 1].as_slice()).is_err());
+        assert!(bool::decode(&mut [].as_slice()).is_err());
+        assert!(String::decode(&mut [0, 0, 0, 0, 0, 0, 0, 1, 0xff].as_slice()).is_err());
+        assert!(<(u8, u8)>::decode(&mut [1].as_slice()).is_err());
+        assert!(Node::<u8>::decode(&mut [0; 20].as_slice()).is_err());
+
+        let mut stream = Vec::new();
+        stream.extend_from_slice(&(MAX_FRAME as u32 + 1).to_be_bytes());
+        assert!(read_frame(&mut stream.as_slice()).is_err());
+        assert!(write_frame(&mut Vec::new(), &vec![0; MAX_FRAME + 1]).is_err());
+    }
+}
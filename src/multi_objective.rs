@@ -0,0 +1,703 @@
+//! Multi-objective runners. These work with a `MultiObjectiveAnalyzer`
+//! and optimize every objective at once, returning the set of
+//! trade-off solutions (the Pareto front) rather than a single winner.
+use crate::parallel::*;
+use crate::{
+    breeding::breed,
+    math::pareto::{
+        crowding_distance, epsilon_archive_insert, hypervolume, lexicographic_cmp,
+        non_dominated_sort, perpendicular_distance, reference_directions, strength_fitness,
+        truncate_archive,
+    },
+    models::algen_result::AlgenResult,
+    models::algorithm::Algorithm,
+    models::multi_objective::{MultiObjectiveParameters, MultiObjectiveStrategy},
+    models::multi_objective_analyzer::MultiObjectiveAnalyzer,
+    models::node::Node,
+    models::test_parameters::TestParameters,
+    random::Seed,
+    scoring::constraint_violation,
+    BREEDING, EVALUATION,
+};
+use rand::prelude::*;
+
+#[cfg(feature = "tracing")]
+use tracing::{event, Level};
+
+/// A method which is run at the end of each generation with the current
+/// non-dominated front. If it returns true, the test will be stopped.
+pub type FrontCallback<Solution> = fn(&[Node<Solution>]) -> bool;
+
+/// Run NSGA-II. This is shorthand for `run_multi_objective` with the
+/// default multi-objective parameters.
+pub fn run_nsga2<
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl MultiObjectiveAnalyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<FrontCallback<Solution>>,
+) -> AlgenResult<OutputData, Solution> {
+    run_multi_objective(
+        params,
+        &MultiObjectiveParameters::default(),
+        input_data,
+        algo,
+        analyzer,
+        on_generation_complete,
+    )
+}
+
+/// The multi-objective runner. This accepts the same types and
+/// parameters as `run_algorithm`, except the analyzer returns a score
+/// per objective, plus the multi-objective parameters which pick the
+/// strategy.
+///
+/// With NSGA-II, parents are picked with binary tournaments that prefer
+/// a better non-domination rank and then a larger crowding distance.
+/// The parents and offspring are then sorted into fronts and the next
+/// population is filled front by front, breaking ties in the last front
+/// by crowding distance.
+///
+/// NSGA-III works the same way, except ties in the last front are broken
+/// by spreading the survivors evenly across a set of reference
+/// directions, which holds up far better than crowding distance once
+/// there are four or more objectives.
+///
+/// With SPEA2, the population and archive are scored together, the
+/// archive is rebuilt from the best of them, and the next population is
+/// bred from binary tournaments over the archive.
+///
+/// With lexicographic ordering, parents and offspring are sorted by
+/// their objectives in priority order and the best of them survive. The
+/// front then holds just the single best node.
+///
+/// - on_generation_complete: A method which is run at the end of each
+///   generation with the current non-dominated front and, if it returns
+///   true, the test will be stopped.
+///
+/// With an epsilon set in the multi-objective parameters, the front
+/// reported to the callback and returned at the end is the
+/// epsilon-dominance archive rather than the current population's front.
+///
+/// With a reference point set, the hypervolume of the front is measured
+/// at the end of every generation.
+///
+/// An operator bandit still picks the operator of every offspring, but
+/// is never rewarded, since there is no single score for an offspring to
+/// improve on its parents by.
+///
+/// Returns the final non-dominated front in `AlgenResult::pareto_front`
+/// and the hypervolume of each generation in `AlgenResult::hypervolume`.
+pub fn run_multi_objective<
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    multi_objective: &MultiObjectiveParameters,
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl MultiObjectiveAnalyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<FrontCallback<Solution>>,
+) -> AlgenResult<OutputData, Solution> {
+    params.reject_loop_only("run_multi_objective", algo.telemetry_sink());
+    let params = &params.for_run();
+    params.install(|| {
+        let seed = Seed::new(params.seed);
+        let _stream = seed.enter();
+        let evaluate = |node: &mut Node<Solution>| {
+            let outputs = algo.output(node, input_data, params);
+            node.objectives = analyzer.evaluate(&outputs, params);
+            node.violation = analyzer.violation(&outputs, params)
+                + constraint_violation(algo, &node.solution, &outputs);
+        };
+
+        // Breed a full population from a pool, given a way to pick a parent
+        let breed_population =
+            |pool: &[Node<Solution>], pick: &(dyn Fn() -> Option<usize> + Sync), streams: Seed| {
+                let mut offspring = (0..params.population)
+                    .into_par_iter()
+                    .filter_map(|idx| {
+                        let _stream = streams.derive(BREEDING).derive(idx as u64).enter();
+                        let (left, right) = (pick()?, pick()?);
+                        Some(breed(algo, pool[left].clone(), pool[right].clone(), params))
+                    })
+                    .collect::<Vec<Node<Solution>>>();
+                offspring
+                    .par_iter_mut()
+                    .enumerate()
+                    .for_each(|(idx, node)| {
+                        let _stream = streams.derive(EVALUATION).derive(idx as u64).enter();
+                        evaluate(node)
+                    });
+                offspring
+            };
+
+        let mut population: Vec<Node<Solution>> = (0..params.population)
+            .map(|_| algo.allocate_node(input_data, params))
+            .collect();
+        population
+            .par_iter_mut()
+            .enumerate()
+            .for_each(|(idx, node)| {
+                let _stream = seed.derive(EVALUATION).derive(idx as u64).enter();
+                evaluate(node)
+            });
+        let (mut ranks, mut crowding) = match multi_objective.strategy {
+            MultiObjectiveStrategy::Lexicographic => lexicographic_ranks(&mut population),
+            _ => rank_population(&population),
+        };
+        let directions = match multi_objective.strategy {
+            MultiObjectiveStrategy::Nsga3 { divisions } => reference_directions(
+                population.first().map_or(0, |node| node.objectives.len()),
+                divisions,
+            ),
+            _ => Vec::new(),
+        };
+        let mut archive: Vec<Node<Solution>> = Vec::new();
+        let mut epsilon_archive: Vec<Node<Solution>> = Vec::new();
+        let mut hypervolumes: Vec<f32> = Vec::new();
+
+        for generation in 0..params.generations {
+            params.state.set_generation(generation);
+            let streams = seed.derive(generation as u64);
+
+            match multi_objective.strategy {
+                MultiObjectiveStrategy::Nsga2 => {
+                    let offspring = breed_population(
+                        &population,
+                        &|| crowded_tournament(&ranks, &crowding),
+                        streams,
+                    );
+
+                    // Environmental selection over parents and offspring combined
+                    population.extend(offspring);
+                    population = environmental_selection(population, params.population);
+                    (ranks, crowding) = rank_population(&population);
+                }
+                MultiObjectiveStrategy::Nsga3 { .. } => {
+                    let offspring = breed_population(
+                        &population,
+                        &|| crowded_tournament(&ranks, &crowding),
+                        streams,
+                    );
+
+                    // Reference point niching takes the place of crowding distance
+                    population.extend(offspring);
+                    population =
+                        reference_point_selection(population, params.population, &directions);
+                    (ranks, _) = rank_population(&population);
+                    crowding = vec![0.0; population.len()];
+                }
+                MultiObjectiveStrategy::Spea2 { archive_size } => {
+                    population.append(&mut archive);
+                    let fitness;
+                    (archive, fitness) = spea2_selection(population, archive_size.max(1));
+                    population =
+                        breed_population(&archive, &|| fitness_tournament(&fitness), streams);
+                }
+                MultiObjectiveStrategy::Lexicographic => {
+                    let offspring = breed_population(
+                        &population,
+                        &|| crowded_tournament(&ranks, &crowding),
+                        streams,
+                    );
+                    population.extend(offspring);
+                    (ranks, crowding) = lexicographic_ranks(&mut population);
+                    population.truncate(params.population);
+                    ranks.truncate(params.population);
+                    crowding.truncate(params.population);
+                }
+            }
+
+            if let Some(epsilon) = multi_objective.epsilon {
+                for node in population.iter().chain(archive.iter()) {
+                    epsilon_archive_insert(&mut epsilon_archive, node.clone(), epsilon);
+                }
+            }
+
+            let front = current_front(
+                multi_objective,
+                &population,
+                &ranks,
+                &archive,
+                &epsilon_archive,
+            );
+            if let Some(reference) = &multi_objective.reference_point {
+                let points: Vec<Vec<f32>> =
+                    front.iter().map(|node| node.objectives.clone()).collect();
+                hypervolumes.push(hypervolume(&points, reference));
+            }
+
+            #[cfg(feature = "tracing")]
+            event!(
+                Level::INFO,
+                msg = "Generation finished processing",
+                generation = generation,
+                hypervolume = hypervolumes.last().copied()
+            );
+
+            if let Some(func) = on_generation_complete {
+                if func(&front) {
+                    break;
+                }
+            }
+        }
+
+        AlgenResult {
+            score: f32::MIN,
+            output: None,
+            node: None,
+            pareto_front: current_front(
+                multi_objective,
+                &population,
+                &ranks,
+                &archive,
+                &epsilon_archive,
+            ),
+            archive: Vec::new(),
+            quality_diversity: Vec::new(),
+            hypervolume: hypervolumes,
+            history: Vec::new(),
+            genealogy: Vec::new(),
+        }
+    })
+}
+
+/// The current non-dominated front for the strategy in use, or the
+/// epsilon-dominance archive when one is kept.
+fn current_front<Solution: Clone>(
+    multi_objective: &MultiObjectiveParameters,
+    population: &[Node<Solution>],
+    ranks: &[usize],
+    archive: &[Node<Solution>],
+    epsilon_archive: &[Node<Solution>],
+) -> Vec<Node<Solution>> {
+    if multi_objective.epsilon.is_some() {
+        return epsilon_archive.to_vec();
+    }
+
+    match multi_objective.strategy {
+        MultiObjectiveStrategy::Nsga2 | MultiObjectiveStrategy::Nsga3 { .. } => {
+            first_front(population, ranks)
+        }
+        MultiObjectiveStrategy::Spea2 { .. } => {
+            let nodes = if archive.is_empty() {
+                population
+            } else {
+                archive
+            };
+            let (ranks, _) = rank_population(nodes);
+            first_front(nodes, &ranks)
+        }
+        MultiObjectiveStrategy::Lexicographic => population.iter().take(1).cloned().collect(),
+    }
+}
+
+/// Sort the population best first by lexicographic comparison. The rank
+/// of each node is its position, and the crowding distance is unused, so
+/// the crowded tournament simply prefers the higher ranked node.
+fn lexicographic_ranks<Solution: Send>(
+    population: &mut [Node<Solution>],
+) -> (Vec<usize>, Vec<f32>) {
+    population.par_sort_by(|left, right| lexicographic_cmp(right, left));
+    ((0..population.len()).collect(), vec![0.0; population.len()])
+}
+
+/// SPEA2 environmental selection. Scores the combined population and
+/// archive, keeps every non-dominated node, and then either fills the
+/// archive with the best dominated nodes or truncates it. Returns the
+/// new archive alongside the fitness of each member.
+fn spea2_selection<Solution>(
+    combined: Vec<Node<Solution>>,
+    archive_size: usize,
+) -> (Vec<Node<Solution>>, Vec<f32>) {
+    let fitness = strength_fitness(&combined);
+    let mut order: Vec<usize> = (0..combined.len()).collect();
+    order.sort_by(|left, right| fitness[*left].total_cmp(&fitness[*right]));
+
+    let non_dominated = order.iter().take_while(|idx| fitness[**idx] < 1.0).count();
+    let keep = if non_dominated > archive_size {
+        truncate_archive(&combined, order[..non_dominated].to_vec(), archive_size)
+    } else {
+        order.into_iter().take(archive_size).collect()
+    };
+
+    let kept_fitness = keep.iter().map(|idx| fitness[*idx]).collect();
+    let mut slots: Vec<Option<Node<Solution>>> = combined.into_iter().map(Some).collect();
+    let archive = keep
+        .into_iter()
+        .filter_map(|idx| slots[idx].take())
+        .collect();
+    (archive, kept_fitness)
+}
+
+/// Binary tournament where the lower fitness wins. Returns `None` when
+/// there is nothing to pick from.
+fn fitness_tournament(fitness: &[f32]) -> Option<usize> {
+    if fitness.is_empty() {
+        return None;
+    }
+    let mut rng = crate::random::rng();
+    let left = rng.gen_range(0..fitness.len());
+    let right = rng.gen_range(0..fitness.len());
+
+    if fitness[left] <= fitness[right] {
+        Some(left)
+    } else {
+        Some(right)
+    }
+}
+
+/// Pick the survivors out of a combined population by filling whole
+/// fronts, then taking the least crowded nodes from the front that does
+/// not fit entirely.
+fn environmental_selection<Solution>(
+    population: Vec<Node<Solution>>,
+    size: usize,
+) -> Vec<Node<Solution>> {
+    let mut keep: Vec<usize> = Vec::with_capacity(size);
+    for front in non_dominated_sort(&population) {
+        if keep.len() + front.len() <= size {
+            keep.extend(front);
+            continue;
+        }
+
+        let distances = crowding_distance(&population, &front);
+        let mut order: Vec<usize> = (0..front.len()).collect();
+        order.sort_by(|left, right| distances[*right].total_cmp(&distances[*left]));
+        keep.extend(
+            order
+                .into_iter()
+                .take(size - keep.len())
+                .map(|idx| front[idx]),
+        );
+        break;
+    }
+
+    let mut slots: Vec<Option<Node<Solution>>> = population.into_iter().map(Some).collect();
+    keep.into_iter()
+        .filter_map(|idx| slots[idx].take())
+        .collect()
+}
+
+/// NSGA-III environmental selection. Whole fronts are kept while they
+/// fit, then the objectives are normalized and every candidate is
+/// associated with its closest reference direction. The remaining slots
+/// go to candidates of the least represented directions, preferring the
+/// one closest to the direction when it has no members yet.
+fn reference_point_selection<Solution>(
+    population: Vec<Node<Solution>>,
+    size: usize,
+    directions: &[Vec<f32>],
+) -> Vec<Node<Solution>> {
+    let mut keep: Vec<usize> = Vec::with_capacity(size);
+    let mut last: Vec<usize> = Vec::new();
+    for front in non_dominated_sort(&population) {
+        if keep.len() + front.len() <= size {
+            keep.extend(front);
+        } else {
+            last = front;
+            break;
+        }
+    }
+
+    if !last.is_empty() && !directions.is_empty() {
+        // Translate so the ideal point is the origin and smaller is better
+        let objectives = directions[0].len();
+        let considered: Vec<usize> = keep.iter().chain(last.iter()).copied().collect();
+        let value = |idx: usize, objective: usize| {
+            population[idx]
+                .objectives
+                .get(objective)
+                .copied()
+                .unwrap_or(0.0)
+        };
+        let ideal: Vec<f32> = (0..objectives)
+            .map(|objective| {
+                considered
+                    .iter()
+                    .map(|idx| value(*idx, objective))
+                    .fold(f32::MIN, f32::max)
+            })
+            .collect();
+        let extent: Vec<f32> = (0..objectives)
+            .map(|objective| {
+                considered
+                    .iter()
+                    .map(|idx| ideal[objective] - value(*idx, objective))
+                    .fold(0.0, f32::max)
+                    .max(f32::EPSILON)
+            })
+            .collect();
+        let associate = |idx: usize| {
+            let point: Vec<f32> = (0..objectives)
+                .map(|objective| (ideal[objective] - value(idx, objective)) / extent[objective])
+                .collect();
+            directions
+                .iter()
+                .map(|direction| perpendicular_distance(&point, direction))
+                .enumerate()
+                .min_by(|(_, left), (_, right)| left.total_cmp(right))
+                .unwrap()
+        };
+
+        let mut niche_count = vec![0; directions.len()];
+        for idx in keep.iter() {
+            niche_count[associate(*idx).0] += 1;
+        }
+        let mut candidates: Vec<(usize, usize, f32)> = last
+            .iter()
+            .map(|idx| {
+                let (direction, distance) = associate(*idx);
+                (*idx, direction, distance)
+            })
+            .collect();
+
+        let mut rng = crate::random::rng();
+        while keep.len() < size && !candidates.is_empty() {
+            let fewest = candidates
+                .iter()
+                .map(|(_, direction, _)| niche_count[*direction])
+                .min()
+                .unwrap();
+            let mut least_crowded: Vec<usize> = candidates
+                .iter()
+                .map(|(_, direction, _)| *direction)
+                .filter(|direction| niche_count[*direction] == fewest)
+                .collect();
+            least_crowded.sort_unstable();
+            least_crowded.dedup();
+            let direction = *least_crowded.choose(&mut rng).unwrap();
+
+            let members =
+                (0..candidates.len()).filter(|position| candidates[*position].1 == direction);
+            let position = if fewest == 0 {
+                members
+                    .min_by(|left, right| candidates[*left].2.total_cmp(&candidates[*right].2))
+                    .unwrap()
+            } else {
+                members.choose(&mut rng).unwrap()
+            };
+
+            keep.push(candidates.swap_remove(position).0);
+            niche_count[direction] += 1;
+        }
+    }
+
+    let mut slots: Vec<Option<Node<Solution>>> = population.into_iter().map(Some).collect();
+    keep.into_iter()
+        .filter_map(|idx| slots[idx].take())
+        .collect()
+}
+
+/// Compute the non-domination rank and crowding distance of every node.
+fn rank_population<Solution>(population: &[Node<Solution>]) -> (Vec<usize>, Vec<f32>) {
+    let mut ranks = vec![0; population.len()];
+    let mut crowding = vec![0.0; population.len()];
+    for (rank, front) in non_dominated_sort(population).into_iter().enumerate() {
+        for (idx, distance) in front.iter().zip(crowding_distance(population, &front)) {
+            ranks[*idx] = rank;
+            crowding[*idx] = distance;
+        }
+    }
+
+    (ranks, crowding)
+}
+
+/// Binary tournament using the crowded comparison operator. A lower rank
+/// wins, and within the same rank a larger crowding distance wins.
+/// Returns `None` when there is nothing to pick from.
+fn crowded_tournament(ranks: &[usize], crowding: &[f32]) -> Option<usize> {
+    if ranks.is_empty() {
+        return None;
+    }
+    let mut rng = crate::random::rng();
+    let left = rng.gen_range(0..ranks.len());
+    let right = rng.gen_range(0..ranks.len());
+
+    if ranks[left] != ranks[right] {
+        if ranks[left] < ranks[right] {
+            Some(left)
+        } else {
+            Some(right)
+        }
+    } else if crowding[left] >= crowding[right] {
+        Some(left)
+    } else {
+        Some(right)
+    }
+}
+
+/// Clone the nodes on the first front.
+fn first_front<Solution: Clone>(
+    population: &[Node<Solution>],
+    ranks: &[usize],
+) -> Vec<Node<Solution>> {
+    population
+        .iter()
+        .zip(ranks)
+        .filter(|(_, rank)| **rank == 0)
+        .map(|(node, _)| node.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn nsga2_finds_a_spread_front() {
+        let front = run_nsga2(&parameters(), &(), &CountOnes {}, &TradeOff {}, None).pareto_front;
+
+        // Every bit string lies on the front, so it should spread out
+        assert!(!front.is_empty());
+        let ones: Vec<f32> = front.iter().map(|node| node.objectives[0]).collect();
+        let spread = ones.iter().fold(f32::MIN, |a, b| a.max(*b))
+            - ones.iter().fold(f32::MAX, |a, b| a.min(*b));
+        assert!(spread >= 8.0);
+    }
+
+    #[test]
+    fn multi_objective_runs_survive_an_empty_population() {
+        let params = TestParameters {
+            population: 0,
+            generations: 3,
+            ..parameters()
+        };
+        for strategy in [
+            MultiObjectiveStrategy::Nsga2,
+            MultiObjectiveStrategy::Nsga3 { divisions: 4 },
+            MultiObjectiveStrategy::Spea2 { archive_size: 4 },
+            MultiObjectiveStrategy::Lexicographic,
+        ] {
+            let multi_objective = MultiObjectiveParameters {
+                strategy,
+                ..Default::default()
+            };
+            let result = run_multi_objective(
+                &params,
+                &multi_objective,
+                &(),
+                &CountOnes {},
+                &TradeOff {},
+                None,
+            );
+            assert!(result.pareto_front.is_empty());
+        }
+    }
+
+    #[test]
+    fn nsga3_finds_a_spread_front() {
+        let multi_objective = MultiObjectiveParameters {
+            strategy: MultiObjectiveStrategy::Nsga3 { divisions: 8 },
+            ..Default::default()
+        };
+        let front = run_multi_objective(
+            &parameters(),
+            &multi_objective,
+            &(),
+            &CountOnes {},
+            &TradeOff {},
+            None,
+        )
+        .pareto_front;
+
+        let ones: Vec<f32> = front.iter().map(|node| node.objectives[0]).collect();
+        let spread = ones.iter().fold(f32::MIN, |a, b| a.max(*b))
+            - ones.iter().fold(f32::MAX, |a, b| a.min(*b));
+        assert!(spread >= 8.0);
+    }
+
+    #[test]
+    fn spea2_finds_a_spread_front() {
+        let multi_objective = MultiObjectiveParameters {
+            strategy: MultiObjectiveStrategy::Spea2 { archive_size: 20 },
+            ..Default::default()
+        };
+        let params = TestParameters {
+            generations: 10,
+            population: 40,
+            ..Default::default()
+        };
+        let front = run_multi_objective(
+            &params,
+            &multi_objective,
+            &(),
+            &CountOnes {},
+            &TradeOff {},
+            None,
+        )
+        .pareto_front;
+
+        assert!(!front.is_empty() && front.len() <= 20);
+    }
+
+    #[test]
+    fn epsilon_archive_bounds_the_front() {
+        let multi_objective = MultiObjectiveParameters {
+            epsilon: Some(4.0),
+            ..Default::default()
+        };
+        let front = run_multi_objective(
+            &parameters(),
+            &multi_objective,
+            &(),
+            &CountOnes {},
+            &TradeOff {},
+            None,
+        )
+        .pareto_front;
+
+        // Each box of objective space holds at most one node
+        assert!(!front.is_empty() && front.len() <= 5);
+    }
+
+    #[test]
+    fn hypervolume_is_reported_each_generation() {
+        let multi_objective = MultiObjectiveParameters {
+            reference_point: Some(vec![0.0, 0.0]),
+            ..Default::default()
+        };
+        let result = run_multi_objective(
+            &parameters(),
+            &multi_objective,
+            &(),
+            &CountOnes {},
+            &TradeOff {},
+            None,
+        );
+
+        assert_eq!(result.hypervolume.len(), 50);
+        assert!(result.hypervolume.iter().all(|volume| *volume > 0.0));
+    }
+
+    #[test]
+    fn lexicographic_prioritizes_the_first_objective() {
+        let multi_objective = MultiObjectiveParameters {
+            strategy: MultiObjectiveStrategy::Lexicographic,
+            ..Default::default()
+        };
+        let front = run_multi_objective(
+            &parameters(),
+            &multi_objective,
+            &(),
+            &CountOnes {},
+            &TradeOff {},
+            None,
+        )
+        .pareto_front;
+
+        assert_eq!(front.len(), 1);
+        assert!(front[0].objectives[0] >= 12.0);
+    }
+}
@@ -0,0 +1,382 @@
+//! Islands which live in separate processes, or on separate machines,
+//! and exchange migrants over TCP. Each process runs a single island
+//! with `run_network_island`, listening for immigrants on its own
+//! address and sending emigrants to its peers. Islands never wait for
+//! one another, so peers may be started, stopped, or restarted at any
+//! time; migrants addressed to a peer which is not listening are simply
+//! dropped.
+use crate::parallel::*;
+use crate::{
+    islands::{evolve, receive, select_emigrants},
+    math::constraints::{better, improves, PenaltyWeight},
+    models::algen_result::AlgenResult,
+    models::algorithm::Algorithm,
+    models::analyzer::Analyzer,
+    models::islands::IslandParameters,
+    models::network::NetworkParameters,
+    models::node::Node,
+    models::statistics::GenerationStatistics,
+    models::test_parameters::TestParameters,
+    models::wire::{read_frame, write_frame, Wire},
+    random::Seed,
+    scoring::{reward, score},
+    BREEDING, EVALUATION,
+};
+use std::{
+    collections::{hash_map::Entry, HashMap},
+    io::{self, ErrorKind},
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Sender},
+    },
+    thread::{self, Scope},
+    time::Duration,
+};
+
+#[cfg(feature = "tracing")]
+use tracing::{event, Level};
+
+/// How often idle network threads check whether the run has ended.
+const POLL: Duration = Duration::from_millis(50);
+
+/// Run one island of a distributed island model. This accepts the same
+/// types and parameters as `run_algorithm`, plus the `islands`
+/// parameters which control migration and the `network` parameters
+/// which say where this island listens and who its neighbors are. The
+/// island holds `params.population` nodes, and `islands.islands` and
+/// `islands.topology` are ignored in favor of `network.peers`.
+///
+/// The returned result holds the best node this island has seen,
+/// including immigrants. An error is returned only if the listening
+/// address can't be bound.
+pub fn run_network_island<
+    InputData: Send + Sync,
+    OutputData: Clone + Send + Sync,
+    Solution: Clone + Send + Sync + Wire,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    islands: &IslandParameters,
+    network: &NetworkParameters,
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
+) -> io::Result<AlgenResult<OutputData, Solution>> {
+    params.reject_loop_only("run_network_island", algo.telemetry_sink());
+    let params = &params.for_run();
+    params.install(|| {
+        let listener = TcpListener::bind(network.listen)?;
+        listener.set_nonblocking(true)?;
+        let interval = islands.migration_interval.max(1);
+        let size = params.population.max(2);
+        let done = AtomicBool::new(false);
+        let (arrivals, inbox) = mpsc::channel::<Vec<Node<Solution>>>();
+
+        let mut best_score = f32::MIN;
+        let mut best_node: Option<Node<Solution>> = None;
+        let mut best_output: Option<OutputData> = None;
+        let mut history = Vec::new();
+
+        thread::scope(|scope| {
+            let done = &done;
+            let timeout = network.frame_timeout;
+            scope.spawn(move || listen(scope, listener, arrivals, timeout, done));
+
+            let mut peers: HashMap<SocketAddr, TcpStream> = HashMap::new();
+            let seed = Seed::new(params.seed);
+            let _stream = seed.enter();
+            let weight = PenaltyWeight::new(&params.constraint_handling).weight();
+            let mut island: Vec<Node<Solution>> = (0..size)
+                .map(|_| algo.allocate_node(input_data, params))
+                .collect();
+
+            for generation in 0..params.generations {
+                params.state.set_generation(generation);
+                let streams = seed.derive(generation as u64);
+
+                let handling = &params.constraint_handling;
+                let generation_best = island
+                    .par_iter_mut()
+                    .enumerate()
+                    .map(|(idx, node)| {
+                        let _stream = streams.derive(EVALUATION).derive(idx as u64).enter();
+                        let output = algo.output(node, input_data, params);
+                        score(algo, analyzer, node, &output, params, weight);
+                        reward(algo, node);
+                        (output, &*node)
+                    })
+                    .reduce_with(|left, right| better(left, right, handling));
+
+                if let Some((output, node)) = generation_best {
+                    if improves(node, &best_node, best_score, handling) {
+                        best_score = node.score;
+                        best_node = Some(node.clone());
+                        best_output = Some(output);
+                    }
+                }
+                let scores: Vec<f32> = island.iter().map(|node| node.score).collect();
+                history.push(GenerationStatistics::new(generation, &scores));
+
+                if generation > 0 && generation % interval == 0 {
+                    let count = islands.migrants(island.len());
+                    let emigrants = select_emigrants(&island, count, islands.emigrants);
+                    let mut payload = Vec::new();
+                    emigrants
+                        .into_iter()
+                        .map(|idx| island[idx].clone())
+                        .collect::<Vec<Node<Solution>>>()
+                        .encode(&mut payload);
+                    for peer in &network.peers {
+                        send(&mut peers, *peer, network, &payload);
+                    }
+                }
+
+                for immigrants in inbox.try_iter() {
+                    receive(&mut island, immigrants, islands.immigrants);
+                }
+
+                let seed = streams.derive(BREEDING);
+                island = evolve(island, size, params, algo, generation, seed);
+
+                #[cfg(feature = "tracing")]
+                event!(
+                    Level::INFO,
+                    msg = "Generation finished processing",
+                    generation = generation,
+                    score = best_score
+                );
+
+                if let (Some(func), Some(output), Some(node)) =
+                    (on_generation_complete, &best_output, &best_node)
+                {
+                    if func(best_score, &node.solution, output) {
+                        break;
+                    }
+                }
+            }
+
+            done.store(true, Ordering::Relaxed);
+        });
+
+        Ok(AlgenResult {
+            score: best_score,
+            output: best_output,
+            node: best_node,
+            pareto_front: Vec::new(),
+            archive: Vec::new(),
+            quality_diversity: Vec::new(),
+            hypervolume: Vec::new(),
+            history,
+            genealogy: Vec::new(),
+        })
+    })
+}
+
+/// Accept peers until the run is done, reading each one's migrants on
+/// its own thread.
+fn listen<'scope, Solution: Wire + Send + 'scope>(
+    scope: &'scope Scope<'scope, '_>,
+    listener: TcpListener,
+    arrivals: Sender<Vec<Node<Solution>>>,
+    timeout: Duration,
+    done: &'scope AtomicBool,
+) {
+    while !done.load(Ordering::Relaxed) {
+        match listener.accept() {
+            Ok((stream, _)) => {
+                let arrivals = arrivals.clone();
+                scope.spawn(move || read_migrants(stream, arrivals, timeout, done));
+            }
+            Err(error) if error.kind() == ErrorKind::WouldBlock => thread::sleep(POLL),
+            Err(_) => thread::sleep(POLL),
+        }
+    }
+}
+
+/// Decode frames of migrants from a peer until it hangs up, it sends
+/// something malformed, or the run is done. A peer which stalls partway
+/// through a frame for longer than `timeout` is dropped along with
+/// whatever it had sent of the frame, so a stalled or half open
+/// connection never holds up the end of the run.
+fn read_migrants<Solution: Wire>(
+    mut stream: TcpStream,
+    arrivals: Sender<Vec<Node<Solution>>>,
+    timeout: Duration,
+    done: &AtomicBool,
+) {
+    if stream.set_nonblocking(false).is_err() || stream.set_read_timeout(Some(POLL)).is_err() {
+        return;
+    }
+
+    while !done.load(Ordering::Relaxed) {
+        // Wait for the start of a frame without consuming it, so that a
+        // timeout never leaves a frame half read.
+        match stream.peek(&mut [0; 4]) {
+            Ok(0) => return,
+            Ok(_) => {}
+            Err(error) if matches!(error.kind(), ErrorKind::WouldBlock | ErrorKind::TimedOut) => {
+                continue
+            }
+            Err(_) => return,
+        }
+
+        if stream.set_read_timeout(Some(timeout.max(POLL))).is_err() {
+            return;
+        }
+        let migrants = read_frame(&mut stream)
+            .and_then(|payload| Vec::<Node<Solution>>::decode(&mut payload.as_slice()));
+        match migrants {
+            Ok(migrants) => {
+                if arrivals.send(migrants).is_err() {
+                    return;
+                }
+            }
+            Err(_) => return,
+        }
+        if stream.set_read_timeout(Some(POLL)).is_err() {
+            return;
+        }
+    }
+}
+
+/// Send a frame to a peer, connecting first if needed. A peer which
+/// can't be reached is forgotten and reconnected at the next migration.
+fn send(
+    peers: &mut HashMap<SocketAddr, TcpStream>,
+    peer: SocketAddr,
+    network: &NetworkParameters,
+    payload: &[u8],
+) {
+    let stream = match peers.entry(peer) {
+        Entry::Occupied(entry) => entry.into_mut(),
+        Entry::Vacant(entry) => match TcpStream::connect_timeout(&peer, network.connect_timeout) {
+            Ok(stream)
+                if stream
+                    .set_write_timeout(Some(network.frame_timeout))
+                    .is_ok() =>
+            {
+                entry.insert(stream)
+            }
+            _ => return,
+        },
+    };
+
+    if write_frame(stream, payload).is_err() {
+        peers.remove(&peer);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Clone)]
+    struct Ones {}
+
+    impl Algorithm<(), usize, Vec<bool>, ()> for Ones {
+        fn output(&self, node: &Node<Vec<bool>>, _: &(), _: &TestParameters<()>) -> usize {
+            node.solution.iter().filter(|gene| **gene).count()
+        }
+
+        fn allocate_node(&self, _: &(), _: &TestParameters<()>) -> Node<Vec<bool>> {
+            Node::new((0..16).map(|_| rand::random()).collect())
+        }
+
+        fn combine_node(
+            &self,
+            left: Node<Vec<bool>>,
+            right: Node<Vec<bool>>,
+            _: &TestParameters<()>,
+        ) -> Node<Vec<bool>> {
+            Node::new(
+                crate::operators::uniform(&left.solution, &right.solution, 0.5)
+                    .into_iter()
+                    .map(|gene| gene ^ (rand::random::<f32>() < 0.05))
+                    .collect(),
+            )
+        }
+    }
+
+    struct Count {}
+    impl Analyzer<(), usize, ()> for Count {
+        fn evaluate(&self, ones: &usize, _: &TestParameters<()>) -> f32 {
+            *ones as f32
+        }
+    }
+
+    fn free_address() -> SocketAddr {
+        TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+    }
+
+    #[test]
+    fn network_islands_work() {
+        let addresses = [free_address(), free_address()];
+        let params = TestParameters {
+            generations: 40,
+            population: 30,
+            ..Default::default()
+        };
+        let islands = IslandParameters {
+            migration_interval: 2,
+            ..Default::default()
+        };
+
+        let results: Vec<_> = thread::scope(|scope| {
+            let handles: Vec<_> = (0..2)
+                .map(|idx| {
+                    let network = NetworkParameters::new(addresses[idx], vec![addresses[1 - idx]]);
+                    let (params, islands) = (&params, &islands);
+                    scope.spawn(move || {
+                        run_network_island(
+                            params,
+                            islands,
+                            &network,
+                            &(),
+                            &Ones {},
+                            &Count {},
+                            None,
+                        )
+                    })
+                })
+                .collect();
+            handles
+                .into_iter()
+                .map(|handle| handle.join().unwrap())
+                .collect()
+        });
+
+        for result in results {
+            let result = result.unwrap();
+            assert!(result.score >= 12.0);
+            assert!(result.node.is_some());
+        }
+    }
+
+    #[test]
+    fn stalled_peers_are_dropped() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let mut peer = TcpStream::connect(listener.local_addr().unwrap()).unwrap();
+        let (stream, _) = listener.accept().unwrap();
+
+        // A whole frame of migrants, then the start of one which never ends
+        let mut payload = Vec::new();
+        vec![Node::new(vec![true, false])].encode(&mut payload);
+        write_frame(&mut peer, &payload).unwrap();
+        io::Write::write_all(&mut peer, &[0, 0, 0, 100, 1]).unwrap();
+
+        let (arrivals, inbox) = mpsc::channel::<Vec<Node<Vec<bool>>>>();
+        let done = AtomicBool::new(false);
+        read_migrants(stream, arrivals, Duration::from_millis(100), &done);
+
+        let migrants: Vec<_> = inbox.try_iter().collect();
+        assert_eq!(migrants.len(), 1);
+        assert_eq!(migrants[0][0].solution, vec![true, false]);
+        drop(peer);
+    }
+}
@@ -0,0 +1,203 @@
+//! A novelty search runner. Rather than selecting parents by how well
+//! they score, nodes are selected by how different their behavior is
+//! from everything seen so far. This ignores the gradient of the fitness
+//! function entirely, which makes it useful on deceptive problems where
+//! following the score leads into a dead end.
+use crate::parallel::*;
+use crate::{
+    breeding::breed,
+    math::behavior::novelty_scores,
+    math::constraints::PenaltyWeight,
+    math::selection::{compatible, Selector},
+    models::algen_result::AlgenResult,
+    models::algorithm::Algorithm,
+    models::analyzer::Analyzer,
+    models::behavior::BehaviorDescriptor,
+    models::node::Node,
+    models::novelty::NoveltyParameters,
+    models::statistics::GenerationStatistics,
+    models::test_parameters::TestParameters,
+    random::Seed,
+    scoring::{reward, score},
+    BREEDING, EVALUATION,
+};
+use rand::prelude::*;
+
+#[cfg(feature = "tracing")]
+use tracing::{event, Level};
+
+/// Run novelty search. This accepts the same types and parameters as
+/// `run_algorithm`, plus the `novelty` parameters and a descriptor which
+/// maps each output to a behavior.
+///
+/// The analyzer is still used to score every node, and the best scoring
+/// node is returned, but selection and elitism are driven purely by
+/// novelty. A random sample of behaviors is kept in an archive so that
+/// the search keeps moving away from places it has already been.
+pub fn run_novelty_search<
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    novelty: &NoveltyParameters,
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+    descriptor: &(impl BehaviorDescriptor<OutputData> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
+) -> AlgenResult<OutputData, Solution> {
+    params.reject_loop_only("run_novelty_search", algo.telemetry_sink());
+    let params = &params.for_run();
+    params.install(|| {
+        let seed = Seed::new(params.seed);
+        let _stream = seed.enter();
+        let weight = PenaltyWeight::new(&params.constraint_handling).weight();
+        let mut best_score = f32::MIN;
+        let mut best_node: Option<Node<Solution>> = None;
+        let mut best_output: Option<OutputData> = None;
+
+        let mut population: Vec<Node<Solution>> = (0..params.population)
+            .map(|_| algo.allocate_node(input_data, params))
+            .collect();
+        let mut archive: Vec<Vec<f32>> = Vec::new();
+        let mut history = Vec::new();
+
+        for generation in 0..params.generations {
+            params.state.set_generation(generation);
+            let streams = seed.derive(generation as u64);
+
+            let outputs = population
+                .par_iter_mut()
+                .enumerate()
+                .map(|(idx, node)| {
+                    let _stream = streams.derive(EVALUATION).derive(idx as u64).enter();
+                    let output = algo.output(node, input_data, params);
+                    score(algo, analyzer, node, &output, params, weight);
+                    reward(algo, node);
+                    node.behavior = descriptor.describe(&output);
+                    output
+                })
+                .collect::<Vec<OutputData>>();
+
+            for (node, output) in population.iter().zip(outputs) {
+                if node.score > best_score {
+                    best_score = node.score;
+                    best_node = Some(node.clone());
+                    best_output = Some(output);
+                }
+            }
+            let raw_scores: Vec<f32> = population.iter().map(|node| node.score).collect();
+            history.push(GenerationStatistics::new(generation, &raw_scores));
+
+            // Novelty takes the place of the score from here on
+            let behaviors: Vec<&[f32]> = population
+                .iter()
+                .map(|node| node.behavior.as_slice())
+                .collect();
+            let scores = novelty_scores(&behaviors, &archive, novelty.neighbors, descriptor);
+            #[cfg(feature = "tracing")]
+            let diversity = descriptor.diversity(&behaviors);
+
+            let mut rng = crate::random::rng();
+            archive.extend(
+                behaviors
+                    .into_iter()
+                    .filter(|_| rng.gen::<f32>() < novelty.archive_probability)
+                    .map(<[f32]>::to_vec),
+            );
+            if archive.len() > novelty.archive_size {
+                archive.drain(..archive.len() - novelty.archive_size);
+            }
+
+            // Offspring are still judged against their parents' raw scores
+            let mut ranked: Vec<(Node<Solution>, f32)> =
+                population.into_iter().zip(raw_scores).collect();
+            for ((node, _), score) in ranked.iter_mut().zip(scores) {
+                node.score = score;
+            }
+            ranked.par_sort_by(|left, right| right.0.score.total_cmp(&left.0.score));
+            let raw_scores: Vec<f32>;
+            (population, raw_scores) = ranked.into_iter().unzip();
+            let elites = (params.elitism_factor * population.len() as f32) as usize;
+
+            let selector = Selector::new(population.as_slice(), params, generation);
+            let offspring = (0..population.len().saturating_sub(elites))
+                .into_par_iter()
+                .filter_map(|idx| {
+                    let _stream = streams.derive(BREEDING).derive(idx as u64).enter();
+                    selector
+                        .select_pair(population.as_slice(), params, |left, right| {
+                            compatible(params, algo.distance(), left, right)
+                        })
+                        .map(|(left, right)| {
+                            let mut child = breed(
+                                algo,
+                                population[left].clone(),
+                                population[right].clone(),
+                                params,
+                            );
+                            child.parent_score = raw_scores[left].max(raw_scores[right]);
+                            child
+                        })
+                })
+                .collect::<Vec<Node<Solution>>>();
+
+            population.truncate(elites);
+            population.extend(offspring);
+
+            #[cfg(feature = "tracing")]
+            event!(
+                Level::INFO,
+                msg = "Generation finished processing",
+                generation = generation,
+                score = best_score,
+                diversity = diversity
+            );
+
+            if let (Some(func), Some(output), Some(node)) =
+                (on_generation_complete, &best_output, &best_node)
+            {
+                if func(best_score, &node.solution, output) {
+                    break;
+                }
+            }
+        }
+
+        AlgenResult {
+            score: best_score,
+            output: best_output,
+            node: best_node,
+            pareto_front: Vec::new(),
+            archive: Vec::new(),
+            quality_diversity: Vec::new(),
+            hypervolume: Vec::new(),
+            history,
+            genealogy: Vec::new(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+
+    #[test]
+    fn novelty_search_works() {
+        let descriptor =
+            |output: &Vec<bool>| vec![output.iter().filter(|bit| **bit).count() as f32];
+        let result = run_novelty_search(
+            &parameters(),
+            &NoveltyParameters::default(),
+            &(),
+            &CountOnes {},
+            &CountOnesAnalyzer {},
+            &descriptor,
+            None,
+        );
+        assert!(result.score >= 12.0);
+    }
+}
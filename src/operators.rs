@@ -0,0 +1,643 @@
+//! Crossover and mutation helpers for writing `combine_node`. The
+//! built-in genomes are assembled from these, and custom algorithms can
+//! use them the same way:
+//!
+//! ```
+//! use algen::operators::{mutate_each, uniform};
+//! use rand::prelude::*;
+//!
+//! let left = [1, 2, 3, 4];
+//! let right = [5, 6, 7, 8];
+//! let mut child = uniform(&left, &right, 0.5);
+//! mutate_each(&mut child, 0.1, |gene| *gene = algen::random::rng().gen_range(0..10));
+//! assert_eq!(child.len(), 4);
+//! ```
+//!
+//! Crossovers over fixed length genomes stop at the end of the shorter
+//! parent. Rates are the probability that each gene is mutated, which is
+//! how the runner's `mutation_factor` is meant to be used.
+//!
+//! Variable length genomes, such as rule lists or instruction sequences,
+//! are just as welcome. `segment_crossover` and `cut_and_splice` let the
+//! length of the child drift away from its parents, `insertion_mutation`
+//! and `deletion_mutation` grow and shrink a genome between limits, and
+//! `length_penalty` can be subtracted from a score to stop genomes from
+//! bloating.
+use crate::models::distance::Distance;
+use rand::prelude::*;
+
+/// One-point crossover. The child takes the genes of `left` up to a
+/// random cut point and the genes of `right` after it.
+pub fn one_point<Gene: Clone>(left: &[Gene], right: &[Gene]) -> Vec<Gene> {
+    let length = left.len().min(right.len());
+    let cut = crate::random::rng().gen_range(0..=length);
+    left[..cut]
+        .iter()
+        .chain(&right[cut..length])
+        .cloned()
+        .collect()
+}
+
+/// Two-point crossover. The child takes the genes of `right` between two
+/// random cut points and the genes of `left` everywhere else.
+pub fn two_point<Gene: Clone>(left: &[Gene], right: &[Gene]) -> Vec<Gene> {
+    let length = left.len().min(right.len());
+    let mut rng = crate::random::rng();
+    let (a, b) = (rng.gen_range(0..=length), rng.gen_range(0..=length));
+    let (start, end) = (a.min(b), a.max(b));
+    left[..start]
+        .iter()
+        .chain(&right[start..end])
+        .chain(&left[end..length])
+        .cloned()
+        .collect()
+}
+
+/// Uniform crossover. Each gene is taken from `left` with probability
+/// `bias` and from `right` otherwise.
+pub fn uniform<Gene: Clone>(left: &[Gene], right: &[Gene], bias: f32) -> Vec<Gene> {
+    let mut rng = crate::random::rng();
+    let bias = bias.clamp(0.0, 1.0) as f64;
+    left.iter()
+        .zip(right)
+        .map(|(l, r)| if rng.gen_bool(bias) { l } else { r })
+        .cloned()
+        .collect()
+}
+
+/// Respectful crossover. Every gene the parents have in common is
+/// copied into the child untouched, and only the genes where they differ
+/// are filled in by `differ`. This keeps crossover from breaking up the
+/// building blocks that both parents agree on.
+pub fn respectful<Gene: Clone + PartialEq>(
+    left: &[Gene],
+    right: &[Gene],
+    mut differ: impl FnMut(&Gene, &Gene) -> Gene,
+) -> Vec<Gene> {
+    left.iter()
+        .zip(right)
+        .map(|(l, r)| if l == r { l.clone() } else { differ(l, r) })
+        .collect()
+}
+
+/// Distance-limited recombination. Children are created with
+/// `crossover` until one lies within `limit` of both parents, using up
+/// to `attempts` tries. If none does, the child whose furthest parent is
+/// nearest is returned, so the child never strays far from the parents.
+pub fn distance_limited<Solution>(
+    left: &Solution,
+    right: &Solution,
+    distance: &dyn Distance<Solution>,
+    limit: f32,
+    attempts: usize,
+    mut crossover: impl FnMut(&Solution, &Solution) -> Solution,
+) -> Solution {
+    let spread = |child: &Solution| {
+        distance
+            .distance(child, left)
+            .max(distance.distance(child, right))
+    };
+
+    let mut best = crossover(left, right);
+    let mut best_spread = spread(&best);
+    for _ in 1..attempts {
+        if best_spread <= limit {
+            break;
+        }
+
+        let child = crossover(left, right);
+        let child_spread = spread(&child);
+        if child_spread < best_spread {
+            best = child;
+            best_spread = child_spread;
+        }
+    }
+    best
+}
+
+/// Diagonal crossover for any number of parents. The genome is cut at
+/// one fewer random points than there are parents, and each of the
+/// resulting segments is taken from the next parent in turn.
+pub fn diagonal<Gene: Clone>(parents: &[impl AsRef<[Gene]>]) -> Vec<Gene> {
+    let length = parents
+        .iter()
+        .map(|parent| parent.as_ref().len())
+        .min()
+        .unwrap_or(0);
+    let mut rng = crate::random::rng();
+    let mut cuts: Vec<usize> = (1..parents.len())
+        .map(|_| rng.gen_range(0..=length))
+        .collect();
+    cuts.sort_unstable();
+    cuts.push(length);
+
+    let mut child = Vec::with_capacity(length);
+    for (parent, end) in parents.iter().zip(cuts) {
+        let start = child.len();
+        child.extend_from_slice(&parent.as_ref()[start..end]);
+    }
+    child
+}
+
+/// Gene pool recombination for any number of parents. Each gene is
+/// taken from a parent chosen at random.
+pub fn gene_pool<Gene: Clone>(parents: &[impl AsRef<[Gene]>]) -> Vec<Gene> {
+    let length = parents
+        .iter()
+        .map(|parent| parent.as_ref().len())
+        .min()
+        .unwrap_or(0);
+    let mut rng = crate::random::rng();
+    (0..length)
+        .map(|idx| parents[rng.gen_range(0..parents.len())].as_ref()[idx].clone())
+        .collect()
+}
+
+/// Apply `mutate` to each gene with probability `rate`. The mutated genes
+/// are found with a mutation clock, so only they cost a random number.
+pub fn mutate_each<Gene>(genes: &mut [Gene], rate: f32, mut mutate: impl FnMut(&mut Gene)) {
+    for idx in MutationClock::new(genes.len(), rate) {
+        mutate(&mut genes[idx]);
+    }
+}
+
+/// A mutation clock yields the indices of the genes which should mutate,
+/// in increasing order, when each of `length` genes mutates independently
+/// with probability `rate`. Rather than rolling the dice for every gene,
+/// it draws the gap to the next mutated gene from a geometric
+/// distribution, which is far faster for long genomes with small rates.
+pub struct MutationClock {
+    next: usize,
+    length: usize,
+    log_miss: f64,
+}
+
+impl MutationClock {
+    pub fn new(length: usize, rate: f32) -> Self {
+        let rate = rate.clamp(0.0, 1.0) as f64;
+        let mut clock = MutationClock {
+            next: 0,
+            length: if rate > 0.0 { length } else { 0 },
+            log_miss: (1.0 - rate).ln(),
+        };
+        clock.next = clock.gap();
+        clock
+    }
+
+    /// How many genes to skip before the next mutation.
+    fn gap(&self) -> usize {
+        if self.log_miss == f64::NEG_INFINITY {
+            return 0;
+        }
+
+        // 1 - u is in (0, 1], so the logarithm is always finite.
+        let u: f64 = crate::random::rng().gen();
+        let gap = (1.0 - u).ln() / self.log_miss;
+        if gap < self.length as f64 {
+            gap as usize
+        } else {
+            self.length
+        }
+    }
+}
+
+impl Iterator for MutationClock {
+    type Item = usize;
+
+    fn next(&mut self) -> Option<usize> {
+        if self.next >= self.length {
+            return None;
+        }
+
+        let idx = self.next;
+        self.next = idx + 1 + self.gap();
+        Some(idx)
+    }
+}
+
+/// Flip each bit with probability `rate`.
+pub fn bit_flip(genes: &mut [bool], rate: f32) {
+    mutate_each(genes, rate, |gene| *gene = !*gene);
+}
+
+/// Draw from a normal distribution using the Box-Muller transform.
+pub fn gaussian(mean: f64, deviation: f64) -> f64 {
+    let mut rng = crate::random::rng();
+    let u: f64 = 1.0 - rng.gen::<f64>();
+    let v: f64 = rng.gen();
+    mean + deviation * (-2.0 * u.ln()).sqrt() * (std::f64::consts::TAU * v).cos()
+}
+
+/// The log-normal self-adaptation rule of evolution strategies. Every
+/// step size is multiplied by `exp(τ' * N + τ * N_i)`, where `N` is drawn
+/// once for the whole genome and `N_i` once per step size, with the
+/// usual learning rates `τ' = 1 / sqrt(2n)` and `τ = 1 / sqrt(2 sqrt(n))`.
+/// Step sizes never drop below `min_step`.
+pub fn log_normal_update(step_sizes: &mut [f64], min_step: f64) {
+    let n = step_sizes.len().max(1) as f64;
+    let global_rate = 1.0 / (2.0 * n).sqrt();
+    let local_rate = 1.0 / (2.0 * n.sqrt()).sqrt();
+    let global = gaussian(0.0, 1.0);
+    for step in step_sizes.iter_mut() {
+        *step =
+            (*step * (global_rate * global + local_rate * gaussian(0.0, 1.0)).exp()).max(min_step);
+    }
+}
+
+/// Arithmetic crossover. The child is a random blend of both parents,
+/// using the same weight for every gene.
+pub fn arithmetic(left: &[f64], right: &[f64]) -> Vec<f64> {
+    let weight: f64 = crate::random::rng().gen();
+    left.iter()
+        .zip(right)
+        .map(|(l, r)| weight * l + (1.0 - weight) * r)
+        .collect()
+}
+
+/// Simulated binary crossover (SBX). Each gene is spread around its
+/// parents in a way that mimics one-point crossover on a binary
+/// encoding. A larger `eta` keeps the child closer to its parents.
+pub fn simulated_binary(left: &[f64], right: &[f64], eta: f64) -> Vec<f64> {
+    let mut rng = crate::random::rng();
+    left.iter()
+        .zip(right)
+        .map(|(l, r)| {
+            if !rng.gen_bool(0.5) {
+                return *l;
+            }
+
+            let u: f64 = rng.gen();
+            let beta = if u <= 0.5 {
+                (2.0 * u).powf(1.0 / (eta + 1.0))
+            } else {
+                (1.0 / (2.0 * (1.0 - u))).powf(1.0 / (eta + 1.0))
+            };
+            let sign = if rng.gen_bool(0.5) { 1.0 } else { -1.0 };
+            0.5 * ((l + r) + sign * beta * (l - r))
+        })
+        .collect()
+}
+
+/// Add gaussian noise to each gene with probability `rate`, using the
+/// matching entry of `step_sizes` as the standard deviation.
+pub fn gaussian_mutation(genes: &mut [f64], rate: f32, step_sizes: &[f64]) {
+    let mut rng = crate::random::rng();
+    let rate = rate.clamp(0.0, 1.0) as f64;
+    for (gene, step) in genes.iter_mut().zip(step_sizes) {
+        if rng.gen_bool(rate) {
+            *gene += gaussian(0.0, *step);
+        }
+    }
+}
+
+/// Polynomial mutation. Each gene is moved with probability `rate` by an
+/// amount drawn from a polynomial distribution which is scaled to fit
+/// within its bounds. A larger `eta` makes smaller moves.
+pub fn polynomial_mutation(genes: &mut [f64], rate: f32, bounds: &[(f64, f64)], eta: f64) {
+    let mut rng = crate::random::rng();
+    let rate = rate.clamp(0.0, 1.0) as f64;
+    for (gene, (low, high)) in genes.iter_mut().zip(bounds) {
+        let range = high - low;
+        if range <= 0.0 || !rng.gen_bool(rate) {
+            continue;
+        }
+
+        let u: f64 = rng.gen();
+        let power = 1.0 / (eta + 1.0);
+        let delta = if u < 0.5 {
+            let headroom = 1.0 - (*gene - low) / range;
+            (2.0 * u + (1.0 - 2.0 * u) * headroom.powf(eta + 1.0)).powf(power) - 1.0
+        } else {
+            let headroom = 1.0 - (high - *gene) / range;
+            1.0 - (2.0 * (1.0 - u) + 2.0 * (u - 0.5) * headroom.powf(eta + 1.0)).powf(power)
+        };
+        *gene += delta * range;
+    }
+}
+
+/// Pick a random segment `start..end` of a sequence with the given
+/// length.
+fn segment(length: usize) -> (usize, usize) {
+    let mut rng = crate::random::rng();
+    let (a, b) = (rng.gen_range(0..=length), rng.gen_range(0..=length));
+    (a.min(b), a.max(b))
+}
+
+/// Segment crossover for variable length genomes. A random segment of
+/// `left` is replaced by a random segment of `right`, so the child may
+/// end up longer or shorter than either parent.
+pub fn segment_crossover<Gene: Clone>(left: &[Gene], right: &[Gene]) -> Vec<Gene> {
+    let (start, end) = segment(left.len());
+    let (from, to) = segment(right.len());
+    left[..start]
+        .iter()
+        .chain(&right[from..to])
+        .chain(&left[end..])
+        .cloned()
+        .collect()
+}
+
+/// Cut-and-splice crossover for variable length genomes. Each parent is
+/// cut at its own random point, and the child is the head of `left`
+/// followed by the tail of `right`.
+pub fn cut_and_splice<Gene: Clone>(left: &[Gene], right: &[Gene]) -> Vec<Gene> {
+    let mut rng = crate::random::rng();
+    let left_cut = rng.gen_range(0..=left.len());
+    let right_cut = rng.gen_range(0..=right.len());
+    let mut child = left[..left_cut].to_vec();
+    child.extend_from_slice(&right[right_cut..]);
+    child
+}
+
+/// Insert a new gene from `create`, with probability `rate`, in front of
+/// each gene and at the end. The genome never grows past `max_length`.
+pub fn insertion_mutation<Gene>(
+    genes: &mut Vec<Gene>,
+    rate: f32,
+    max_length: usize,
+    mut create: impl FnMut() -> Gene,
+) {
+    let mut rng = crate::random::rng();
+    let rate = rate.clamp(0.0, 1.0) as f64;
+    let mut idx = 0;
+    while idx <= genes.len() && genes.len() < max_length {
+        if rng.gen_bool(rate) {
+            genes.insert(idx, create());
+            idx += 1;
+        }
+        idx += 1;
+    }
+}
+
+/// Remove each gene with probability `rate`. The genome never shrinks
+/// below `min_length`.
+pub fn deletion_mutation<Gene>(genes: &mut Vec<Gene>, rate: f32, min_length: usize) {
+    let mut rng = crate::random::rng();
+    let rate = rate.clamp(0.0, 1.0) as f64;
+    let mut idx = 0;
+    while idx < genes.len() && genes.len() > min_length {
+        if rng.gen_bool(rate) {
+            genes.remove(idx);
+        } else {
+            idx += 1;
+        }
+    }
+}
+
+/// A penalty for genomes longer than `limit`, which grows by
+/// `coefficient` for every gene past the limit. Subtract it from the
+/// score of a variable length genome to keep its length under control.
+pub fn length_penalty(length: usize, limit: usize, coefficient: f32) -> f32 {
+    length.saturating_sub(limit) as f32 * coefficient
+}
+
+/// Order crossover (OX) for permutations of `0..n`. The child copies a
+/// random segment of `left`, then fills the remaining positions, starting
+/// after the segment and wrapping around, with the missing values in the
+/// order they appear in `right`.
+pub fn order_crossover(left: &[usize], right: &[usize]) -> Vec<usize> {
+    let length = left.len();
+    let (start, end) = segment(length);
+    let mut child = vec![usize::MAX; length];
+    let mut used = vec![false; length];
+    for idx in start..end {
+        child[idx] = left[idx];
+        used[left[idx]] = true;
+    }
+
+    let mut position = end;
+    for offset in 0..length {
+        let value = right[(end + offset) % length];
+        if used[value] {
+            continue;
+        }
+        while child[position % length] != usize::MAX {
+            position += 1;
+        }
+        child[position % length] = value;
+        used[value] = true;
+    }
+
+    child
+}
+
+/// Partially mapped crossover (PMX) for permutations of `0..n`. The child
+/// copies a random segment of `left`. Values of `right` from the same
+/// segment which were displaced are placed by following the mapping
+/// between the two segments, and every other position is copied from
+/// `right`.
+pub fn partially_mapped(left: &[usize], right: &[usize]) -> Vec<usize> {
+    let length = left.len();
+    let (start, end) = segment(length);
+    let mut position_in_right = vec![0; length];
+    for (idx, value) in right.iter().enumerate() {
+        position_in_right[*value] = idx;
+    }
+
+    let mut child = vec![usize::MAX; length];
+    let mut used = vec![false; length];
+    for idx in start..end {
+        child[idx] = left[idx];
+        used[left[idx]] = true;
+    }
+
+    for (idx, value) in right.iter().copied().enumerate().take(end).skip(start) {
+        if used[value] {
+            continue;
+        }
+
+        let mut position = idx;
+        while (start..end).contains(&position) {
+            position = position_in_right[left[position]];
+        }
+        child[position] = value;
+        used[value] = true;
+    }
+
+    for (gene, value) in child.iter_mut().zip(right) {
+        if *gene == usize::MAX {
+            *gene = *value;
+        }
+    }
+
+    child
+}
+
+/// Swap each gene, with probability `rate`, with another random gene.
+pub fn swap_mutation<Gene>(genes: &mut [Gene], rate: f32) {
+    let mut rng = crate::random::rng();
+    let rate = rate.clamp(0.0, 1.0) as f64;
+    for idx in 0..genes.len() {
+        if rng.gen_bool(rate) {
+            let other = rng.gen_range(0..genes.len());
+            genes.swap(idx, other);
+        }
+    }
+}
+
+/// Reverse, once per gene with probability `rate`, the segment between
+/// that gene and another random gene.
+pub fn inversion_mutation<Gene>(genes: &mut [Gene], rate: f32) {
+    let mut rng = crate::random::rng();
+    let rate = rate.clamp(0.0, 1.0) as f64;
+    for idx in 0..genes.len() {
+        if rng.gen_bool(rate) {
+            let other = rng.gen_range(0..genes.len());
+            genes[idx.min(other)..=idx.max(other)].reverse();
+        }
+    }
+}
+
+/// Replace each gene, with probability `rate`, with a random value from
+/// its inclusive bounds.
+pub fn random_reset(genes: &mut [i64], rate: f32, bounds: &[(i64, i64)]) {
+    let mut rng = crate::random::rng();
+    let rate = rate.clamp(0.0, 1.0) as f64;
+    for (gene, (low, high)) in genes.iter_mut().zip(bounds) {
+        if low <= high && rng.gen_bool(rate) {
+            *gene = rng.gen_range(*low..=*high);
+        }
+    }
+}
+
+/// Nudge each gene, with probability `rate`, up or down by at most `step`
+/// while staying within its inclusive bounds.
+pub fn creep_mutation(genes: &mut [i64], rate: f32, bounds: &[(i64, i64)], step: i64) {
+    let mut rng = crate::random::rng();
+    let rate = rate.clamp(0.0, 1.0) as f64;
+    let step = step.max(1);
+    for (gene, (low, high)) in genes.iter_mut().zip(bounds) {
+        if low <= high && rng.gen_bool(rate) {
+            let amount = rng.gen_range(1..=step);
+            let moved = if rng.gen_bool(0.5) {
+                gene.saturating_add(amount)
+            } else {
+                gene.saturating_sub(amount)
+            };
+            *gene = moved.clamp(*low, *high);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn crossover_keeps_genes_in_place() {
+        let left = [0; 8];
+        let right = [1; 8];
+        for child in [
+            one_point(&left, &right),
+            two_point(&left, &right),
+            uniform(&left, &right, 0.5),
+        ] {
+            assert_eq!(child.len(), 8);
+            assert!(child.iter().all(|gene| *gene == 0 || *gene == 1));
+        }
+
+        let child = one_point(&left, &right);
+        let cut = child.iter().position(|gene| *gene == 1).unwrap_or(8);
+        assert!(child[cut..].iter().all(|gene| *gene == 1));
+    }
+
+    #[test]
+    fn real_crossover_stays_between_parents() {
+        let child = arithmetic(&[0.0, 10.0], &[1.0, 20.0]);
+        assert!((0.0..=1.0).contains(&child[0]));
+        assert!((10.0..=20.0).contains(&child[1]));
+
+        let mut genes = vec![0.5; 100];
+        polynomial_mutation(&mut genes, 1.0, &[(0.0, 1.0); 100], 20.0);
+        assert!(genes.iter().all(|gene| (0.0..=1.0).contains(gene)));
+    }
+
+    #[test]
+    fn permutation_operators_keep_every_value() {
+        let left: Vec<usize> = (0..20).collect();
+        let right: Vec<usize> = (0..20).rev().collect();
+        for _ in 0..100 {
+            for mut child in [
+                order_crossover(&left, &right),
+                partially_mapped(&left, &right),
+            ] {
+                swap_mutation(&mut child, 0.1);
+                inversion_mutation(&mut child, 0.1);
+                child.sort_unstable();
+                assert_eq!(child, left);
+            }
+        }
+    }
+
+    #[test]
+    fn variable_length_operators_respect_their_limits() {
+        for _ in 0..100 {
+            let child = cut_and_splice(&[0; 5], &[1; 7]);
+            let zeros = child.iter().take_while(|gene| **gene == 0).count();
+            assert!(zeros <= 5 && child.len() - zeros <= 7);
+            assert!(child[zeros..].iter().all(|gene| *gene == 1));
+
+            let mut genes = vec![0; 4];
+            insertion_mutation(&mut genes, 0.5, 6, || 1);
+            assert!((4..=6).contains(&genes.len()));
+            assert_eq!(genes.iter().filter(|gene| **gene == 0).count(), 4);
+
+            deletion_mutation(&mut genes, 0.5, 2);
+            assert!(genes.len() >= 2);
+        }
+
+        let mut genes = vec![0; 4];
+        insertion_mutation(&mut genes, 1.0, 100, || 1);
+        assert_eq!(genes, vec![1, 0, 1, 0, 1, 0, 1, 0, 1]);
+
+        assert_eq!(length_penalty(12, 10, 0.5), 1.0);
+        assert_eq!(length_penalty(8, 10, 0.5), 0.0);
+    }
+
+    #[test]
+    fn multi_parent_crossover_keeps_genes_in_place() {
+        let parents = [vec![0; 6], vec![1; 6], vec![2; 6]];
+        for _ in 0..100 {
+            let child = diagonal(&parents);
+            assert_eq!(child.len(), 6);
+            assert!(child.windows(2).all(|pair| pair[0] <= pair[1]));
+
+            let child = gene_pool(&parents);
+            assert_eq!(child.len(), 6);
+            assert!(child.iter().all(|gene| *gene <= 2));
+        }
+    }
+
+    #[test]
+    fn respectful_crossover_keeps_common_genes() {
+        let child = respectful(&[1, 2, 3, 4], &[1, 5, 3, 6], |_, _| 0);
+        assert_eq!(child, vec![1, 0, 3, 0]);
+    }
+
+    #[test]
+    fn distance_limited_children_stay_near_their_parents() {
+        let hamming = |left: &Vec<bool>, right: &Vec<bool>| {
+            left.iter().zip(right).filter(|(l, r)| l != r).count() as f32
+        };
+        let (left, right) = (vec![false; 10], vec![true; 10]);
+
+        for _ in 0..20 {
+            let child = distance_limited(&left, &right, &hamming, 6.0, 50, |l, r| one_point(l, r));
+            assert!(hamming(&child, &left) <= 6.0 && hamming(&child, &right) <= 6.0);
+        }
+    }
+
+    #[test]
+    fn mutation_clock_matches_the_rate() {
+        assert_eq!(MutationClock::new(10, 0.0).count(), 0);
+        assert_eq!(
+            MutationClock::new(10, 1.0).collect::<Vec<_>>(),
+            (0..10).collect::<Vec<_>>()
+        );
+
+        let picks: Vec<usize> = MutationClock::new(100_000, 0.01).collect();
+        assert!(picks.windows(2).all(|pair| pair[0] < pair[1]));
+        assert!(picks.iter().all(|idx| *idx < 100_000));
+        assert!((800..1200).contains(&picks.len()));
+    }
+}
@@ -0,0 +1,87 @@
+//! The parallel iterators used throughout the crate. With the `parallel`
+//! feature these are rayon's, and without it they are ordinary iterators
+//! under the same names, so every runner is written once and simply runs
+//! on the calling thread.
+
+#[cfg(feature = "parallel")]
+pub use rayon::{current_num_threads, prelude::*};
+
+#[cfg(not(feature = "parallel"))]
+pub use sequential::*;
+
+#[cfg(not(feature = "parallel"))]
+mod sequential {
+    use core::cmp::Ordering;
+    use core::iter::FlatMap;
+    use core::slice::{Chunks, Iter, IterMut};
+
+    /// There is only ever the calling thread.
+    pub fn current_num_threads() -> usize {
+        1
+    }
+
+    pub trait IntoParallelIterator: IntoIterator + Sized {
+        fn into_par_iter(self) -> Self::IntoIter {
+            self.into_iter()
+        }
+    }
+
+    impl<T: IntoIterator> IntoParallelIterator for T {}
+
+    pub trait ParallelSlice<T> {
+        fn par_iter(&self) -> Iter<'_, T>;
+        fn par_chunks(&self, size: usize) -> Chunks<'_, T>;
+    }
+
+    impl<T> ParallelSlice<T> for [T] {
+        fn par_iter(&self) -> Iter<'_, T> {
+            self.iter()
+        }
+
+        fn par_chunks(&self, size: usize) -> Chunks<'_, T> {
+            self.chunks(size)
+        }
+    }
+
+    pub trait ParallelSliceMut<T> {
+        fn par_iter_mut(&mut self) -> IterMut<'_, T>;
+        fn par_sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, compare: F);
+        fn par_sort_unstable_by<F: FnMut(&T, &T) -> Ordering>(&mut self, compare: F);
+    }
+
+    impl<T> ParallelSliceMut<T> for [T] {
+        fn par_iter_mut(&mut self) -> IterMut<'_, T> {
+            self.iter_mut()
+        }
+
+        fn par_sort_by<F: FnMut(&T, &T) -> Ordering>(&mut self, compare: F) {
+            self.sort_by(compare)
+        }
+
+        fn par_sort_unstable_by<F: FnMut(&T, &T) -> Ordering>(&mut self, compare: F) {
+            self.sort_unstable_by(compare)
+        }
+    }
+
+    pub trait ParallelIterator: Iterator + Sized {
+        fn with_min_len(self, _min: usize) -> Self {
+            self
+        }
+
+        fn reduce_with<F: FnMut(Self::Item, Self::Item) -> Self::Item>(
+            self,
+            reduce: F,
+        ) -> Option<Self::Item> {
+            self.reduce(reduce)
+        }
+
+        fn flat_map_iter<U: IntoIterator, F: FnMut(Self::Item) -> U>(
+            self,
+            map: F,
+        ) -> FlatMap<Self, U, F> {
+            self.flat_map(map)
+        }
+    }
+
+    impl<I: Iterator> ParallelIterator for I {}
+}
@@ -0,0 +1,288 @@
+//! A lean generation loop for real-time and embedded uses of the runner.
+//! Every buffer is allocated before the first generation, and the loop
+//! itself never touches the heap, so a generation takes a predictable
+//! amount of time and a run can't fail partway through for want of
+//! memory.
+use crate::parallel::*;
+use crate::{
+    breeding::breed,
+    math::constraints::{compare, improves, PenaltyWeight},
+    math::selection::tournament,
+    models::algen_result::AlgenResult,
+    models::algorithm::Algorithm,
+    models::analyzer::Analyzer,
+    models::node::Node,
+    models::test_parameters::TestParameters,
+    random::Seed,
+    scoring::{reward, score},
+};
+use alloc::vec::Vec;
+
+/// Run a generational algorithm without allocating inside the loop. This
+/// accepts the same types and parameters as `run_algorithm`, but
+/// solutions must be `Copy`, which means they have a fixed size and hold
+/// nothing on the heap.
+///
+/// Only the essentials are supported: nodes are scored with `evaluate`,
+/// the best `elitism_factor` of each generation survive, and the rest are
+/// bred from parents picked by tournament. The returned result has no
+/// history. Nothing here allocates as long as the algorithm's `output`,
+/// `combine_node` and `repair`, and the analyzer, don't either, lineage
+/// isn't tracked, and tournaments are deterministic and drawn with
+/// replacement.
+pub fn run_preallocated<
+    InputData: Send + Sync,
+    OutputData: Clone + Send + Sync,
+    Solution: Copy + Send + Sync,
+    FeatureFlags: Clone + Send + Sync,
+>(
+    params: &TestParameters<FeatureFlags>,
+    input_data: &InputData,
+    algo: &(impl Algorithm<InputData, OutputData, Solution, FeatureFlags> + Sync),
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+
+    on_generation_complete: Option<fn(f32, &Solution, &OutputData) -> bool>,
+) -> AlgenResult<OutputData, Solution> {
+    params.reject_loop_only("run_preallocated", algo.telemetry_sink());
+    let params = &params.for_run();
+    params.install(|| {
+        let seed = Seed::new(params.seed);
+        let _stream = seed.enter();
+        let weight = PenaltyWeight::new(&params.constraint_handling).weight();
+
+        let size = params.population.max(2);
+        let mut population: Vec<Node<Solution>> = (0..size)
+            .map(|_| algo.allocate_node(input_data, params))
+            .collect();
+        let mut next_population = population.clone();
+        let mut order: Vec<usize> = (0..size).collect();
+        let elites = ((params.elitism_factor * size as f32) as usize).min(size);
+
+        let mut best_score = f32::MIN;
+        let mut best_node: Option<Node<Solution>> = None;
+        let mut best_output: Option<OutputData> = None;
+
+        for generation in 0..params.generations {
+            params.state.set_generation(generation);
+            let streams = seed.derive(generation as u64);
+
+            population
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(idx, node)| {
+                    if node.evaluated {
+                        return;
+                    }
+                    let _stream = streams.derive(idx as u64).enter();
+                    let outputs = algo.output(node, input_data, params);
+                    score(algo, analyzer, node, &outputs, params, weight);
+                    reward(algo, node);
+                    node.evaluated = true;
+                });
+
+            // The best come first, without sorting the rest
+            let better_first = |left: &usize, right: &usize| {
+                compare(
+                    &population[*right],
+                    &population[*left],
+                    &params.constraint_handling,
+                )
+            };
+            let front = elites.max(1);
+            order.select_nth_unstable_by(front - 1, better_first);
+            order[..front].sort_unstable_by(better_first);
+
+            let champion = &population[order[0]];
+            if improves(
+                champion,
+                &best_node,
+                best_score,
+                &params.constraint_handling,
+            ) {
+                best_score = champion.score;
+                best_output = Some(algo.output(champion, input_data, params));
+                best_node = Some(champion.clone());
+            }
+
+            let stop = match (on_generation_complete, &best_node, &best_output) {
+                (Some(func), Some(node), Some(output)) => func(best_score, &node.solution, output),
+                _ => false,
+            };
+            if stop || generation + 1 == params.generations {
+                break;
+            }
+
+            for (slot, idx) in next_population.iter_mut().zip(&order[..elites]) {
+                slot.clone_from(&population[*idx]);
+            }
+            next_population[elites..]
+                .par_iter_mut()
+                .enumerate()
+                .for_each(|(idx, slot)| {
+                    let _stream = streams.derive((size + idx) as u64).enter();
+                    let compare = |left: usize, right: usize| {
+                        compare(
+                            &population[left],
+                            &population[right],
+                            &params.constraint_handling,
+                        )
+                    };
+                    let left = tournament(size, params, compare).unwrap_or(0);
+                    let right = tournament(size, params, compare).unwrap_or(0);
+                    *slot = breed(
+                        algo,
+                        population[left].clone(),
+                        population[right].clone(),
+                        params,
+                    );
+                });
+            core::mem::swap(&mut population, &mut next_population);
+        }
+
+        AlgenResult {
+            score: best_score,
+            output: best_output,
+            node: best_node,
+            pareto_front: Vec::new(),
+            archive: Vec::new(),
+            quality_diversity: Vec::new(),
+            hypervolume: Vec::new(),
+            history: Vec::new(),
+            genealogy: Vec::new(),
+        }
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    /// Count the ones in a fixed size word.
+    struct Word {}
+    impl Algorithm<(), u32, u32, ()> for Word {
+        fn output(&self, node: &Node<u32>, _: &(), _: &TestParameters<()>) -> u32 {
+            node.solution
+        }
+
+        fn allocate_node(&self, _: &(), _: &TestParameters<()>) -> Node<u32> {
+            Node::new(crate::random::rng().gen())
+        }
+
+        fn combine_node(
+            &self,
+            left: Node<u32>,
+            right: Node<u32>,
+            params: &TestParameters<()>,
+        ) -> Node<u32> {
+            let mut rng = crate::random::rng();
+            let mask: u32 = rng.gen();
+            let mut solution = (left.solution & mask) | (right.solution & !mask);
+            for bit in 0..32 {
+                if rng.gen_bool(params.current_mutation_factor() as f64) {
+                    solution ^= 1 << bit;
+                }
+            }
+            Node::new(solution)
+        }
+    }
+
+    struct Ones {}
+    impl Analyzer<(), u32, ()> for Ones {
+        fn evaluate(&self, attempt: &u32, _: &TestParameters<()>) -> f32 {
+            attempt.count_ones() as f32
+        }
+    }
+
+    #[test]
+    fn preallocated_runs_work() {
+        let params = TestParameters {
+            generations: 50,
+            population: 100,
+            mutation_factor: 0.02,
+            ..Default::default()
+        };
+        let result = run_preallocated(&params, &(), &Word {}, &Ones {}, None);
+        assert!(result.score >= 30.0);
+        assert_eq!(result.output, result.node.map(|node| node.solution));
+    }
+
+    #[test]
+    fn violations_are_penalized() {
+        struct AtMostSixteen {}
+        impl Analyzer<(), u32, ()> for AtMostSixteen {
+            fn evaluate(&self, attempt: &u32, params: &TestParameters<()>) -> f32 {
+                Ones {}.evaluate(attempt, params)
+            }
+
+            fn violation(&self, attempt: &u32, _: &TestParameters<()>) -> f32 {
+                attempt.count_ones().saturating_sub(16) as f32
+            }
+        }
+
+        let params = TestParameters {
+            generations: 20,
+            population: 100,
+            constraint_handling: crate::models::constraint::ConstraintHandling::DeathPenalty,
+            ..Default::default()
+        };
+        let result = run_preallocated(&params, &(), &Word {}, &AtMostSixteen {}, None);
+        assert_eq!(result.node.unwrap().violation, 0.0);
+        assert!(result.score <= 16.0);
+    }
+
+    #[test]
+    fn champions_are_compared_by_feasibility() {
+        // Hardly any random word is feasible, so the first champion isn't
+        struct AtMostFour {}
+        impl Analyzer<(), u32, ()> for AtMostFour {
+            fn evaluate(&self, attempt: &u32, params: &TestParameters<()>) -> f32 {
+                Ones {}.evaluate(attempt, params)
+            }
+
+            fn violation(&self, attempt: &u32, _: &TestParameters<()>) -> f32 {
+                attempt.count_ones().saturating_sub(4) as f32
+            }
+        }
+
+        let params = TestParameters {
+            generations: 50,
+            population: 100,
+            constraint_handling: crate::models::constraint::ConstraintHandling::FeasibilityFirst,
+            ..Default::default()
+        };
+        let result = run_preallocated(&params, &(), &Word {}, &AtMostFour {}, None);
+        assert_eq!(result.node.unwrap().violation, 0.0);
+        assert!(result.score <= 4.0);
+    }
+
+    #[cfg(feature = "memory")]
+    #[test]
+    fn the_loop_does_not_allocate() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        // The calling thread's allocation count when each generation ends
+        static COUNTS: [AtomicUsize; 20] = [const { AtomicUsize::new(0) }; 20];
+        fn record(_: f32, _: &u32, _: &u32) -> bool {
+            let generation = COUNTS
+                .iter()
+                .position(|count| count.load(Ordering::Relaxed) == 0);
+            let allocations = crate::memory::thread_allocations() + 1;
+            COUNTS[generation.unwrap()].store(allocations, Ordering::Relaxed);
+            false
+        }
+
+        let params = TestParameters {
+            generations: 20,
+            population: 100,
+            parallelism: crate::models::parallelism::Parallelism::Sequential,
+            ..Default::default()
+        };
+        run_preallocated(&params, &(), &Word {}, &Ones {}, Some(record));
+        let counts: Vec<usize> = COUNTS
+            .iter()
+            .map(|count| count.load(Ordering::Relaxed))
+            .collect();
+        assert!(counts.iter().all(|count| *count == counts[0]));
+    }
+}
@@ -0,0 +1,155 @@
+//! The random numbers behind a run. Everything in the crate draws from
+//! `rng()`, and algorithms which want their runs to be reproducible
+//! should too, in place of `rand::thread_rng()`.
+//!
+//! Without a seed, `rng()` is simply the thread's generator. With one,
+//! the runner hands every piece of parallel work its own stream, seeded
+//! from the run's seed and the position of that work in the run, such
+//! as the generation and the index of the node. No stream depends on
+//! which thread ran the work or in what order, so a seeded run makes
+//! the same draws however rayon schedules it.
+//!
+//! Without the `std` feature there are no threads and no thread
+//! generator, so the whole run shares one stream. An unseeded run starts
+//! that stream from a fixed seed and repeats itself, so seed runs from a
+//! hardware source of entropy when they should differ.
+use rand::{rngs::StdRng, Error, RngCore, SeedableRng};
+
+// Tests run on many threads even without std, so they keep to thread
+// locals too
+#[cfg(any(feature = "std", test))]
+thread_local! {
+    static STREAM: std::cell::RefCell<Option<StdRng>> = const { std::cell::RefCell::new(None) };
+}
+
+#[cfg(not(any(feature = "std", test)))]
+static STREAM: crate::sync::Mutex<Option<StdRng>> = crate::sync::Mutex::new(None);
+
+#[cfg(any(feature = "std", test))]
+fn with_stream<R>(op: impl FnOnce(&mut Option<StdRng>) -> R) -> R {
+    STREAM.with(|stream| op(&mut stream.borrow_mut()))
+}
+
+#[cfg(not(any(feature = "std", test)))]
+fn with_stream<R>(op: impl FnOnce(&mut Option<StdRng>) -> R) -> R {
+    op(&mut STREAM.lock().unwrap())
+}
+
+/// The generator for the work running on this thread.
+pub fn rng() -> Stream {
+    Stream {}
+}
+
+/// A handle to the stream of the work running on the calling thread, or
+/// to the thread's generator when the run isn't seeded. Like
+/// `ThreadRng`, it is cheap to create and should not be sent between
+/// threads.
+#[derive(Clone, Copy, Debug)]
+pub struct Stream {}
+
+impl Stream {
+    fn draw<R>(&self, op: impl FnOnce(&mut dyn RngCore) -> R) -> R {
+        with_stream(|stream| match stream {
+            Some(rng) => op(rng),
+            #[cfg(feature = "std")]
+            None => op(&mut rand::thread_rng()),
+            #[cfg(not(feature = "std"))]
+            None => op(stream.insert(StdRng::seed_from_u64(0))),
+        })
+    }
+}
+
+impl RngCore for Stream {
+    fn next_u32(&mut self) -> u32 {
+        self.draw(|rng| rng.next_u32())
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.draw(|rng| rng.next_u64())
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.draw(|rng| rng.fill_bytes(dest))
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.draw(|rng| rng.try_fill_bytes(dest))
+    }
+}
+
+/// The seed of one piece of a run, from which the seeds of its parts are
+/// derived. An unseeded run has no seed, and leaves every thread on its
+/// own generator.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct Seed(Option<u64>);
+
+impl Seed {
+    pub fn new(seed: Option<u64>) -> Self {
+        Seed(seed)
+    }
+
+    /// The seed of the part of this piece identified by `key`.
+    pub fn derive(self, key: u64) -> Self {
+        Seed(self.0.map(|seed| split_mix(seed ^ split_mix(key))))
+    }
+
+    /// Make `rng()` draw from this seed's stream until the returned guard
+    /// is dropped, when the thread gets back whatever stream it had
+    /// before. Rayon may run other work on this thread while this work
+    /// waits, which is why the stream is restored rather than cleared.
+    pub fn enter(self) -> Entered {
+        Entered {
+            previous: self
+                .0
+                .map(|seed| with_stream(|stream| stream.replace(StdRng::seed_from_u64(seed)))),
+        }
+    }
+}
+
+/// Puts back the stream a thread had before a seed was entered.
+#[must_use]
+pub(crate) struct Entered {
+    previous: Option<Option<StdRng>>,
+}
+
+impl Drop for Entered {
+    fn drop(&mut self) {
+        if let Some(previous) = self.previous.take() {
+            with_stream(|stream| *stream = previous);
+        }
+    }
+}
+
+/// One step of SplitMix64, which scatters nearby inputs, such as
+/// consecutive indices, into unrelated seeds.
+fn split_mix(state: u64) -> u64 {
+    let mut z = state.wrapping_add(0x9e37_79b9_7f4a_7c15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::Rng;
+
+    #[test]
+    fn streams_depend_only_on_their_seed() {
+        let seed = Seed::new(Some(7));
+        let draw = |key| {
+            let _stream = seed.derive(key).enter();
+            rng().gen::<u64>()
+        };
+        assert_eq!(draw(1), draw(1));
+        assert_ne!(draw(1), draw(2));
+
+        // A nested stream leaves the outer one where it was
+        let _stream = seed.enter();
+        let first = rng().gen::<u64>();
+        draw(3);
+        let second = rng().gen::<u64>();
+        let mut expected = StdRng::seed_from_u64(7);
+        assert_eq!((first, second), (expected.gen(), expected.gen()));
+    }
+}
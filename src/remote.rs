@@ -0,0 +1,253 @@
+//! Fitness evaluation on remote worker processes. Expensive analyzers,
+//! such as simulations or game playouts, can run on a fleet of machines:
+//! each worker calls `serve_evaluations` with the real analyzer, and the
+//! runner scores nodes with a `RemoteAnalyzer` which ships every output
+//! to one of the workers and waits for its score.
+//!
+//! Requests and responses are `Wire` frames on a plain TCP connection
+//! rather than gRPC or HTTP, for the reasons given in `models::wire`. A
+//! request is a kind byte followed by the output, and the response is
+//! the score, the constraint violation, or the case scores, depending on
+//! the kind.
+use crate::models::{
+    analyzer::Analyzer,
+    test_parameters::TestParameters,
+    wire::{read_frame, write_frame, Wire},
+};
+use std::{
+    io,
+    marker::PhantomData,
+    net::{SocketAddr, TcpListener, TcpStream},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+const SCORE: u8 = 0;
+const VIOLATION: u8 = 1;
+const CASES: u8 = 2;
+
+/// An analyzer which evaluates outputs on remote workers.
+///
+/// Requests are spread over the workers in turn. A request which fails,
+/// or which a straggling worker hasn't answered within `timeout`, is
+/// retried on the next worker, up to `retries` times. If every attempt
+/// fails the output scores `failure_score`, so one bad solution can't
+/// stop the run.
+pub struct RemoteAnalyzer<OutputData> {
+    pub workers: Vec<SocketAddr>,
+    /// How long to wait for a worker to connect and to answer.
+    pub timeout: Duration,
+    /// How many other workers to try after the first one fails.
+    pub retries: usize,
+    /// The score of an output which no worker could evaluate.
+    pub failure_score: f32,
+    next: AtomicUsize,
+    idle: Mutex<Vec<(SocketAddr, TcpStream)>>,
+    output: PhantomData<fn(&OutputData)>,
+}
+
+impl<OutputData> RemoteAnalyzer<OutputData> {
+    /// Evaluate on the given workers, waiting up to 30 seconds for each
+    /// answer and retrying twice.
+    pub fn new(workers: Vec<SocketAddr>) -> Self {
+        RemoteAnalyzer {
+            workers,
+            timeout: Duration::from_secs(30),
+            retries: 2,
+            failure_score: f32::MIN,
+            next: AtomicUsize::new(0),
+            idle: Mutex::new(Vec::new()),
+            output: PhantomData,
+        }
+    }
+}
+
+impl<OutputData: Wire> RemoteAnalyzer<OutputData> {
+    /// Send a request to the workers in turn until one answers.
+    fn request(&self, kind: u8, attempt: &OutputData) -> Option<Vec<u8>> {
+        if self.workers.is_empty() {
+            return None;
+        }
+
+        let mut payload = vec![kind];
+        attempt.encode(&mut payload);
+        for _ in 0..=self.retries {
+            let worker =
+                self.workers[self.next.fetch_add(1, Ordering::Relaxed) % self.workers.len()];
+            let Ok(mut stream) = self.connect(worker) else {
+                continue;
+            };
+            // A connection which fails or times out is dropped, so a
+            // late answer can never be read as the reply to a later
+            // request.
+            if let Ok(response) =
+                write_frame(&mut stream, &payload).and_then(|_| read_frame(&mut stream))
+            {
+                self.idle.lock().unwrap().push((worker, stream));
+                return Some(response);
+            }
+        }
+        None
+    }
+
+    /// Reuse an idle connection to the worker, or open a new one.
+    fn connect(&self, worker: SocketAddr) -> io::Result<TcpStream> {
+        let idle = {
+            let mut idle = self.idle.lock().unwrap();
+            idle.iter()
+                .position(|(address, _)| *address == worker)
+                .map(|idx| idle.swap_remove(idx).1)
+        };
+        match idle {
+            Some(stream) => Ok(stream),
+            None => {
+                let stream = TcpStream::connect_timeout(&worker, self.timeout)?;
+                stream.set_read_timeout(Some(self.timeout))?;
+                stream.set_write_timeout(Some(self.timeout))?;
+                Ok(stream)
+            }
+        }
+    }
+}
+
+impl<InputData, OutputData: Wire, FeatureFlags> Analyzer<InputData, OutputData, FeatureFlags>
+    for RemoteAnalyzer<OutputData>
+{
+    fn evaluate(&self, attempt: &OutputData, _params: &TestParameters<FeatureFlags>) -> f32 {
+        self.request(SCORE, attempt)
+            .and_then(|response| f32::decode(&mut response.as_slice()).ok())
+            .unwrap_or(self.failure_score)
+    }
+
+    fn evaluate_cases(
+        &self,
+        attempt: &OutputData,
+        _params: &TestParameters<FeatureFlags>,
+    ) -> Option<Vec<f32>> {
+        let response = self.request(CASES, attempt)?;
+        let mut input = response.as_slice();
+        match bool::decode(&mut input).ok()? {
+            true => Vec::decode(&mut input).ok(),
+            false => None,
+        }
+    }
+
+    fn violation(&self, attempt: &OutputData, _params: &TestParameters<FeatureFlags>) -> f32 {
+        self.request(VIOLATION, attempt)
+            .and_then(|response| f32::decode(&mut response.as_slice()).ok())
+            .unwrap_or(0.0)
+    }
+}
+
+/// Serve evaluations with `analyzer` to every runner which connects to
+/// `listener`, each connection on its own thread. This only returns if
+/// the listener fails.
+pub fn serve_evaluations<InputData, OutputData: Wire, FeatureFlags: Sync>(
+    listener: TcpListener,
+    analyzer: &(impl Analyzer<InputData, OutputData, FeatureFlags> + Sync),
+    params: &TestParameters<FeatureFlags>,
+) -> io::Result<()> {
+    thread::scope(|scope| loop {
+        let (stream, _) = listener.accept()?;
+        scope.spawn(move || answer(stream, analyzer, params));
+    })
+}
+
+/// Answer requests on a single connection until the runner hangs up or
+/// sends something malformed.
+fn answer<InputData, OutputData: Wire, FeatureFlags>(
+    mut stream: TcpStream,
+    analyzer: &impl Analyzer<InputData, OutputData, FeatureFlags>,
+    params: &TestParameters<FeatureFlags>,
+) {
+    loop {
+        let Ok(request) = read_frame(&mut stream) else {
+            return;
+        };
+        let Some((kind, mut input)) = request.split_first() else {
+            return;
+        };
+        let Ok(attempt) = OutputData::decode(&mut input) else {
+            return;
+        };
+
+        let mut response = Vec::new();
+        match *kind {
+            SCORE => analyzer.evaluate(&attempt, params).encode(&mut response),
+            VIOLATION => analyzer.violation(&attempt, params).encode(&mut response),
+            CASES => match analyzer.evaluate_cases(&attempt, params) {
+                Some(cases) => (true, cases).encode(&mut response),
+                None => false.encode(&mut response),
+            },
+            _ => return,
+        }
+        if write_frame(&mut stream, &response).is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct Sum {}
+    impl Analyzer<(), Vec<f32>, ()> for Sum {
+        fn evaluate(&self, attempt: &Vec<f32>, _params: &TestParameters<()>) -> f32 {
+            attempt.iter().sum()
+        }
+
+        fn evaluate_cases(&self, attempt: &Vec<f32>, _: &TestParameters<()>) -> Option<Vec<f32>> {
+            Some(attempt.clone())
+        }
+    }
+
+    #[test]
+    fn workers_evaluate_remotely() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let worker = listener.local_addr().unwrap();
+        thread::spawn(move || serve_evaluations(listener, &Sum {}, &TestParameters::default()));
+
+        // The first worker isn't listening, so every request which starts
+        // there is retried on the second.
+        let unreachable = TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap();
+        let analyzer = RemoteAnalyzer {
+            timeout: Duration::from_secs(5),
+            ..RemoteAnalyzer::new(vec![unreachable, worker])
+        };
+        let params = TestParameters::<()>::default();
+        let attempt = vec![1.0, 2.0, 3.5];
+
+        for _ in 0..3 {
+            assert_eq!(
+                Analyzer::<(), _, _>::evaluate(&analyzer, &attempt, &params),
+                6.5
+            );
+        }
+        assert_eq!(
+            Analyzer::<(), _, _>::evaluate_cases(&analyzer, &attempt, &params),
+            Some(attempt.clone())
+        );
+        assert_eq!(
+            Analyzer::<(), _, _>::violation(&analyzer, &attempt, &params),
+            0.0
+        );
+
+        let stranded = RemoteAnalyzer {
+            retries: 0,
+            failure_score: -1.0,
+            ..RemoteAnalyzer::new(vec![unreachable])
+        };
+        assert_eq!(
+            Analyzer::<(), _, _>::evaluate(&stranded, &attempt, &params),
+            -1.0
+        );
+    }
+}
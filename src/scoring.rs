@@ -0,0 +1,108 @@
+use crate::math::constraints::penalize;
+use crate::models::{
+    algorithm::Algorithm, analyzer::Analyzer, node::Node, test_parameters::TestParameters,
+};
+
+/// Score a node from its outputs. Every runner scores through here so
+/// that the steps which follow the analyzer, such as the constraints and
+/// their penalty, are applied consistently. The penalty is applied with
+/// `weight`, which only `run_algorithm` adapts across generations.
+pub fn score<InputData, OutputData, Solution, FeatureFlags>(
+    algo: &impl Algorithm<InputData, OutputData, Solution, FeatureFlags>,
+    analyzer: &impl Analyzer<InputData, OutputData, FeatureFlags>,
+    node: &mut Node<Solution>,
+    outputs: &OutputData,
+    params: &TestParameters<FeatureFlags>,
+    weight: f32,
+) where
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+{
+    node.score = analyzer.evaluate(outputs, params);
+    node.case_scores = analyzer.evaluate_cases(outputs, params).unwrap_or_default();
+    node.violation =
+        analyzer.violation(outputs, params) + constraint_violation(algo, &node.solution, outputs);
+    penalize(node, &params.constraint_handling, weight);
+}
+
+/// Credit the operator which created a node with its improvement on its
+/// parents. Every runner whose nodes earn a single score calls this once
+/// that score is final, which for most is straight after `score`, and
+/// the operator is only credited the first time. Multi-objective runners
+/// have no single score to measure an improvement by, and cooperative
+/// components are bred without a bandit, so neither calls this.
+///
+/// Both scores are raw, before any scaling or novelty takes their place.
+/// A parent without a usable score, such as one killed by the death
+/// penalty, leaves nothing to improve on, so its offspring's operator is
+/// not credited at all. A child without one has not improved.
+pub fn reward<InputData, OutputData, Solution, FeatureFlags>(
+    algo: &impl Algorithm<InputData, OutputData, Solution, FeatureFlags>,
+    node: &mut Node<Solution>,
+) where
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+{
+    if let (Some(bandit), Some(operator)) = (algo.operator_selection(), node.operator.take()) {
+        let improvement = (node.score - node.parent_score).max(0.0);
+        if node.parent_score > f32::MIN && improvement.is_finite() {
+            bandit.reward(operator, improvement);
+        }
+    }
+}
+
+/// The total violation of the algorithm's constraints, which is added to
+/// whatever violation the analyzer reports.
+pub fn constraint_violation<InputData, OutputData, Solution, FeatureFlags>(
+    algo: &impl Algorithm<InputData, OutputData, Solution, FeatureFlags>,
+    solution: &Solution,
+    outputs: &OutputData,
+) -> f32
+where
+    InputData: Send + Sync,
+    OutputData: Send + Sync,
+    Solution: Clone + Send + Sync,
+{
+    algo.constraints()
+        .iter()
+        .map(|constraint| constraint.violation(solution, outputs))
+        .sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fixtures::*;
+    use crate::models::operator_selection::{OperatorBandit, OperatorSelection};
+
+    fn rewarded(parent_score: f32, score: f32) -> Vec<usize> {
+        let algo = Configured {
+            bandit: Some(OperatorBandit::new(2, OperatorSelection::default())),
+            ..Default::default()
+        };
+        let mut node = Node {
+            score,
+            parent_score,
+            operator: Some(1),
+            ..Node::new(Vec::new())
+        };
+        reward(&algo, &mut node);
+        algo.bandit.unwrap().rewards()
+    }
+
+    #[test]
+    fn improvements_are_credited() {
+        assert_eq!(rewarded(3.0, 5.0), vec![0, 1]);
+        assert_eq!(rewarded(5.0, 3.0), vec![0, 1]);
+        assert_eq!(rewarded(3.0, f32::MIN), vec![0, 1]);
+    }
+
+    #[test]
+    fn penalized_parents_earn_no_reward() {
+        assert_eq!(rewarded(f32::MIN, 5.0), vec![0, 0]);
+        assert_eq!(rewarded(f32::NEG_INFINITY, 5.0), vec![0, 0]);
+        assert_eq!(rewarded(f32::NAN, 5.0), vec![0, 0]);
+    }
+}
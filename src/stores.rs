@@ -0,0 +1,4 @@
+pub mod file;
+pub mod memory;
+#[cfg(feature = "network")]
+pub mod redis;
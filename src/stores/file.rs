@@ -0,0 +1,69 @@
+use crate::models::{node::Node, population_store::PopulationStore, wire::Wire};
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// A store which writes the population to a single file. The file is
+/// replaced atomically, by writing a sibling file and renaming it over
+/// the original, so a crash mid-save never leaves a torn population.
+#[derive(Clone, Debug)]
+pub struct FileStore {
+    pub path: PathBuf,
+}
+
+impl FileStore {
+    pub fn new(path: impl AsRef<Path>) -> Self {
+        FileStore {
+            path: path.as_ref().to_path_buf(),
+        }
+    }
+}
+
+impl<Solution: Wire> PopulationStore<Solution> for FileStore {
+    fn save(&self, generation: usize, nodes: &[Node<Solution>]) -> io::Result<()> {
+        let mut bytes = Vec::new();
+        generation.encode(&mut bytes);
+        nodes.len().encode(&mut bytes);
+        for node in nodes {
+            node.encode(&mut bytes);
+        }
+
+        let mut partial = self.path.clone().into_os_string();
+        partial.push(".partial");
+        fs::write(&partial, bytes)?;
+        fs::rename(&partial, &self.path)
+    }
+
+    fn load(&self) -> io::Result<(usize, Vec<Node<Solution>>)> {
+        match fs::read(&self.path) {
+            Ok(bytes) => <(usize, Vec<Node<Solution>>)>::decode(&mut bytes.as_slice()),
+            Err(error) if error.kind() == io::ErrorKind::NotFound => Ok((0, Vec::new())),
+            Err(error) => Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn populations_survive_a_round_trip() {
+        let path = std::env::temp_dir().join(format!("algen-store-{}", std::process::id()));
+        let store = FileStore::new(&path);
+        assert_eq!(PopulationStore::<Vec<u8>>::load(&store).unwrap().0, 0);
+
+        let mut node = Node::new(vec![1u8, 2, 3]);
+        node.score = 4.5;
+        node.age = 2;
+        store.save(7, &[node, Node::new(vec![])]).unwrap();
+
+        let (generation, nodes): (usize, Vec<Node<Vec<u8>>>) = store.load().unwrap();
+        assert_eq!(generation, 7);
+        assert_eq!(nodes.len(), 2);
+        assert_eq!(nodes[0].solution, vec![1, 2, 3]);
+        assert_eq!((nodes[0].score, nodes[0].age), (4.5, 2));
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,33 @@
+use crate::models::{node::Node, population_store::PopulationStore};
+use std::{io, sync::Mutex};
+
+/// A store which keeps the population in memory. This is mostly useful
+/// for inspecting a run's population from another thread.
+pub struct MemoryStore<Solution> {
+    saved: Mutex<(usize, Vec<Node<Solution>>)>,
+}
+
+impl<Solution> MemoryStore<Solution> {
+    pub fn new() -> Self {
+        MemoryStore {
+            saved: Mutex::new((0, Vec::new())),
+        }
+    }
+}
+
+impl<Solution> Default for MemoryStore<Solution> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<Solution: Clone> PopulationStore<Solution> for MemoryStore<Solution> {
+    fn save(&self, generation: usize, nodes: &[Node<Solution>]) -> io::Result<()> {
+        *self.saved.lock().unwrap() = (generation, nodes.to_vec());
+        Ok(())
+    }
+
+    fn load(&self) -> io::Result<(usize, Vec<Node<Solution>>)> {
+        Ok(self.saved.lock().unwrap().clone())
+    }
+}
@@ -0,0 +1,230 @@
+use crate::models::{node::Node, population_store::PopulationStore, wire::Wire};
+use std::{
+    io::{self, BufRead, BufReader, Write},
+    net::{SocketAddr, TcpStream},
+    sync::Mutex,
+};
+
+/// A store which keeps the population in a Redis list, one encoded node
+/// per element, so several processes can share or watch a population.
+/// The generation the population goes on from is kept beside it, under
+/// `<key>:generation`. Saves replace both inside a transaction, and
+/// loads read both inside one. This speaks the Redis protocol directly
+/// and needs no client library.
+#[derive(Debug)]
+pub struct RedisStore {
+    pub address: SocketAddr,
+    /// The key of the list which holds the population.
+    pub key: String,
+    connection: Mutex<Option<BufReader<TcpStream>>>,
+}
+
+impl RedisStore {
+    pub fn new(address: SocketAddr, key: impl Into<String>) -> Self {
+        RedisStore {
+            address,
+            key: key.into(),
+            connection: Mutex::new(None),
+        }
+    }
+
+    fn generation_key(&self) -> String {
+        format!("{}:generation", self.key)
+    }
+
+    /// Send each command in turn and return the replies. The connection
+    /// is opened on first use, and dropped after any error so that the
+    /// next call starts afresh.
+    fn commands(&self, commands: &[Vec<&[u8]>]) -> io::Result<Vec<Reply>> {
+        let mut connection = self.connection.lock().unwrap();
+        if connection.is_none() {
+            *connection = Some(BufReader::new(TcpStream::connect(self.address)?));
+        }
+        let stream = connection.as_mut().unwrap();
+
+        let result: io::Result<Vec<Reply>> = (|| {
+            let mut request = Vec::new();
+            for command in commands {
+                request.extend_from_slice(format!("*{}\r\n", command.len()).as_bytes());
+                for argument in command {
+                    request.extend_from_slice(format!("${}\r\n", argument.len()).as_bytes());
+                    request.extend_from_slice(argument);
+                    request.extend_from_slice(b"\r\n");
+                }
+            }
+            stream.get_mut().write_all(&request)?;
+            commands.iter().map(|_| reply(stream)).collect()
+        })();
+
+        if result.is_err() {
+            *connection = None;
+        }
+        result
+    }
+}
+
+impl<Solution: Wire> PopulationStore<Solution> for RedisStore {
+    fn save(&self, generation: usize, nodes: &[Node<Solution>]) -> io::Result<()> {
+        let encoded: Vec<Vec<u8>> = nodes
+            .iter()
+            .map(|node| {
+                let mut bytes = Vec::new();
+                node.encode(&mut bytes);
+                bytes
+            })
+            .collect();
+
+        let key = self.key.as_bytes();
+        let generation_key = self.generation_key();
+        let generation = generation.to_string();
+        let mut push: Vec<&[u8]> = vec![b"RPUSH", key];
+        push.extend(encoded.iter().map(Vec::as_slice));
+        let mut commands = vec![
+            vec![&b"MULTI"[..]],
+            vec![b"DEL", key],
+            vec![b"SET", generation_key.as_bytes(), generation.as_bytes()],
+        ];
+        if !encoded.is_empty() {
+            commands.push(push);
+        }
+        commands.push(vec![b"EXEC"]);
+
+        transaction(self.commands(&commands)?).map(|_| ())
+    }
+
+    fn load(&self) -> io::Result<(usize, Vec<Node<Solution>>)> {
+        let key = self.key.as_bytes();
+        let generation_key = self.generation_key();
+        let commands = [
+            vec![&b"MULTI"[..]],
+            vec![b"GET", generation_key.as_bytes()],
+            vec![b"LRANGE", key, b"0", b"-1"],
+            vec![b"EXEC"],
+        ];
+        let mut results = transaction(self.commands(&commands)?)?.into_iter();
+        let generation = match results.next() {
+            Some(Reply::Bulk(Some(bytes))) => String::from_utf8(bytes)
+                .ok()
+                .and_then(|generation| generation.parse().ok())
+                .ok_or_else(|| invalid("bad generation"))?,
+            Some(Reply::Bulk(None)) => 0,
+            _ => return Err(invalid("expected a bulk string")),
+        };
+        let nodes = match results.next() {
+            Some(Reply::Array(Some(items))) => items
+                .into_iter()
+                .map(|item| match item {
+                    Reply::Bulk(Some(bytes)) => Node::decode(&mut bytes.as_slice()),
+                    _ => Err(invalid("expected a bulk string")),
+                })
+                .collect::<io::Result<_>>()?,
+            _ => return Err(invalid("expected an array")),
+        };
+        Ok((generation, nodes))
+    }
+}
+
+/// A reply in the Redis protocol.
+#[derive(Debug, PartialEq)]
+enum Reply {
+    Status,
+    Error(String),
+    Integer,
+    Bulk(Option<Vec<u8>>),
+    Array(Option<Vec<Reply>>),
+}
+
+fn invalid(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message.to_string())
+}
+
+/// Check the replies to a transaction, which end with the reply to EXEC,
+/// and return the replies to the commands inside it. Commands which fail
+/// inside a transaction don't fail EXEC itself, but leave an error among
+/// the replies it returns, and EXEC returns nil when the transaction was
+/// aborted.
+fn transaction(mut replies: Vec<Reply>) -> io::Result<Vec<Reply>> {
+    let results = match replies.pop() {
+        Some(Reply::Array(Some(results))) => results,
+        Some(Reply::Array(None)) => return Err(io::Error::other("transaction was aborted")),
+        Some(Reply::Error(message)) => return Err(io::Error::other(message)),
+        _ => return Err(invalid("expected an array")),
+    };
+    for reply in replies.iter().chain(&results) {
+        if let Reply::Error(message) = reply {
+            return Err(io::Error::other(message.clone()));
+        }
+    }
+    Ok(results)
+}
+
+/// Read a single reply, including every element of an array.
+fn reply(stream: &mut impl BufRead) -> io::Result<Reply> {
+    let mut line = String::new();
+    stream.read_line(&mut line)?;
+    let line = line
+        .strip_suffix("\r\n")
+        .ok_or_else(|| invalid("truncated reply"))?;
+    let (kind, rest) = line.split_at(line.len().min(1));
+    let length = || rest.parse::<i64>().map_err(|_| invalid("bad length"));
+
+    match kind {
+        "+" => Ok(Reply::Status),
+        "-" => Ok(Reply::Error(rest.to_string())),
+        ":" => length().map(|_| Reply::Integer),
+        "$" => match length()? {
+            length if length < 0 => Ok(Reply::Bulk(None)),
+            length => {
+                let mut bytes = vec![0; length as usize + 2];
+                stream.read_exact(&mut bytes)?;
+                bytes.truncate(length as usize);
+                Ok(Reply::Bulk(Some(bytes)))
+            }
+        },
+        "*" => match length()? {
+            length if length < 0 => Ok(Reply::Array(None)),
+            length => (0..length)
+                .map(|_| reply(stream))
+                .collect::<io::Result<_>>()
+                .map(|items| Reply::Array(Some(items))),
+        },
+        _ => Err(invalid("unknown reply")),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn replies_parse() {
+        let mut input = &b"*3\r\n$3\r\nab\n\r\n:2\r\n$-1\r\n*-1\r\n-ERR nope\r\n+OK\r\n"[..];
+        assert_eq!(
+            reply(&mut input).unwrap(),
+            Reply::Array(Some(vec![
+                Reply::Bulk(Some(b"ab\n".to_vec())),
+                Reply::Integer,
+                Reply::Bulk(None),
+            ]))
+        );
+        assert_eq!(reply(&mut input).unwrap(), Reply::Array(None));
+        assert_eq!(reply(&mut input).unwrap(), Reply::Error("ERR nope".into()));
+        assert_eq!(reply(&mut input).unwrap(), Reply::Status);
+        assert!(reply(&mut input).is_err());
+    }
+
+    #[test]
+    fn failed_transactions_are_errors() {
+        let replies = |exec| vec![Reply::Status, Reply::Status, Reply::Status, exec];
+        let exec = |results| replies(Reply::Array(results));
+
+        assert!(transaction(exec(Some(vec![Reply::Integer, Reply::Integer]))).is_ok());
+        // RPUSH onto a key which holds something other than a list
+        let wrong_type = Reply::Error("WRONGTYPE wrong kind of value".into());
+        let error = transaction(exec(Some(vec![Reply::Integer, wrong_type]))).unwrap_err();
+        assert!(error.to_string().starts_with("WRONGTYPE"));
+        assert!(transaction(exec(None)).is_err());
+        assert!(transaction(replies(Reply::Error("EXECABORT".into()))).is_err());
+        assert!(transaction(Vec::new()).is_err());
+    }
+}
@@ -0,0 +1,73 @@
+//! The locks used by the parts of the crate which build without `std`.
+//! With the `std` feature this is std's `Mutex`, and without it a spin
+//! lock under the same name. Runs without `std` happen on a single
+//! thread, so the lock is only ever contended if an interrupt handler
+//! draws from algen while the code it interrupted is, which deadlocks.
+
+#[cfg(feature = "std")]
+pub use std::sync::Mutex;
+
+#[cfg(not(feature = "std"))]
+pub use spin::Mutex;
+
+#[cfg(not(feature = "std"))]
+mod spin {
+    use core::cell::UnsafeCell;
+    use core::convert::Infallible;
+    use core::ops::{Deref, DerefMut};
+    use core::sync::atomic::{AtomicBool, Ordering};
+
+    pub struct Mutex<T> {
+        locked: AtomicBool,
+        value: UnsafeCell<T>,
+    }
+
+    // The lock hands out one guard at a time, like std's
+    unsafe impl<T: Send> Sync for Mutex<T> {}
+
+    impl<T> Mutex<T> {
+        pub const fn new(value: T) -> Self {
+            Mutex {
+                locked: AtomicBool::new(false),
+                value: UnsafeCell::new(value),
+            }
+        }
+
+        /// Never fails, since nothing can poison the lock, but returns a
+        /// `Result` so that callers read the same as with std's.
+        pub fn lock(&self) -> Result<MutexGuard<'_, T>, Infallible> {
+            while self
+                .locked
+                .compare_exchange_weak(false, true, Ordering::Acquire, Ordering::Relaxed)
+                .is_err()
+            {
+                core::hint::spin_loop();
+            }
+            Ok(MutexGuard { mutex: self })
+        }
+    }
+
+    pub struct MutexGuard<'a, T> {
+        mutex: &'a Mutex<T>,
+    }
+
+    impl<T> Deref for MutexGuard<'_, T> {
+        type Target = T;
+
+        fn deref(&self) -> &T {
+            unsafe { &*self.mutex.value.get() }
+        }
+    }
+
+    impl<T> DerefMut for MutexGuard<'_, T> {
+        fn deref_mut(&mut self) -> &mut T {
+            unsafe { &mut *self.mutex.value.get() }
+        }
+    }
+
+    impl<T> Drop for MutexGuard<'_, T> {
+        fn drop(&mut self) {
+            self.mutex.locked.store(false, Ordering::Release);
+        }
+    }
+}